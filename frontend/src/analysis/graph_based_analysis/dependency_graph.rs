@@ -246,12 +246,16 @@ impl<'a> DependencyAnalyser<'a> {
                 self.add_edges_for_expression(current_node, if_case, location, mapping);
                 self.add_edges_for_expression(current_node, else_case, location, mapping);
             }
-            ExpressionKind::Binary(_, left, right) => {
+            ExpressionKind::Binary(_, left, right) | ExpressionKind::Let(_, left, right) => {
                 self.add_edges_for_expression(current_node, left, location, mapping);
                 self.add_edges_for_expression(current_node, right, location, mapping);
             }
             ExpressionKind::Ident(_) => match &self.naming_table[&expr.id] {
-                Declaration::Type(_) | Declaration::Func(_) | Declaration::Param(_) | Declaration::Const(_) => {}
+                Declaration::Type(_)
+                | Declaration::Func(_)
+                | Declaration::Param(_)
+                | Declaration::Let(_)
+                | Declaration::Const(_) => {}
                 Declaration::In(input) => {
                     let target_stream_id = input.id;
                     let target_stream_entry = mapping[&target_stream_id];
@@ -315,8 +319,9 @@ impl<'a> DependencyAnalyser<'a> {
                 );
             }
             ExpressionKind::Field(expr, ident) => {
-                let num: usize = ident.name.parse::<usize>().expect("checked in AST verifier");
-                if let Some(inner) = expr.get_expr_from_tuple(num) {
+                // named fields cannot be resolved syntactically, the whole tuple is a dependency then
+                let num = ident.name.parse::<usize>().ok();
+                if let Some(inner) = num.and_then(|num| expr.get_expr_from_tuple(num)) {
                     self.add_edges_for_expression(current_node, inner, location, mapping);
                 } else {
                     self.add_edges_for_expression(current_node, expr, location, mapping);
@@ -477,6 +482,9 @@ impl<'a> DependencyAnalyser<'a> {
             let id = trigger.id;
             let current_node = mapping[&id].index;
             self.add_edges_for_expression(current_node, &trigger.expression, Location::Expression, &mut mapping);
+            for expr in trigger.placeholders() {
+                self.add_edges_for_expression(current_node, expr, Location::Expression, mapping);
+            }
         }
     }
 
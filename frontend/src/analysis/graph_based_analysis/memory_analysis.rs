@@ -49,7 +49,7 @@ fn add_sliding_windows(
     use ExpressionKind::*;
     match &expr.kind {
         Lit(_) | Ident(_) => {}
-        Binary(_, left, right) | Default(left, right) => {
+        Binary(_, left, right) | Default(left, right) | Let(_, left, right) => {
             match add_sliding_windows(left, type_table, declaration_table) {
                 MemoryBound::Bounded(u) => required_memory += u,
                 MemoryBound::Unbounded => return MemoryBound::Unbounded,
@@ -110,8 +110,8 @@ fn add_sliding_windows(
             }
         }
         Field(expr, ident) => {
-            let num: usize = ident.name.parse::<usize>().expect("checked in AST verifier");
-            if let Some(inner) = expr.get_expr_from_tuple(num) {
+            let num = ident.name.parse::<usize>().ok();
+            if let Some(inner) = num.and_then(|num| expr.get_expr_from_tuple(num)) {
                 match add_sliding_windows(inner, type_table, declaration_table) {
                     MemoryBound::Bounded(u) => required_memory += u,
                     MemoryBound::Unbounded => return MemoryBound::Unbounded,
@@ -332,15 +332,17 @@ pub(crate) fn determine_worst_case_memory_consumption(
 
             //----------------------
             // windows
-            let windows_space = match add_sliding_windows(&trigger.expression, type_table, declaration_table) {
-                MemoryBound::Bounded(i) => i,
-                MemoryBound::Unknown => {
-                    unknown_size = true;
-                    0
-                }
-                MemoryBound::Unbounded => return MemoryBound::Unbounded,
-            };
-            required_memory += windows_space;
+            for expr in std::iter::once(&trigger.expression).chain(trigger.placeholders()) {
+                let windows_space = match add_sliding_windows(expr, type_table, declaration_table) {
+                    MemoryBound::Bounded(i) => i,
+                    MemoryBound::Unknown => {
+                        unknown_size = true;
+                        0
+                    }
+                    MemoryBound::Unbounded => return MemoryBound::Unbounded,
+                };
+                required_memory += windows_space;
+            }
 
             //----------------------
             // fixed overhead per stream
@@ -181,6 +181,11 @@ fn get_byte_size(value_ty: &ValueTy) -> MemoryBound {
         // an abstract data type, e.g., structs, enums, etc.
         //ValueTy::Adt(AdtDef),
         ValueTy::String | ValueTy::Bytes => MemoryBound::Unbounded,
+        ValueTy::Duration | ValueTy::Timestamp => MemoryBound::Bounded(8),
+        ValueTy::IPv4Addr => MemoryBound::Bounded(4),
+        ValueTy::IPv6Addr => MemoryBound::Bounded(16),
+        ValueTy::IPv4Net => MemoryBound::Bounded(5),
+        ValueTy::IPv6Net => MemoryBound::Bounded(17),
         ValueTy::Tuple(elements) => {
             let mut accu = 0_u128;
             for element in elements.iter().map(get_byte_size) {
@@ -192,8 +197,14 @@ fn get_byte_size(value_ty: &ValueTy) -> MemoryBound {
             }
             MemoryBound::Bounded(accu)
         }
+        // named tuples are laid out like their unnamed counterpart
+        ValueTy::NamedTuple(fields) => {
+            get_byte_size(&ValueTy::Tuple(fields.iter().map(|(_, element)| element.clone()).collect()))
+        }
         // an optional value type, e.g., resulting from accessing a stream with offset -1
         ValueTy::Option(inner) => get_byte_size(inner),
+        // collections have no static bound on their number of elements
+        ValueTy::Set(_) | ValueTy::List(_) | ValueTy::Map(_, _) => MemoryBound::Unbounded,
         // Used during type inference
         ValueTy::Infer(_value_var) => unreachable!(),
         ValueTy::Constr(_type_constraint) => unreachable!(),
@@ -53,7 +53,11 @@ pub(crate) fn assign_ids(spec: &mut LolaAst) {
     for t in &mut spec.trigger {
         assert_eq!(t.id, NodeId::DUMMY, "Ids already assigned.");
         t.id = next_id();
+        assign_ids_extend(&mut t.extend, &mut next_id);
         assign_ids_expr(&mut t.expression, &mut next_id);
+        for expr in t.placeholders_mut() {
+            assign_ids_expr(expr, &mut next_id);
+        }
     }
 }
 
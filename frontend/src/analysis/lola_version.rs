@@ -38,7 +38,7 @@ fn analyse_expression(version_tracker: &mut VersionTracker, expr: &Expression, t
         ExpressionKind::SlidingWindowAggregation { expr, .. } => {
             analyse_expression(version_tracker, expr, false);
         }
-        ExpressionKind::Binary(_, left, right) => {
+        ExpressionKind::Binary(_, left, right) | ExpressionKind::Let(_, left, right) => {
             analyse_expression(version_tracker, left, false);
             analyse_expression(version_tracker, right, false);
         }
@@ -122,7 +122,30 @@ impl<'b> NamingAnalysis<'b> {
             TypeKind::Tuple(elements) => elements.iter().for_each(|ty| {
                 self.check_type(ty);
             }),
-            TypeKind::Optional(ty) => self.check_type(ty),
+            TypeKind::NamedTuple(fields) => {
+                for (idx, (name, ty)) in fields.iter().enumerate() {
+                    if let Some((previous, _)) = fields[..idx].iter().find(|(other, _)| other == name) {
+                        let mut builder = self.handler.build_error_with_span(
+                            &format!("field `{}` is declared more than once", name),
+                            LabeledSpan::new(name.span, &format!("`{}` redeclared here", name), true),
+                        );
+                        builder.add_span_with_label(
+                            previous.span,
+                            &format!("previous declaration of the field `{}` here", name),
+                            false,
+                        );
+                        builder.emit();
+                    }
+                    self.check_type(ty);
+                }
+            }
+            TypeKind::Optional(ty) | TypeKind::Set(ty) | TypeKind::List(ty) | TypeKind::Unit(ty, _) => {
+                self.check_type(ty)
+            }
+            TypeKind::Map(key, value) => {
+                self.check_type(key);
+                self.check_type(value);
+            }
             TypeKind::Inferred => {}
         }
     }
@@ -166,12 +189,22 @@ impl<'b> NamingAnalysis<'b> {
             match import.name.name.as_str() {
                 "math" => stdlib::import_math_module(&mut self.fun_declarations),
                 "regex" => stdlib::import_regex_module(&mut self.fun_declarations),
+                "string" => stdlib::import_string_module(&mut self.fun_declarations),
+                "geo" => stdlib::import_geo_module(&mut self.fun_declarations),
                 n => self.handler.error_with_span(
                     &format!("unresolved import `{}`", n),
                     LabeledSpan::new(import.name.span, &format!("no `{}` in the root", n), true),
                 ),
             }
         }
+        for annotation in &spec.annotations {
+            if annotation.name.name != "robustness" {
+                self.handler.error_with_span(
+                    &format!("unknown annotation `{}`", annotation.name.name),
+                    LabeledSpan::new(annotation.name.span, "expected `robustness`", true),
+                );
+            }
+        }
 
         // Store global declarations, i.e., constants, inputs, and outputs of the given specification
         for constant in &spec.constants {
@@ -200,13 +233,14 @@ impl<'b> NamingAnalysis<'b> {
             self.check_type(&output.ty);
         }
 
+        self.check_inputs(spec);
         self.check_outputs(&spec);
         self.check_triggers(&spec);
 
         self.result.clone()
     }
 
-    /// Checks that if the trigger has a name, it is unique
+    /// Checks that if the trigger has a name, it is unique among triggers and streams, as it names the trigger in the IR
     fn check_triggers(&mut self, spec: &RTLolaAst) {
         let mut trigger_names: Vec<(&String, &Trigger)> = Vec::new();
         for trigger in &spec.trigger {
@@ -249,11 +283,25 @@ impl<'b> NamingAnalysis<'b> {
                 }
             }
             self.declarations.push();
+            if let Some(expr) = trigger.extend.expr.as_ref() {
+                self.check_expression(expr);
+            }
             self.check_expression(&trigger.expression);
+            trigger.placeholders().for_each(|expr| self.check_expression(expr));
             self.declarations.pop();
         }
     }
 
+    fn check_inputs(&mut self, spec: &RTLolaAst) {
+        for input in &spec.inputs {
+            if let Some(assumption) = input.assumption.as_ref() {
+                self.declarations.push();
+                self.check_expression(assumption);
+                self.declarations.pop();
+            }
+        }
+    }
+
     fn check_outputs(&mut self, spec: &RTLolaAst) {
         // recurse into expressions and check them
         for output in &spec.outputs {
@@ -298,6 +346,18 @@ impl<'b> NamingAnalysis<'b> {
         }
     }
 
+    /// Checks that a let binding is not accessed like a stream, e.g., with an offset
+    fn check_stream_target(&mut self, target: &Expression) {
+        if let Some(Declaration::Let(binding)) = self.result.get(&target.id) {
+            let mut builder = self.handler.build_error_with_span(
+                &format!("`{}` is not a stream", binding.name),
+                LabeledSpan::new(target.span, "let bindings cannot be accessed like streams", true),
+            );
+            builder.add_span_with_label(binding.span, &format!("`{}` is bound here", binding.name), false);
+            builder.emit();
+        }
+    }
+
     fn check_function(&mut self, expression: &Expression, name: &FunctionName) {
         let str_repr = name.to_string();
         if let Some(decl) = self.fun_declarations.get_decl_for(str_repr.as_str()) {
@@ -322,12 +382,17 @@ impl<'b> NamingAnalysis<'b> {
             Ident(ident) => {
                 self.check_ident(expression, ident);
             }
-            StreamAccess(expr, _) => self.check_expression(expr),
+            StreamAccess(expr, _) => {
+                self.check_expression(expr);
+                self.check_stream_target(expr);
+            }
             Offset(expr, _) => {
                 self.check_expression(expr);
+                self.check_stream_target(expr);
             }
             SlidingWindowAggregation { expr, duration, .. } => {
                 self.check_expression(expr);
+                self.check_stream_target(expr);
                 self.check_expression(duration);
             }
             Binary(_, left, right) => {
@@ -343,6 +408,15 @@ impl<'b> NamingAnalysis<'b> {
             ParenthesizedExpression(_, expr, _) | Unary(_, expr) | Field(expr, _) => {
                 self.check_expression(expr);
             }
+            Let(pattern, value, body) => {
+                self.check_expression(value);
+                self.declarations.push();
+                for binding in pattern.bindings() {
+                    self.add_decl_for(Declaration::Let(binding.clone()));
+                }
+                self.check_expression(body);
+                self.declarations.pop();
+            }
             Tuple(exprs) => {
                 exprs.iter().for_each(|expr| self.check_expression(expr));
             }
@@ -424,6 +498,8 @@ pub(crate) enum Declaration {
     ParamOut(Rc<Output>),
     Type(Rc<ValueTy>),
     Param(Rc<Parameter>),
+    /// A name bound by a let expression
+    Let(Rc<LetBinding>),
     Func(Rc<FuncDecl>),
 }
 
@@ -435,6 +511,7 @@ impl Declaration {
             Declaration::Out(output) => Some(output.name.span),
             Declaration::ParamOut(output) => Some(output.name.span),
             Declaration::Param(p) => Some(p.name.span),
+            Declaration::Let(binding) => Some(binding.name.span),
             Declaration::Type(_) | Declaration::Func(_) => None,
         }
     }
@@ -446,6 +523,7 @@ impl Declaration {
             Declaration::Out(output) => Some(&output.name.name),
             Declaration::ParamOut(output) => Some(&output.name.name),
             Declaration::Param(p) => Some(&p.name.name),
+            Declaration::Let(binding) => Some(&binding.name.name),
             Declaration::Type(_) | Declaration::Func(_) => None,
         }
     }
@@ -458,6 +536,7 @@ impl Declaration {
             | Declaration::Out(_)
             | Declaration::ParamOut(_)
             | Declaration::Param(_)
+            | Declaration::Let(_)
             | Declaration::Func(_) => false,
         }
     }
@@ -544,9 +623,23 @@ mod tests {
         assert_eq!(1, number_of_naming_errors("import xzy"))
     }
 
+    #[test]
+    fn unknown_stream_in_trigger_message() {
+        assert_eq!(1, number_of_naming_errors("input gps: UInt8\ntrigger gps < 2 \"{alt}\""));
+    }
+
+    #[test]
+    fn unknown_annotation() {
+        assert_eq!(1, number_of_naming_errors("#![robust]\ninput a: Float64"));
+        assert_eq!(0, number_of_naming_errors("#![robustness]\ninput a: Float64"))
+    }
+
     #[test]
     fn known_import() {
-        assert_eq!(0, number_of_naming_errors("import math"))
+        assert_eq!(0, number_of_naming_errors("import math"));
+        assert_eq!(0, number_of_naming_errors("import geo\noutput d := distance(0.0, 0.0, 1.0, 1.0)"));
+        assert_eq!(0, number_of_naming_errors("import string\ninput s: String\noutput n := len(s)"));
+        assert_eq!(1, number_of_naming_errors("input s: String\noutput n := concat(s, s)"));
     }
 
     #[test]
@@ -577,9 +670,37 @@ mod tests {
         assert_eq!(0, number_of_naming_errors(spec));
     }
 
+    #[test]
+    fn let_bindings_are_only_visible_in_the_body() {
+        assert_eq!(0, number_of_naming_errors("input a: Int8\noutput b := let x = a in x + 1"));
+        assert_eq!(1, number_of_naming_errors("input a: Int8\noutput b := (let x = a in x) + x"));
+    }
+
+    #[test]
+    fn duplicate_names_in_let_pattern_are_reported() {
+        assert_eq!(1, number_of_naming_errors("input a: (Int8, Int8)\noutput b := let (x, x) = a in x"));
+    }
+
+    #[test]
+    fn let_bindings_are_not_streams() {
+        assert_eq!(1, number_of_naming_errors("input a: Int8\noutput b := let x = a in x.offset(by: -1)"));
+    }
+
+    #[test]
+    fn duplicate_fields_in_named_tuples_are_reported() {
+        assert_eq!(1, number_of_naming_errors("input a: (x: Int8, x: Int8)"));
+    }
+
     #[test]
     fn test_param_spec_wrong_parameters() {
         let spec = "input in(a: Int8, b: Int8): Int8\noutput x := in(1)";
         assert_eq!(1, number_of_naming_errors(spec));
     }
+
+    #[test]
+    fn trigger_names_clashing_with_streams_are_reported() {
+        assert_eq!(1, number_of_naming_errors("input a: Int8\noutput alarm := a > 3\ntrigger alarm := a > 5"));
+        assert_eq!(1, number_of_naming_errors("input alarm: Int8\ntrigger alarm := alarm > 5"));
+        assert_eq!(1, number_of_naming_errors("input a: Int8\ntrigger alarm := a > 3\ntrigger alarm := a > 5"));
+    }
 }
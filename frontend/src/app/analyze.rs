@@ -38,6 +38,7 @@ enum Analysis {
     Prettyprint,
     Analyze,
     IR,
+    Stl(u32),
 }
 
 pub(crate) struct Config {
@@ -66,6 +67,17 @@ impl Config {
             .subcommand(
                 SubCommand::with_name("ir").about("Parses the input file and returns the intermediate representation"),
             )
+            .subcommand(
+                SubCommand::with_name("stl")
+                    .about("Translates the bounded STL requirements in the input file into a RTLola specification")
+                    .arg(
+                        Arg::with_name("FREQUENCY")
+                            .long("frequency")
+                            .takes_value(true)
+                            .default_value("10")
+                            .help("Sets the sampling frequency in Hz, which has to divide 1000Hz"),
+                    ),
+            )
             .get_matches_from(args);
 
         let verbosity = match matches.occurrences_of("v") {
@@ -95,6 +107,13 @@ impl Config {
             ("pretty-print", Some(_)) => Config { which: Analysis::Prettyprint, filename },
             ("analyze", Some(_)) => Config { which: Analysis::Analyze, filename },
             ("ir", Some(_)) | ("intermediate-representation", Some(_)) => Config { which: Analysis::IR, filename },
+            ("stl", Some(stl_matches)) => {
+                let frequency = stl_matches.value_of("FREQUENCY").unwrap().parse().unwrap_or_else(|_| {
+                    eprintln!("the sampling frequency has to be a positive integer");
+                    std::process::exit(1)
+                });
+                Config { which: Analysis::Stl(frequency), filename }
+            }
             ("", None) => {
                 // default to `analyze`
                 Config { which: Analysis::Analyze, filename }
@@ -171,6 +190,14 @@ impl Config {
                     Ok(()) // TODO throw a good `Error`
                 }
             }
+            Analysis::Stl(frequency) => {
+                let spec = crate::import_stl(&self.filename, &contents, *frequency).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1)
+                });
+                println!("{}", spec);
+                Ok(())
+            }
         }
     }
 }
@@ -4,6 +4,8 @@ Every node in the abstract syntax tree is assigned a unique id and stores the ma
 */
 
 pub(crate) mod conversion;
+pub(crate) mod expansion;
+pub(crate) mod past_time;
 pub(crate) mod print;
 pub(crate) mod verify;
 
@@ -19,6 +21,8 @@ use std::rc::Rc;
 pub struct RTLolaAst {
     /// The imports of additional modules
     pub imports: Vec<Import>,
+    /// The annotations of the specification
+    pub annotations: Vec<Annotation>,
     /// The constant stream declarations
     pub constants: Vec<Rc<Constant>>,
     /// The input stream declarations
@@ -35,6 +39,7 @@ impl RTLolaAst {
     pub(crate) fn new() -> RTLolaAst {
         RTLolaAst {
             imports: Vec::new(),
+            annotations: Vec::new(),
             constants: Vec::new(),
             inputs: Vec::new(),
             outputs: Vec::new(),
@@ -58,6 +63,20 @@ pub struct Import {
     pub span: Span,
 }
 
+/**
+An AST node representing an annotation of the specification, such as `#![robustness]`.
+The 'robustness' annotation, for example, lets triggers report how far their condition is from being violated.
+*/
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    /// The name of the annotation
+    pub name: Ident,
+    /// The ID of the node in the AST
+    pub id: NodeId,
+    /// The span in the specification declaring the annotation
+    pub span: Span,
+}
+
 /**
 An AST node representing the declaration of a constant.
 */
@@ -86,6 +105,8 @@ pub struct Input {
     pub ty: Type,
     /// The parameters of a parameterized input stream; The vector is empty in non-parametrized streams.
     pub params: Vec<Rc<Parameter>>,
+    /// The assumption every value of the input stream has to satisfy
+    pub assumption: Option<Expression>,
     /// The ID of the node in the AST
     pub id: NodeId,
     /// The span in the specification declaring the input stream
@@ -115,6 +136,8 @@ pub struct Output {
     pub id: NodeId,
     /// The span in the specification declaring the output stream
     pub span: Span,
+    /// Flag to mark streams derived by the frontend, e.g., for past-time operators, which are not reported to the user
+    pub hidden: bool,
 }
 
 /**
@@ -212,16 +235,77 @@ An AST node representing the declaration of a trigger
 pub struct Trigger {
     /// The optional name of a trigger
     pub name: Option<Ident>,
+    /// The optional severity of a named trigger, e.g., `trigger gps_lost: critical := ...`
+    pub severity: Option<TriggerSeverity>,
+    /// The activation condition of a trigger, which is inferred if omitted
+    pub extend: ActivationCondition,
     /// The boolean expression of a trigger
     pub expression: Expression,
     /// The optional trigger message, which is printed if the monitor raises the trigger
     pub message: Option<String>,
+    /// The parts of the trigger message, which may interpolate stream values, e.g., `"only {gps} satellites"`
+    pub message_parts: Vec<MessagePart>,
+    /// The options suppressing repeated reports of the trigger, e.g., `with rising, limit: 3 per 1min`
+    pub options: Vec<TriggerOption>,
     /// The ID of the node in the AST
     pub id: NodeId,
     /// The span in the specification declaring the extend declaration
     pub span: Span,
 }
 
+impl Trigger {
+    /// Returns the expressions whose values are interpolated into the trigger message
+    pub fn placeholders(&self) -> impl Iterator<Item = &Expression> {
+        self.message_parts.iter().filter_map(|part| match part {
+            MessagePart::Text(_) => None,
+            MessagePart::Placeholder(expr) => Some(expr),
+        })
+    }
+
+    pub(crate) fn placeholders_mut(&mut self) -> impl Iterator<Item = &mut Expression> {
+        self.message_parts.iter_mut().filter_map(|part| match part {
+            MessagePart::Text(_) => None,
+            MessagePart::Placeholder(expr) => Some(expr),
+        })
+    }
+}
+
+/// The severity of a trigger, ordered from the least to the most severe
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum TriggerSeverity {
+    /// The trigger reports an informative observation
+    Info,
+    /// The trigger reports a violation, which is the default for triggers without an explicit severity
+    #[default]
+    Warning,
+    /// The trigger reports a violation that requires immediate attention
+    Critical,
+}
+
+/**
+An option of a trigger, which suppresses repeated reports.
+*/
+#[derive(Debug, Clone)]
+pub enum TriggerOption {
+    /// Reports only when the condition becomes true, i.e., `rising`
+    RisingEdge(Span),
+    /// Re-arms the trigger once its condition has been false for the duration, e.g., `rearm: 10s`
+    Rearm(Literal),
+    /// Limits the number of reports per interval, e.g., `limit: 3 per 1min`
+    Limit(Literal, Literal),
+}
+
+/**
+A part of a trigger message.
+*/
+#[derive(Debug, Clone)]
+pub enum MessagePart {
+    /// Literal text, in which the escaped braces `{{` and `}}` are already replaced
+    Text(String),
+    /// An expression whose value is interpolated into the message, e.g., `{alt.hold()}`
+    Placeholder(Expression),
+}
+
 /**
 An AST node representing the declaration of a user-defined type.
 */
@@ -293,10 +377,30 @@ impl Type {
         Type { id, kind: TypeKind::Tuple(tuple), span }
     }
 
+    pub(crate) fn new_named_tuple(id: NodeId, fields: Vec<(Ident, Type)>, span: Span) -> Type {
+        Type { id, kind: TypeKind::NamedTuple(fields), span }
+    }
+
     pub(crate) fn new_optional(id: NodeId, name: Type, span: Span) -> Type {
         Type { id, kind: TypeKind::Optional(name.into()), span }
     }
 
+    pub(crate) fn new_set(id: NodeId, element: Type, span: Span) -> Type {
+        Type { id, kind: TypeKind::Set(element.into()), span }
+    }
+
+    pub(crate) fn new_list(id: NodeId, element: Type, span: Span) -> Type {
+        Type { id, kind: TypeKind::List(element.into()), span }
+    }
+
+    pub(crate) fn new_map(id: NodeId, key: Type, value: Type, span: Span) -> Type {
+        Type { id, kind: TypeKind::Map(key.into(), value.into()), span }
+    }
+
+    pub(crate) fn new_unit(id: NodeId, ty: Type, unit: Vec<(Ident, i32)>, span: Span) -> Type {
+        Type { id, kind: TypeKind::Unit(ty.into(), unit), span }
+    }
+
     pub(crate) fn new_inferred(id: NodeId) -> Type {
         Type { id, kind: TypeKind::Inferred, span: Span::unknown() }
     }
@@ -309,8 +413,18 @@ pub enum TypeKind {
     Simple(String),
     /// A tuple type, e.g., `(Int32, Float32)`
     Tuple(Vec<Type>),
+    /// A tuple type with named fields, e.g., `(lat: Float64, lon: Float64)`
+    NamedTuple(Vec<(Ident, Type)>),
     /// An optional type, e.g., `Int?`
     Optional(Box<Type>),
+    /// A set type, e.g., `Set<UInt16>`
+    Set(Box<Type>),
+    /// A list type, e.g., `List<String>`
+    List(Box<Type>),
+    /// A map type, e.g., `Map<UInt32, UInt16>`
+    Map(Box<Type>, Box<Type>),
+    /// A type with a physical unit, e.g., `Float64<m/s^2>`, with the unit given as symbols and their exponents
+    Unit(Box<Type>, Vec<(Ident, i32)>),
     /// Should be inferred, i.e., is not annotated
     Inferred,
 }
@@ -366,6 +480,8 @@ pub enum ExpressionKind {
     Unary(UnOp, Box<Expression>),
     /// An if-then-else expression
     Ite(Box<Expression>, Box<Expression>, Box<Expression>),
+    /// A let binding, e.g., `let (lat, lon) = pos in lat + lon`
+    Let(LetPattern, Box<Expression>, Box<Expression>),
     /// An expression enveloped in parentheses
     ParenthesizedExpression(Option<Box<Parenthesis>>, Box<Expression>, Option<Box<Parenthesis>>),
 
@@ -381,6 +497,38 @@ pub enum ExpressionKind {
     Function(FunctionName, Vec<Type>, Vec<Box<Expression>>),
 }
 
+/// The left-hand side of a let binding
+#[derive(Debug, Clone)]
+pub enum LetPattern {
+    /// Binds the whole value, e.g., `let x = ...`
+    Ident(Rc<LetBinding>),
+    /// Binds the components of a tuple, e.g., `let (a, b) = ...`
+    Tuple(Vec<Rc<LetBinding>>),
+}
+
+impl LetPattern {
+    /// Returns all names bound by this pattern
+    pub fn bindings(&self) -> Vec<&Rc<LetBinding>> {
+        match self {
+            LetPattern::Ident(binding) => vec![binding],
+            LetPattern::Tuple(bindings) => bindings.iter().collect(),
+        }
+    }
+}
+
+/**
+An AST node representing a name introduced by a let binding.
+*/
+#[derive(Debug, Clone)]
+pub struct LetBinding {
+    /// The bound name
+    pub name: Ident,
+    /// The ID of the node in the AST
+    pub id: NodeId,
+    /// The span in the specification declaring the binding
+    pub span: Span,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 /// The AST representation of the different aggregation functions
 pub enum WindowOperation {
@@ -469,6 +617,10 @@ impl Literal {
     pub(crate) fn new_raw_str(id: NodeId, val: &str, span: Span) -> Literal {
         Literal { id, kind: LitKind::RawStr(val.to_string()), span }
     }
+
+    pub(crate) fn new_subnet(id: NodeId, val: &str, span: Span) -> Literal {
+        Literal { id, kind: LitKind::Subnet(val.to_string()), span }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -483,6 +635,8 @@ pub enum LitKind {
     Numeric(String, Option<String>),
     /// A boolean literal (`true`)
     Bool(bool),
+    /// A network in CIDR notation (`10.0.0.0/8`, `fe80::/10`)
+    Subnet(String),
 }
 
 /**
@@ -3,6 +3,7 @@ use crate::ast::Literal;
 use num::rational::Rational64 as Rational;
 use num::traits::{CheckedMul, Inv, Pow};
 use num::{BigInt, BigRational, FromPrimitive, One, Signed, ToPrimitive};
+use std::net::IpAddr;
 use std::str::FromStr;
 use uom::si::frequency::hertz;
 use uom::si::rational64::Frequency as UOM_Frequency;
@@ -29,42 +30,9 @@ impl Expression {
     }
 
     pub(crate) fn parse_duration(&self) -> Result<UOM_Time, String> {
-        let (val, unit) = match &self.kind {
-            ExpressionKind::Lit(l) => match &l.kind {
-                LitKind::Numeric(val, Some(unit)) => (parse_rational(val)?, unit),
-                _ => return Err(format!("expected numeric value with unit, found `{}`", l)),
-            },
-            _ => return Err(format!("expected numeric value with unit, found `{}`", self)),
-        };
-
-        match unit.as_str() {
-            "ns" | "μs" | "us" | "ms" | "s" | "min" | "h" | "d" | "w" | "a" => {
-                use uom::si::time::*;
-                let factor = match unit.as_str() {
-                    "ns" => UOM_Time::new::<nanosecond>(Rational::one()),
-                    "μs" | "us" => UOM_Time::new::<microsecond>(Rational::one()),
-                    "ms" => UOM_Time::new::<millisecond>(Rational::one()),
-                    "s" => UOM_Time::new::<second>(Rational::one()),
-                    "min" => UOM_Time::new::<minute>(Rational::one()),
-                    "h" => UOM_Time::new::<hour>(Rational::one()),
-                    "d" => UOM_Time::new::<day>(Rational::one()),
-                    "w" => UOM_Time::new::<day>(Rational::from_u64(7).unwrap()),
-                    "a" => UOM_Time::new::<day>(Rational::from_u64(365).unwrap()),
-                    u => unreachable!("'{}' should not have been catched by outer match", u),
-                };
-                let factor = factor.get::<second>();
-                let duration = match val.checked_mul(&factor) {
-                    Some(d) => d,
-                    _ => {
-                        return Err(format!(
-                            "parsing duration failed: rational {}*{} does not fit into Rational64",
-                            val, factor
-                        ))
-                    }
-                };
-                Ok(UOM_Time::new::<second>(duration))
-            }
-            u => Err(format!("expected duration unit, found `{}`", u)),
+        match &self.kind {
+            ExpressionKind::Lit(l) => l.parse_duration(),
+            _ => Err(format!("expected numeric value with unit, found `{}`", self)),
         }
     }
 
@@ -193,6 +161,43 @@ impl Expression {
 }
 
 impl Literal {
+    pub(crate) fn parse_duration(&self) -> Result<UOM_Time, String> {
+        let (val, unit) = match &self.kind {
+            LitKind::Numeric(val, Some(unit)) => (parse_rational(val)?, unit),
+            _ => return Err(format!("expected numeric value with unit, found `{}`", self)),
+        };
+
+        match unit.as_str() {
+            "ns" | "μs" | "us" | "ms" | "s" | "min" | "h" | "d" | "w" | "a" => {
+                use uom::si::time::*;
+                let factor = match unit.as_str() {
+                    "ns" => UOM_Time::new::<nanosecond>(Rational::one()),
+                    "μs" | "us" => UOM_Time::new::<microsecond>(Rational::one()),
+                    "ms" => UOM_Time::new::<millisecond>(Rational::one()),
+                    "s" => UOM_Time::new::<second>(Rational::one()),
+                    "min" => UOM_Time::new::<minute>(Rational::one()),
+                    "h" => UOM_Time::new::<hour>(Rational::one()),
+                    "d" => UOM_Time::new::<day>(Rational::one()),
+                    "w" => UOM_Time::new::<day>(Rational::from_u64(7).unwrap()),
+                    "a" => UOM_Time::new::<day>(Rational::from_u64(365).unwrap()),
+                    u => unreachable!("'{}' should not have been catched by outer match", u),
+                };
+                let factor = factor.get::<second>();
+                let duration = match val.checked_mul(&factor) {
+                    Some(d) => d,
+                    _ => {
+                        return Err(format!(
+                            "parsing duration failed: rational {}*{} does not fit into Rational64",
+                            val, factor
+                        ))
+                    }
+                };
+                Ok(UOM_Time::new::<second>(duration))
+            }
+            u => Err(format!("expected duration unit, found `{}`", u)),
+        }
+    }
+
     pub(crate) fn parse_numeric<T>(&self) -> Option<T>
     where
         T: FromStr,
@@ -207,6 +212,32 @@ impl Literal {
             _ => None,
         }
     }
+
+    /// Parses a network in CIDR notation into its network address and prefix length.
+    /// The prefix length has to fit the address family, and the host bits of the network address have to be zero.
+    pub(crate) fn parse_subnet(&self) -> Result<(IpAddr, u8), String> {
+        let source = match &self.kind {
+            LitKind::Subnet(source) => source,
+            _ => return Err(format!("expected a network in CIDR notation, found `{}`", self)),
+        };
+        let index = source.find('/').expect("ensured by grammar");
+        let address: IpAddr =
+            source[..index].parse().map_err(|_| format!("`{}` is not an IP address", &source[..index]))?;
+        // the network address as bits and its width
+        let (bits, width): (u128, u32) = match address {
+            IpAddr::V4(addr) => (u32::from(addr).into(), 32),
+            IpAddr::V6(addr) => (addr.into(), 128),
+        };
+        let prefix = source[index + 1..]
+            .parse::<u32>()
+            .ok()
+            .filter(|&prefix| prefix <= width)
+            .ok_or_else(|| format!("the prefix length has to be at most {}", width))?;
+        if prefix < width && bits.trailing_zeros() < width - prefix {
+            return Err("the host bits of the network address have to be zero".to_string());
+        }
+        Ok((address, prefix as u8))
+    }
 }
 
 impl Offset {
@@ -285,6 +316,7 @@ impl Expression {
             | ParenthesizedExpression(_, inner, _) => Box::new(std::iter::once(self).chain(inner.iter())),
             Binary(_, left, right)
             | Default(left, right)
+            | Let(_, left, right)
             | SlidingWindowAggregation { expr: left, duration: right, .. } => {
                 Box::new(std::iter::once(self).chain(left.iter()).chain(right.iter()))
             }
@@ -0,0 +1,202 @@
+//! This module contains the instantiation of templates.
+//!
+//! The streams declared in a template are parsed anew for every instantiation, which assigns fresh node ids.
+//! Afterwards, `Instantiation` renames the template parameters and the declared streams and moves all spans
+//! behind the end of the specification, see `Handler::register_expansion`.
+
+use super::*;
+use crate::parse::{Ident, Span};
+use std::collections::HashMap;
+
+/// Renames and relocates the streams of a single template instantiation.
+pub(crate) struct Instantiation {
+    /// Maps template parameters, declared streams, and trigger names to their names in the instantiation
+    renaming: HashMap<String, String>,
+    /// The offset added to every span
+    offset: usize,
+    /// Names bound by stream parameters and let expressions, which shadow the renaming
+    shadowed: Vec<String>,
+}
+
+impl Instantiation {
+    pub(crate) fn new(renaming: HashMap<String, String>, offset: usize) -> Self {
+        Instantiation { renaming, offset, shadowed: Vec::new() }
+    }
+
+    pub(crate) fn instantiate_output(&mut self, output: &mut Output) {
+        self.rename(&mut output.name);
+        self.relocate(&mut output.name.span);
+        self.relocate_type(&mut output.ty);
+        self.relocate(&mut output.span);
+
+        let shadowed = self.shadowed.len();
+        for param in &mut output.params {
+            let param = Rc::get_mut(param).expect("parameters of a freshly parsed stream are not shared");
+            self.relocate(&mut param.name.span);
+            self.relocate_type(&mut param.ty);
+            self.relocate(&mut param.span);
+            self.shadowed.push(param.name.name.clone());
+        }
+
+        self.relocate(&mut output.extend.span);
+        if let Some(expr) = output.extend.expr.as_mut() {
+            self.instantiate_expression(expr);
+        }
+        if let Some(template_spec) = output.template_spec.as_mut() {
+            self.relocate(&mut template_spec.span);
+            if let Some(inv) = template_spec.inv.as_mut() {
+                self.relocate(&mut inv.span);
+                self.instantiate_expression(&mut inv.target);
+                if let Some(condition) = inv.condition.as_mut() {
+                    self.instantiate_expression(condition);
+                }
+            }
+            if let Some(ext) = template_spec.ext.as_mut() {
+                self.relocate(&mut ext.span);
+                self.instantiate_expression(&mut ext.target);
+            }
+            if let Some(ter) = template_spec.ter.as_mut() {
+                self.relocate(&mut ter.span);
+                self.instantiate_expression(&mut ter.target);
+            }
+        }
+        if let Some(termination) = output.termination.as_mut() {
+            self.instantiate_expression(termination);
+        }
+        self.instantiate_expression(&mut output.expression);
+        self.shadowed.truncate(shadowed);
+    }
+
+    pub(crate) fn instantiate_trigger(&mut self, trigger: &mut Trigger) {
+        if let Some(name) = trigger.name.as_mut() {
+            self.rename(name);
+            self.relocate(&mut name.span);
+        }
+        self.relocate(&mut trigger.extend.span);
+        if let Some(expr) = trigger.extend.expr.as_mut() {
+            self.instantiate_expression(expr);
+        }
+        self.instantiate_expression(&mut trigger.expression);
+        for expr in trigger.placeholders_mut() {
+            self.instantiate_expression(expr);
+        }
+        self.relocate(&mut trigger.span);
+    }
+
+    fn instantiate_expression(&mut self, expr: &mut Expression) {
+        use ExpressionKind::*;
+        self.relocate(&mut expr.span);
+        match &mut expr.kind {
+            Lit(literal) => self.relocate(&mut literal.span),
+            Ident(ident) => {
+                self.rename(ident);
+                self.relocate(&mut ident.span);
+            }
+            MissingExpression => {}
+            StreamAccess(inner, _) | Offset(inner, _) | Unary(_, inner) => self.instantiate_expression(inner),
+            Default(left, right) | Binary(_, left, right) => {
+                self.instantiate_expression(left);
+                self.instantiate_expression(right);
+            }
+            SlidingWindowAggregation { expr, duration, .. } => {
+                self.instantiate_expression(expr);
+                self.instantiate_expression(duration);
+            }
+            Ite(cond, cons, alt) => {
+                self.instantiate_expression(cond);
+                self.instantiate_expression(cons);
+                self.instantiate_expression(alt);
+            }
+            Let(pattern, value, body) => {
+                self.instantiate_expression(value);
+                let shadowed = self.shadowed.len();
+                let bindings = match pattern {
+                    LetPattern::Ident(binding) => std::slice::from_mut(binding),
+                    LetPattern::Tuple(bindings) => bindings.as_mut_slice(),
+                };
+                for binding in bindings {
+                    let binding = Rc::get_mut(binding).expect("bindings of a freshly parsed stream are not shared");
+                    self.relocate(&mut binding.name.span);
+                    self.relocate(&mut binding.span);
+                    self.shadowed.push(binding.name.name.clone());
+                }
+                self.instantiate_expression(body);
+                self.shadowed.truncate(shadowed);
+            }
+            ParenthesizedExpression(open, inner, close) => {
+                if let Some(open) = open.as_mut() {
+                    self.relocate(&mut open.span);
+                }
+                self.instantiate_expression(inner);
+                if let Some(close) = close.as_mut() {
+                    self.relocate(&mut close.span);
+                }
+            }
+            Tuple(elements) => elements.iter_mut().for_each(|element| self.instantiate_expression(element)),
+            Field(inner, field) => {
+                self.instantiate_expression(inner);
+                self.relocate(&mut field.span);
+            }
+            Method(inner, name, types, args) => {
+                self.instantiate_expression(inner);
+                self.relocate_function_name(name);
+                types.iter_mut().for_each(|ty| self.relocate_type(ty));
+                args.iter_mut().for_each(|arg| self.instantiate_expression(arg));
+            }
+            Function(name, types, args) => {
+                // parametrized streams are accessed like functions
+                self.rename(&mut name.name);
+                self.relocate_function_name(name);
+                types.iter_mut().for_each(|ty| self.relocate_type(ty));
+                args.iter_mut().for_each(|arg| self.instantiate_expression(arg));
+            }
+        }
+    }
+
+    fn relocate_function_name(&self, name: &mut FunctionName) {
+        self.relocate(&mut name.name.span);
+        for arg_name in name.arg_names.iter_mut().flatten() {
+            self.relocate(&mut arg_name.span);
+        }
+    }
+
+    fn relocate_type(&self, ty: &mut Type) {
+        self.relocate(&mut ty.span);
+        match &mut ty.kind {
+            TypeKind::Simple(_) | TypeKind::Inferred => {}
+            TypeKind::Tuple(elements) => elements.iter_mut().for_each(|element| self.relocate_type(element)),
+            TypeKind::NamedTuple(fields) => {
+                for (name, element) in fields {
+                    self.relocate(&mut name.span);
+                    self.relocate_type(element);
+                }
+            }
+            TypeKind::Optional(inner) | TypeKind::Set(inner) | TypeKind::List(inner) => self.relocate_type(inner),
+            TypeKind::Unit(inner, unit) => {
+                self.relocate_type(inner);
+                unit.iter_mut().for_each(|(symbol, _)| self.relocate(&mut symbol.span));
+            }
+            TypeKind::Map(key, value) => {
+                self.relocate_type(key);
+                self.relocate_type(value);
+            }
+        }
+    }
+
+    /// Replaces template parameters and declared streams by their names in the instantiation
+    fn rename(&self, ident: &mut Ident) {
+        if self.shadowed.contains(&ident.name) {
+            return;
+        }
+        if let Some(name) = self.renaming.get(&ident.name) {
+            ident.name = name.clone();
+        }
+    }
+
+    fn relocate(&self, span: &mut Span) {
+        if *span != Span::unknown() {
+            span.start += self.offset;
+            span.end += self.offset;
+        }
+    }
+}
@@ -0,0 +1,577 @@
+//! This module desugars the past-time temporal operators `once`, `historically`, and `since`, as well as the
+//! signal-processing operators `derivative`, `ema`, and `lowpass`.
+//!
+//! Each untimed operator is replaced by an access to a derived output stream that recursively accumulates the
+//! operator's value, e.g., `once(x)` becomes an access to `once(x) := x ∨ once(x).offset(by: -1).defaults(to: false)`.
+//! The timed variants, e.g., `once(x, within: 5s)`, become sliding windows.
+//! The signal-processing operators keep their state in derived streams as well; the elapsed time between two values
+//! is taken from a derived stream recording `now()` whenever the operand is evaluated.
+//! Derived streams are marked as hidden, so they are not reported during monitoring.
+
+use super::*;
+use crate::parse::{Ident, NodeId, Span};
+use crate::reporting::{Handler, LabeledSpan};
+use uom::si::frequency::hertz;
+
+/// The past-time operators that are desugared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PastTimeOperator {
+    /// `once(x)`: `x` held at some point in the past
+    Once,
+    /// `historically(x)`: `x` held at every point in the past
+    Historically,
+    /// `x since y`: `y` held at some point in the past and `x` held ever since
+    Since,
+}
+
+impl PastTimeOperator {
+    fn from_signature(signature: &str) -> Option<(PastTimeOperator, bool)> {
+        use PastTimeOperator::*;
+        match signature {
+            "once(_:)" => Some((Once, false)),
+            "once(_:within:)" => Some((Once, true)),
+            "historically(_:)" => Some((Historically, false)),
+            "historically(_:within:)" => Some((Historically, true)),
+            "since(_:_:)" => Some((Since, false)),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PastTimeOperator::Once => "once",
+            PastTimeOperator::Historically => "historically",
+            PastTimeOperator::Since => "since",
+        }
+    }
+}
+
+/// The signal-processing operators that are desugared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignalOperator {
+    /// `x.derivative()`: the change of `x` per second since its last value
+    Derivative,
+    /// `x.ema(alpha: a)`: the exponential moving average `a * x + (1 - a) * ema`
+    Ema,
+    /// `x.lowpass(cutoff: f)`: a first-order low-pass filter with cutoff frequency `f`
+    Lowpass,
+}
+
+impl SignalOperator {
+    fn from_signature(signature: &str, method: bool) -> Option<SignalOperator> {
+        use SignalOperator::*;
+        match (signature, method) {
+            ("derivative()", true) | ("derivative(_:)", false) => Some(Derivative),
+            ("ema(alpha:)", true) | ("ema(_:alpha:)", false) => Some(Ema),
+            ("lowpass(cutoff:)", true) | ("lowpass(_:cutoff:)", false) => Some(Lowpass),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SignalOperator::Derivative => "derivative",
+            SignalOperator::Ema => "ema",
+            SignalOperator::Lowpass => "lowpass",
+        }
+    }
+}
+
+/// Replaces past-time and signal-processing operators by derived streams and sliding windows.
+pub(crate) struct PastTimeDesugaring<'a, 'b> {
+    handler: &'b Handler,
+    next_id: &'a dyn Fn() -> NodeId,
+    /// The derived streams
+    derived: Vec<Output>,
+    /// The activation condition of the stream currently desugared, which derived streams inherit
+    activation: Option<Expression>,
+    /// Names bound by stream parameters and let expressions, which must not occur in operands
+    bound: Vec<String>,
+}
+
+impl<'a, 'b> PastTimeDesugaring<'a, 'b> {
+    pub(crate) fn new(handler: &'b Handler, next_id: &'a dyn Fn() -> NodeId) -> Self {
+        PastTimeDesugaring { handler, next_id, derived: Vec::new(), activation: None, bound: Vec::new() }
+    }
+
+    pub(crate) fn desugar(mut self, spec: &mut RTLolaAst) {
+        for output in &mut spec.outputs {
+            let output = Rc::get_mut(output).expect("streams are not shared during parsing");
+            self.activation = output.extend.expr.clone();
+            self.bound = output.params.iter().map(|param| param.name.name.clone()).collect();
+            if let Some(expr) = output.extend.expr.as_mut() {
+                self.desugar_expression(expr);
+            }
+            if let Some(template_spec) = output.template_spec.as_mut() {
+                if let Some(inv) = template_spec.inv.as_mut() {
+                    self.desugar_expression(&mut inv.target);
+                    if let Some(condition) = inv.condition.as_mut() {
+                        self.desugar_expression(condition);
+                    }
+                }
+                if let Some(ext) = template_spec.ext.as_mut() {
+                    self.desugar_expression(&mut ext.target);
+                }
+                if let Some(ter) = template_spec.ter.as_mut() {
+                    self.desugar_expression(&mut ter.target);
+                }
+            }
+            if let Some(termination) = output.termination.as_mut() {
+                self.desugar_expression(termination);
+            }
+            self.desugar_expression(&mut output.expression);
+        }
+        self.bound.clear();
+        for trigger in &mut spec.trigger {
+            let trigger = Rc::get_mut(trigger).expect("streams are not shared during parsing");
+            self.activation = trigger.extend.expr.clone();
+            if let Some(expr) = trigger.extend.expr.as_mut() {
+                self.desugar_expression(expr);
+            }
+            self.desugar_expression(&mut trigger.expression);
+            for expr in trigger.placeholders_mut() {
+                self.desugar_expression(expr);
+            }
+        }
+        spec.outputs.extend(self.derived.into_iter().map(Rc::new));
+    }
+
+    /// Desugars the operands first, so nested operators become accesses to derived streams.
+    fn desugar_expression(&mut self, expr: &mut Expression) {
+        use ExpressionKind::*;
+        match &mut expr.kind {
+            Lit(_) | Ident(_) | MissingExpression => {}
+            StreamAccess(inner, _) | Offset(inner, _) | Unary(_, inner) | Field(inner, _) => {
+                self.desugar_expression(inner)
+            }
+            ParenthesizedExpression(_, inner, _) => self.desugar_expression(inner),
+            Default(left, right) | Binary(_, left, right) => {
+                self.desugar_expression(left);
+                self.desugar_expression(right);
+            }
+            SlidingWindowAggregation { expr, duration, .. } => {
+                self.desugar_expression(expr);
+                self.desugar_expression(duration);
+            }
+            Ite(cond, cons, alt) => {
+                self.desugar_expression(cond);
+                self.desugar_expression(cons);
+                self.desugar_expression(alt);
+            }
+            Let(pattern, value, body) => {
+                self.desugar_expression(value);
+                let bound = self.bound.len();
+                self.bound.extend(pattern.bindings().iter().map(|binding| binding.name.name.clone()));
+                self.desugar_expression(body);
+                self.bound.truncate(bound);
+            }
+            Tuple(elements) => elements.iter_mut().for_each(|element| self.desugar_expression(element)),
+            Method(inner, _, _, args) => {
+                self.desugar_expression(inner);
+                args.iter_mut().for_each(|arg| self.desugar_expression(arg));
+            }
+            Function(_, _, args) => args.iter_mut().for_each(|arg| self.desugar_expression(arg)),
+        }
+        let operator = match &expr.kind {
+            Function(name, types, args) if types.is_empty() => PastTimeOperator::from_signature(&name.as_string())
+                .filter(|(operator, _)| args.iter().all(|arg| self.check_operand(operator.name(), arg))),
+            _ => None,
+        };
+        if let Some((operator, timed)) = operator {
+            if let Function(_, _, args) = std::mem::replace(&mut expr.kind, MissingExpression) {
+                expr.kind = self.desugar_operator(operator, timed, args, expr.span);
+            }
+            return;
+        }
+        let signal = match &expr.kind {
+            Method(_, name, types, _) if types.is_empty() => SignalOperator::from_signature(&name.as_string(), true),
+            Function(name, types, _) if types.is_empty() => SignalOperator::from_signature(&name.as_string(), false),
+            _ => None,
+        };
+        let signal = signal.filter(|operator| {
+            let args: Vec<&Expression> = match &expr.kind {
+                Method(inner, _, _, args) => std::iter::once(inner).chain(args).map(Box::as_ref).collect(),
+                Function(_, _, args) => args.iter().map(Box::as_ref).collect(),
+                _ => unreachable!(),
+            };
+            args.iter().all(|arg| self.check_operand(operator.name(), arg))
+                && self.check_parameter(*operator, &args[1..])
+        });
+        if let Some(operator) = signal {
+            let name = self.derived_name(expr.to_string(), true);
+            let (operand, parameter) = match std::mem::replace(&mut expr.kind, MissingExpression) {
+                Method(inner, _, _, args) => (inner, args.into_iter().next()),
+                Function(_, _, args) => {
+                    let mut args = args.into_iter();
+                    (args.next().expect("checked by signature"), args.next())
+                }
+                _ => unreachable!(),
+            };
+            expr.kind = self.desugar_signal(operator, name, operand, parameter, expr.span);
+        }
+    }
+
+    /// Operands are moved into derived streams, so they must not refer to names bound in the surrounding stream.
+    fn check_operand(&self, operator: &str, operand: &Expression) -> bool {
+        let mut valid = true;
+        for expr in operand.iter() {
+            if let ExpressionKind::Ident(ident) = &expr.kind {
+                if self.bound.contains(&ident.name) {
+                    self.handler.error_with_span(
+                        &format!("the operand of `{}` can only refer to streams", operator),
+                        LabeledSpan::new(ident.span, &format!("`{}` is bound in this stream", ident.name), true),
+                    );
+                    valid = false;
+                }
+            }
+        }
+        valid
+    }
+
+    #[allow(clippy::vec_box)]
+    fn desugar_operator(
+        &mut self,
+        operator: PastTimeOperator,
+        timed: bool,
+        mut args: Vec<Box<Expression>>,
+        span: Span,
+    ) -> ExpressionKind {
+        use PastTimeOperator::*;
+        if timed {
+            let duration = args.pop().expect("checked by signature");
+            let operand = args.pop().expect("checked by signature");
+            let target = match operand.kind {
+                ExpressionKind::Ident(_) => operand,
+                _ => {
+                    // the operand is evaluated whenever the accessed streams are, so the window sees every value
+                    let name = self.derived_name(format!("({})", operand), false);
+                    Box::new(self.access_derived(name, *operand, false, span))
+                }
+            };
+            let aggregation =
+                if operator == Once { WindowOperation::Disjunction } else { WindowOperation::Conjunction };
+            return ExpressionKind::SlidingWindowAggregation { expr: target, duration, wait: false, aggregation };
+        }
+
+        let names: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let name = self.derived_name(format!("{}({})", operator.name(), names.join(", ")), true);
+        let previous = self.previous_value(&name, operator == Historically, span);
+        let mut args = args.into_iter();
+        let first = args.next().expect("checked by signature");
+        let body = match operator {
+            Once => ExpressionKind::Binary(BinOp::Or, first, previous),
+            Historically => ExpressionKind::Binary(BinOp::And, first, previous),
+            Since => {
+                let second = args.next().expect("checked by signature");
+                let hold = Expression::new(self.next_id(), ExpressionKind::Binary(BinOp::And, first, previous), span);
+                ExpressionKind::Binary(BinOp::Or, second, Box::new(hold))
+            }
+        };
+        let body = Expression::new(self.next_id(), body, span);
+        self.access_derived(name, body, true, span).kind
+    }
+
+    /// Checks the parameter of a signal-processing operator: the smoothing factor of `ema` has to be in `(0, 1]` if
+    /// it is a literal, and the cutoff of `lowpass` has to be a frequency literal.
+    fn check_parameter(&self, operator: SignalOperator, parameters: &[&Expression]) -> bool {
+        match (operator, parameters.first()) {
+            (SignalOperator::Ema, Some(alpha)) => match &alpha.kind {
+                ExpressionKind::Lit(Literal { kind: LitKind::Numeric(val, None), .. }) => {
+                    let valid = matches!(val.parse::<f64>(), Ok(alpha) if alpha > 0.0 && alpha <= 1.0);
+                    if !valid {
+                        self.handler.error_with_span(
+                            "the smoothing factor of `ema` has to be in (0, 1]",
+                            LabeledSpan::new(alpha.span, "found here", true),
+                        );
+                    }
+                    valid
+                }
+                _ => true,
+            },
+            (SignalOperator::Lowpass, Some(cutoff)) => match cutoff.parse_frequency() {
+                Ok(_) => true,
+                Err(reason) => {
+                    self.handler.error_with_span(
+                        "the cutoff of `lowpass` has to be a frequency, e.g., `2Hz`",
+                        LabeledSpan::new(cutoff.span, &reason, true),
+                    );
+                    false
+                }
+            },
+            _ => true,
+        }
+    }
+
+    /// Declares the derived stream of a signal-processing operator and returns an access to it.
+    /// The operand is moved into a derived stream of its own, so that its previous value can be accessed.
+    fn desugar_signal(
+        &mut self,
+        operator: SignalOperator,
+        name: String,
+        operand: Box<Expression>,
+        parameter: Option<Box<Expression>>,
+        span: Span,
+    ) -> ExpressionKind {
+        use ExpressionKind::*;
+        let stream = self.operand_stream(operand, span);
+        let body = match operator {
+            SignalOperator::Derivative => {
+                // if elapsed > 0.0 then (cast(x) - cast(x[-1])) / elapsed else derivative[-1]
+                let elapsed = self.elapsed_seconds(&stream, span);
+                let previous = self.previous(&stream, self.ident(&stream, span), span);
+                let change = self.parenthesized(
+                    Binary(BinOp::Sub, self.cast(self.ident(&stream, span), span), self.cast(previous, span)),
+                    span,
+                );
+                let rate = self.expression(Binary(BinOp::Div, change, Box::new(self.renumber(&elapsed))), span);
+                let hold = self.previous(&name, self.float("0.0", span), span);
+                let positive = self.expression(Binary(BinOp::Gt, Box::new(elapsed), self.float("0.0", span)), span);
+                Ite(positive, rate, hold)
+            }
+            SignalOperator::Ema => {
+                // alpha * x + (1.0 - alpha) * ema[-1]
+                let alpha = parameter.expect("checked by signature");
+                let current = self
+                    .expression(Binary(BinOp::Mul, Box::new(self.renumber(&alpha)), self.ident(&stream, span)), span);
+                let decay = self.parenthesized(Binary(BinOp::Sub, self.float("1.0", span), alpha), span);
+                let previous = self.previous(&name, self.ident(&stream, span), span);
+                let history = self.expression(Binary(BinOp::Mul, decay, previous), span);
+                Binary(BinOp::Add, current, history)
+            }
+            SignalOperator::Lowpass => {
+                // with the time constant rc = 1 / (2π cutoff):
+                // if elapsed > 0.0 then lowpass[-1] + elapsed / (rc + elapsed) * (cast(x) - lowpass[-1])
+                // else lowpass[-1]
+                let cutoff = parameter.expect("checked by signature").parse_frequency().expect("checked before");
+                let cutoff = cutoff.get::<hertz>();
+                let time_constant =
+                    1.0 / (2.0 * std::f64::consts::PI * *cutoff.numer() as f64 / *cutoff.denom() as f64);
+                let mut time_constant = time_constant.to_string();
+                if !time_constant.contains('.') {
+                    time_constant.push_str(".0");
+                }
+                let elapsed = self.elapsed_seconds(&stream, span);
+                let previous = |this: &Self| this.previous(&name, this.cast(this.ident(&stream, span), span), span);
+                let period = self.parenthesized(
+                    Binary(BinOp::Add, self.float(&time_constant, span), Box::new(self.renumber(&elapsed))),
+                    span,
+                );
+                let gain = self.expression(Binary(BinOp::Div, Box::new(self.renumber(&elapsed)), period), span);
+                let error = self.parenthesized(
+                    Binary(BinOp::Sub, self.cast(self.ident(&stream, span), span), previous(self)),
+                    span,
+                );
+                let step = self.expression(Binary(BinOp::Mul, gain, error), span);
+                let filtered = self.expression(Binary(BinOp::Add, previous(self), step), span);
+                let positive = self.expression(Binary(BinOp::Gt, Box::new(elapsed), self.float("0.0", span)), span);
+                Ite(positive, filtered, previous(self))
+            }
+        };
+        let body = Expression::new(self.next_id(), body, span);
+        self.access_derived(name, body, true, span).kind
+    }
+
+    /// Returns the name of the stream holding the operand, which is a derived stream unless the operand is a stream and
+    /// the current stream has no explicit activation condition.
+    /// With an explicit activation condition, the previous value of the operand has to be the one seen at the previous
+    /// evaluation of the current stream, not the previous value of the operand stream itself.
+    fn operand_stream(&mut self, mut operand: Box<Expression>, span: Span) -> String {
+        while let ExpressionKind::ParenthesizedExpression(_, inner, _) = operand.kind {
+            operand = inner;
+        }
+        match &operand.kind {
+            ExpressionKind::Ident(ident) if self.activation.is_none() => ident.name.clone(),
+            _ => {
+                let name = self.derived_name(format!("({})", operand), true);
+                self.access_derived(name.clone(), *operand, true, span);
+                name
+            }
+        }
+    }
+
+    /// Builds the seconds elapsed since the previous evaluation of the operand stream, i.e.,
+    /// `(now() - time.offset(by: -1).defaults(to: now())).seconds()`, where the derived stream `time := now()` is
+    /// evaluated together with the operand.
+    /// With an explicit activation condition, the operand stream inherits it, so the time stream uses it as well.
+    fn elapsed_seconds(&mut self, stream: &str, span: Span) -> Expression {
+        let activation = match &self.activation {
+            Some(activation) => self.renumber(activation),
+            None => *self.ident(stream, span),
+        };
+        let name = format!("now() @ {}", activation);
+        let now = *self.now(span);
+        self.declare_derived(name.clone(), now, Some(activation), span);
+        let previous = self.previous(&name, self.now(span), span);
+        let difference = self.parenthesized(ExpressionKind::Binary(BinOp::Sub, self.now(span), previous), span);
+        let seconds = FunctionName::new("seconds".to_string(), &[]);
+        Expression::new(self.next_id(), ExpressionKind::Method(difference, seconds, Vec::new(), Vec::new()), span)
+    }
+
+    fn expression(&self, kind: ExpressionKind, span: Span) -> Box<Expression> {
+        Box::new(Expression::new(self.next_id(), kind, span))
+    }
+
+    fn parenthesized(&self, kind: ExpressionKind, span: Span) -> Box<Expression> {
+        let open = Some(Box::new(Parenthesis::new(self.next_id(), span)));
+        let close = Some(Box::new(Parenthesis::new(self.next_id(), span)));
+        self.expression(ExpressionKind::ParenthesizedExpression(open, self.expression(kind, span), close), span)
+    }
+
+    fn ident(&self, name: &str, span: Span) -> Box<Expression> {
+        self.expression(ExpressionKind::Ident(Ident::new(name.to_string(), span)), span)
+    }
+
+    fn float(&self, val: &str, span: Span) -> Box<Expression> {
+        self.expression(ExpressionKind::Lit(Literal::new_numeric(self.next_id(), val, None, span)), span)
+    }
+
+    fn now(&self, span: Span) -> Box<Expression> {
+        self.expression(
+            ExpressionKind::Function(FunctionName::new("now".to_string(), &[]), Vec::new(), Vec::new()),
+            span,
+        )
+    }
+
+    /// Builds `cast(expr)`, so that integral operands can be filtered as well.
+    fn cast(&self, expr: Box<Expression>, span: Span) -> Box<Expression> {
+        let cast = FunctionName::new("cast".to_string(), &[None]);
+        self.expression(ExpressionKind::Function(cast, Vec::new(), vec![expr]), span)
+    }
+
+    /// Builds `name.offset(by: -1).defaults(to: initial)`.
+    fn previous_value(&self, name: &str, initial: bool, span: Span) -> Box<Expression> {
+        let initial = Expression::new(
+            self.next_id(),
+            ExpressionKind::Lit(Literal::new_bool(self.next_id(), initial, span)),
+            span,
+        );
+        self.previous(name, Box::new(initial), span)
+    }
+
+    /// Builds `name.offset(by: -1).defaults(to: default)`.
+    fn previous(&self, name: &str, default: Box<Expression>, span: Span) -> Box<Expression> {
+        let offset = self.expression(ExpressionKind::Offset(self.ident(name, span), Offset::Discrete(-1)), span);
+        self.expression(ExpressionKind::Default(offset, default), span)
+    }
+
+    /// Chooses the name of a derived stream, which is the operator application itself so that printed specifications
+    /// can be parsed again. Only if the same operator is used with another activation condition, the activation
+    /// condition is appended.
+    fn derived_name(&self, name: String, inherit: bool) -> String {
+        let activation = self.activation.as_ref().filter(|_| inherit).map(|activation| activation.to_string());
+        let existing = self.derived.iter().find(|output| output.name.name == name);
+        match existing {
+            Some(output) if output.extend.expr.as_ref().map(|expr| expr.to_string()) != activation => {
+                format!("{} @ {}", name, activation.unwrap_or_else(|| "any".to_string()))
+            }
+            _ => name,
+        }
+    }
+
+    /// Declares a derived stream with the given expression, unless one with the same name exists, and returns an
+    /// access to it. If `inherit` is set, the derived stream uses the activation condition of the current stream.
+    fn access_derived(&mut self, name: String, expression: Expression, inherit: bool, span: Span) -> Expression {
+        let activation = self.activation.as_ref().filter(|_| inherit).map(|expr| self.renumber(expr));
+        self.declare_derived(name, expression, activation, span)
+    }
+
+    /// Declares a derived stream with the given expression and activation condition, unless one with the same name
+    /// exists, and returns an access to it.
+    fn declare_derived(
+        &mut self,
+        name: String,
+        expression: Expression,
+        activation: Option<Expression>,
+        span: Span,
+    ) -> Expression {
+        if !self.derived.iter().any(|output| output.name.name == name) {
+            let extend = ActivationCondition { expr: activation, id: self.next_id(), span: Span::unknown() };
+            self.derived.push(Output {
+                name: Ident::new(name.clone(), span),
+                ty: Type::new_inferred(self.next_id()),
+                extend,
+                params: Vec::new(),
+                template_spec: None,
+                termination: None,
+                expression,
+                id: self.next_id(),
+                span,
+                hidden: true,
+            });
+        }
+        Expression::new(self.next_id(), ExpressionKind::Ident(Ident::new(name, span)), span)
+    }
+
+    /// Copies an expression and assigns fresh ids to all of its nodes.
+    fn renumber(&self, expr: &Expression) -> Expression {
+        use ExpressionKind::*;
+        let renumber = |expr: &Expression| Box::new(self.renumber(expr));
+        let kind = match &expr.kind {
+            Lit(literal) => Lit(Literal { id: self.next_id(), ..literal.clone() }),
+            Ident(_) | MissingExpression => expr.kind.clone(),
+            StreamAccess(inner, kind) => StreamAccess(renumber(inner), *kind),
+            Default(left, right) => Default(renumber(left), renumber(right)),
+            Offset(inner, offset) => Offset(renumber(inner), *offset),
+            SlidingWindowAggregation { expr, duration, wait, aggregation } => SlidingWindowAggregation {
+                expr: renumber(expr),
+                duration: renumber(duration),
+                wait: *wait,
+                aggregation: *aggregation,
+            },
+            Binary(op, left, right) => Binary(*op, renumber(left), renumber(right)),
+            Unary(op, inner) => Unary(*op, renumber(inner)),
+            Ite(cond, cons, alt) => Ite(renumber(cond), renumber(cons), renumber(alt)),
+            Let(pattern, value, body) => {
+                let rebind =
+                    |binding: &Rc<LetBinding>| Rc::new(LetBinding { id: self.next_id(), ..(**binding).clone() });
+                let pattern = match pattern {
+                    LetPattern::Ident(binding) => LetPattern::Ident(rebind(binding)),
+                    LetPattern::Tuple(bindings) => LetPattern::Tuple(bindings.iter().map(rebind).collect()),
+                };
+                Let(pattern, renumber(value), renumber(body))
+            }
+            ParenthesizedExpression(open, inner, close) => {
+                let reparen = |paren: &Option<Box<Parenthesis>>| {
+                    paren.as_ref().map(|paren| Box::new(Parenthesis::new(self.next_id(), paren.span)))
+                };
+                ParenthesizedExpression(reparen(open), renumber(inner), reparen(close))
+            }
+            Tuple(elements) => Tuple(elements.iter().map(|element| renumber(element)).collect()),
+            Field(inner, field) => Field(renumber(inner), field.clone()),
+            Method(inner, name, types, args) => Method(
+                renumber(inner),
+                name.clone(),
+                types.iter().map(|ty| self.renumber_type(ty)).collect(),
+                args.iter().map(|arg| renumber(arg)).collect(),
+            ),
+            Function(name, types, args) => Function(
+                name.clone(),
+                types.iter().map(|ty| self.renumber_type(ty)).collect(),
+                args.iter().map(|arg| renumber(arg)).collect(),
+            ),
+        };
+        Expression::new(self.next_id(), kind, expr.span)
+    }
+
+    fn renumber_type(&self, ty: &Type) -> Type {
+        let kind = match &ty.kind {
+            TypeKind::Simple(_) | TypeKind::Inferred => ty.kind.clone(),
+            TypeKind::Tuple(elements) => TypeKind::Tuple(elements.iter().map(|ty| self.renumber_type(ty)).collect()),
+            TypeKind::NamedTuple(fields) => {
+                TypeKind::NamedTuple(fields.iter().map(|(name, ty)| (name.clone(), self.renumber_type(ty))).collect())
+            }
+            TypeKind::Optional(inner) => TypeKind::Optional(Box::new(self.renumber_type(inner))),
+            TypeKind::Set(inner) => TypeKind::Set(Box::new(self.renumber_type(inner))),
+            TypeKind::List(inner) => TypeKind::List(Box::new(self.renumber_type(inner))),
+            TypeKind::Unit(inner, unit) => TypeKind::Unit(Box::new(self.renumber_type(inner)), unit.clone()),
+            TypeKind::Map(key, value) => {
+                TypeKind::Map(Box::new(self.renumber_type(key)), Box::new(self.renumber_type(value)))
+            }
+        };
+        Type { kind, id: self.next_id(), span: ty.span }
+    }
+
+    fn next_id(&self) -> NodeId {
+        (self.next_id)()
+    }
+}
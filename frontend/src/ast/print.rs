@@ -49,7 +49,11 @@ impl Display for Input {
         if !self.params.is_empty() {
             write_delim_list(f, &self.params, " (", ")", ", ")?;
         }
-        write!(f, ": {}", self.ty)
+        write!(f, ": {}", self.ty)?;
+        if let Some(assumption) = &self.assumption {
+            write!(f, " assume {}", assumption)?;
+        }
+        Ok(())
     }
 }
 
@@ -128,11 +132,18 @@ impl Display for Trigger {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "trigger{} {}{}",
-            format_opt(&self.name, " ", " :="),
+            "trigger{}{}{}{} {}{}",
+            format_opt(&self.name, " ", ""),
+            format_opt(&self.severity, ": ", ""),
+            format_opt(&self.extend.expr, " @ ", ""),
+            if self.name.is_some() { " :=" } else { "" },
             self.expression,
             format_opt(&self.message, " \"", "\""),
-        )
+        )?;
+        if !self.options.is_empty() {
+            write_delim_list(f, &self.options, " with ", "", ", ")?;
+        }
+        Ok(())
     }
 }
 
@@ -147,12 +158,46 @@ impl Display for TypeKind {
         match &self {
             TypeKind::Simple(name) => write!(f, "{}", name),
             TypeKind::Tuple(types) => write_delim_list(f, types, "(", ")", ", "),
+            TypeKind::NamedTuple(fields) => {
+                let fields: Vec<String> = fields.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+                write_delim_list(f, &fields, "(", ")", ", ")
+            }
             TypeKind::Optional(ty) => write!(f, "{}?", ty),
+            TypeKind::Set(ty) => write!(f, "Set<{}>", ty),
+            TypeKind::List(ty) => write!(f, "List<{}>", ty),
+            TypeKind::Map(key, value) => write!(f, "Map<{}, {}>", key, value),
+            TypeKind::Unit(ty, unit) => {
+                write!(f, "{}<", ty)?;
+                for (idx, (symbol, exponent)) in unit.iter().enumerate() {
+                    match (idx, exponent.signum()) {
+                        (0, -1) => write!(f, "1/{}", symbol)?,
+                        (0, _) => write!(f, "{}", symbol)?,
+                        (_, -1) => write!(f, "/{}", symbol)?,
+                        (_, _) => write!(f, "*{}", symbol)?,
+                    }
+                    if exponent.abs() != 1 {
+                        write!(f, "^{}", exponent.abs())?;
+                    }
+                }
+                write!(f, ">")
+            }
             TypeKind::Inferred => write!(f, "_"),
         }
     }
 }
 
+impl Display for LetPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            LetPattern::Ident(binding) => write!(f, "{}", binding.name),
+            LetPattern::Tuple(bindings) => {
+                let names: Vec<&Ident> = bindings.iter().map(|binding| &binding.name).collect();
+                write_delim_list(f, &names, "(", ")", ", ")
+            }
+        }
+    }
+}
+
 impl Display for TypeDeclField {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "{}: {}", &self.name, &self.ty)
@@ -185,6 +230,7 @@ impl Display for Expression {
             ExpressionKind::Binary(op, lhs, rhs) => write!(f, "{} {} {}", lhs, op, rhs),
             ExpressionKind::Unary(operator, operand) => write!(f, "{}{}", operator, operand),
             ExpressionKind::Ite(cond, cons, alt) => write!(f, "if {} then {} else {}", cond, cons, alt),
+            ExpressionKind::Let(pattern, value, body) => write!(f, "let {} = {} in {}", pattern, value, body),
             ExpressionKind::ParenthesizedExpression(left, expr, right) => {
                 write!(f, "{}{}{}", if left.is_some() { "(" } else { "" }, expr, if right.is_some() { ")" } else { "" })
             }
@@ -289,6 +335,30 @@ impl Display for WindowOperation {
     }
 }
 
+impl Display for TriggerOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            TriggerOption::RisingEdge(_) => write!(f, "rising"),
+            TriggerOption::Rearm(duration) => write!(f, "rearm: {}", duration),
+            TriggerOption::Limit(count, interval) => write!(f, "limit: {} per {}", count, interval),
+        }
+    }
+}
+
+impl Display for TriggerSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                TriggerSeverity::Info => "info",
+                TriggerSeverity::Warning => "warning",
+                TriggerSeverity::Critical => "critical",
+            }
+        )
+    }
+}
+
 impl Display for UnOp {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
@@ -309,6 +379,7 @@ impl Display for Literal {
             LitKind::Bool(val) => write!(f, "{}", val),
             LitKind::Numeric(val, unit) => write!(f, "{}{}", val, unit.clone().unwrap_or_default()),
             LitKind::Str(s) => write!(f, "\"{}\"", s),
+            LitKind::Subnet(s) => write!(f, "{}", s),
             LitKind::RawStr(s) => {
                 // need to determine padding with `#`
                 let mut padding = 0;
@@ -364,8 +435,17 @@ impl Display for Import {
     }
 }
 
+impl Display for Annotation {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "#![{}]", self.name)
+    }
+}
+
 impl Display for RTLolaAst {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for annotation in &self.annotations {
+            writeln!(f, "{}", annotation)?;
+        }
         for import in &self.imports {
             writeln!(f, "{}", import)?;
         }
@@ -378,7 +458,8 @@ impl Display for RTLolaAst {
         for input in &self.inputs {
             writeln!(f, "{}", input)?;
         }
-        for output in &self.outputs {
+        // hidden streams are derived from the operators that refer to them and are declared anew when parsing
+        for output in self.outputs.iter().filter(|output| !output.hidden) {
             writeln!(f, "{}", output)?;
         }
         for trigger in &self.trigger {
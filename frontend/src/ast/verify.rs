@@ -16,6 +16,12 @@ impl<'a, 'b> Verifier<'a, 'b> {
     }
 
     pub(crate) fn check(&self) {
+        for input in &self.spec.inputs {
+            if let Some(assumption) = input.assumption.as_ref() {
+                self.check_expression(assumption);
+                self.check_input_assumption(input, assumption);
+            }
+        }
         for output in &self.spec.outputs {
             if let Some(extend) = output.extend.expr.as_ref() {
                 self.check_expression(extend);
@@ -23,7 +29,12 @@ impl<'a, 'b> Verifier<'a, 'b> {
             self.check_expression(&output.expression);
         }
         for trigger in &self.spec.trigger {
+            if let Some(extend) = trigger.extend.expr.as_ref() {
+                self.check_expression(extend);
+            }
             self.check_expression(&trigger.expression);
+            trigger.placeholders().for_each(|expr| self.check_expression(expr));
+            Self::check_trigger_options(self.handler, trigger);
         }
     }
 
@@ -31,7 +42,6 @@ impl<'a, 'b> Verifier<'a, 'b> {
         expr.iter().for_each(|inner| Self::check_missing_paranthesis(self.handler, inner));
         expr.iter().for_each(|inner| Self::check_missing_expression(self.handler, inner));
         expr.iter().for_each(|inner| Self::check_direct_access(self.handler, inner));
-        expr.iter().for_each(|inner| Self::check_field_access(self.handler, inner));
         expr.iter().for_each(|inner| Self::check_valid_offset(self.handler, inner));
         expr.iter().for_each(|inner| Self::check_sliding_window_duration(self.handler, inner));
     }
@@ -100,15 +110,89 @@ impl<'a, 'b> Verifier<'a, 'b> {
         }
     }
 
-    fn check_field_access(handler: &Handler, expr: &Expression) {
+    /// Assumptions only constrain the current value of the input they are declared on
+    fn check_input_assumption(&self, input: &Input, assumption: &Expression) {
         use ExpressionKind::*;
-        if let Field(_, ident) = &expr.kind {
-            if ident.name.parse::<usize>().is_err() {
+        let bound: Vec<&str> = assumption
+            .iter()
+            .filter_map(|expr| match &expr.kind {
+                Let(pattern, _, _) => Some(pattern.bindings()),
+                _ => None,
+            })
+            .flatten()
+            .map(|binding| binding.name.name.as_str())
+            .collect();
+        for expr in assumption.iter() {
+            match &expr.kind {
+                Offset(..) | SlidingWindowAggregation { .. } | StreamAccess(..) => self.handler.error_with_span(
+                    "assumptions can only refer to the current value of the input",
+                    LabeledSpan::new(expr.span, "stream access is not allowed in an assumption", true),
+                ),
+                Ident(ident)
+                    if ident.name != input.name.name
+                        && !bound.contains(&ident.name.as_str())
+                        && !self.spec.constants.iter().any(|c| c.name.name == ident.name) =>
+                {
+                    self.handler.error_with_span(
+                        &format!("the assumption of input `{}` refers to `{}`", input.name.name, ident.name),
+                        LabeledSpan::new(ident.span, "assumptions can only refer to the input itself", true),
+                    )
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Every trigger option can be given at most once, with positive durations and a positive number of reports
+    fn check_trigger_options(handler: &Handler, trigger: &Trigger) {
+        let mut seen: Vec<(&str, Span)> = Vec::new();
+        for option in &trigger.options {
+            let (name, span) = match option {
+                TriggerOption::RisingEdge(span) => ("rising", *span),
+                TriggerOption::Rearm(duration) => {
+                    Self::check_positive_duration(handler, duration, "re-arm duration");
+                    ("rearm", duration.span)
+                }
+                TriggerOption::Limit(count, interval) => {
+                    if count.parse_numeric::<u32>().filter(|count| *count > 0).is_none() {
+                        handler.error_with_span(
+                            "the number of reports needs to be a positive integer",
+                            LabeledSpan::new(count.span, "expected a positive integer", true),
+                        );
+                    }
+                    Self::check_positive_duration(handler, interval, "rate limit interval");
+                    ("limit", count.span)
+                }
+            };
+            if let Some((_, previous)) = seen.iter().find(|(other, _)| *other == name) {
+                let mut builder = handler.build_error_with_span(
+                    &format!("the trigger option `{}` is given multiple times", name),
+                    LabeledSpan::new(span, "given again here", true),
+                );
+                builder.add_span_with_label(*previous, "first given here", false);
+                builder.emit();
+            } else {
+                seen.push((name, span));
+            }
+        }
+    }
+
+    fn check_positive_duration(handler: &Handler, duration: &Literal, what: &str) {
+        match duration.parse_duration() {
+            Err(_) => {
                 handler.error_with_span(
-                    "field access has to be an integer",
-                    LabeledSpan::new(ident.span, "expected an integer", true),
+                    &format!("{} invalid", what),
+                    LabeledSpan::new(duration.span, "expected a duration, e.g., `10s`", true),
                 );
             }
+            Ok(dur) => {
+                if !dur.get::<second>().is_positive() {
+                    handler.error_with_span(
+                        &format!("only positive {}s are supported", what),
+                        LabeledSpan::new(duration.span, "duration non-positive", true),
+                    );
+                }
+            }
         }
     }
 
@@ -191,4 +275,24 @@ mod tests {
         assert_eq!(1, number_of_errors("output a := (x+1).hold()"));
         assert_eq!(1, number_of_errors("output a := (x+1).aggregate(over: 1h, using: avg)"));
     }
+
+    #[test]
+    fn test_trigger_options() {
+        assert_eq!(0, number_of_errors("trigger x > 5 \"x\" with rising, rearm: 10s, limit: 3 per 1min"));
+        assert_eq!(1, number_of_errors("trigger x > 5 with rearm: 0s"));
+        assert_eq!(1, number_of_errors("trigger x > 5 with rearm: 10"));
+        assert_eq!(1, number_of_errors("trigger x > 5 with limit: 0 per 1s"));
+        assert_eq!(1, number_of_errors("trigger x > 5 with limit: 1.5 per 1s"));
+        assert_eq!(1, number_of_errors("trigger x > 5 with limit: 3 per 1Hz"));
+        assert_eq!(1, number_of_errors("trigger x > 5 with rising, rising"));
+    }
+
+    #[test]
+    fn test_input_assumptions() {
+        assert_eq!(0, number_of_errors("input alt: Float64 assume alt >= 0.0 && alt < 10000.0"));
+        assert_eq!(0, number_of_errors("constant max: Float64 := 10.0\ninput x: Float64 assume let y = x in y < max"));
+        assert_eq!(1, number_of_errors("input a: Int64, b: Int64 assume b > a"));
+        assert_eq!(1, number_of_errors("input a: Int64 assume a.offset(by: -1).defaults(to: 0) < a"));
+        assert_eq!(1, number_of_errors("input a: Int64 assume a.hold().defaults(to: 0) > 0"));
+    }
 }
@@ -4,13 +4,16 @@ This module describes the intermediate representation of a specification.
 
 pub(crate) mod lowering;
 mod print;
+pub(crate) mod range;
 mod schedule;
 
 pub use crate::ast::StreamAccessKind;
+pub use crate::ast::TriggerSeverity;
 pub use crate::ast::WindowOperation;
 pub use crate::ir::schedule::{Deadline, Schedule};
 pub use crate::ty::{Activation, FloatTy, IntTy, UIntTy, ValueTy}; // Re-export needed for IR
 
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::time::Duration;
 use uom::si::rational64::Frequency as UOM_Frequency;
 use uom::si::rational64::Time as UOM_Time;
@@ -48,10 +51,28 @@ pub enum Type {
     String,
     /// A sequence of 8bit bytes
     Bytes,
+    /// A span of time with nanosecond precision
+    Duration,
+    /// A point in time with nanosecond precision
+    Timestamp,
+    /// An IPv4 address
+    IPv4Addr,
+    /// An IPv6 address
+    IPv6Addr,
+    /// An IPv4 network given by its network address and prefix length
+    IPv4Net,
+    /// An IPv6 network given by its network address and prefix length
+    IPv6Net,
     /// A n-ary tuples where n is the length of the contained vector.
     Tuple(Vec<Type>),
     /// An optional value type, e.g., resulting from accessing a stream with offset -1
     Option(Box<Type>),
+    /// An immutable set containing values of the given type
+    Set(Box<Type>),
+    /// An immutable sequence containing values of the given type
+    List(Box<Type>),
+    /// An immutable map from keys of the first type to values of the second type
+    Map(Box<Type>, Box<Type>),
     /// A type describing a function containing its argument types and return type. Resolve ambiguities in polymorphic functions and operations.
     Function(Vec<Type>, Box<Type>),
 }
@@ -65,8 +86,18 @@ impl From<&ValueTy> for Type {
             ValueTy::Float(f) => Type::Float(*f),
             ValueTy::String => Type::String,
             ValueTy::Bytes => Type::Bytes,
+            ValueTy::Duration => Type::Duration,
+            ValueTy::Timestamp => Type::Timestamp,
+            ValueTy::IPv4Addr => Type::IPv4Addr,
+            ValueTy::IPv6Addr => Type::IPv6Addr,
+            ValueTy::IPv4Net => Type::IPv4Net,
+            ValueTy::IPv6Net => Type::IPv6Net,
             ValueTy::Tuple(t) => Type::Tuple(t.iter().map(|e| e.into()).collect()),
+            ValueTy::NamedTuple(fields) => Type::Tuple(fields.iter().map(|(_, e)| e.into()).collect()),
             ValueTy::Option(o) => Type::Option(Box::new(o.as_ref().into())),
+            ValueTy::Set(e) => Type::Set(Box::new(e.as_ref().into())),
+            ValueTy::List(e) => Type::List(Box::new(e.as_ref().into())),
+            ValueTy::Map(k, v) => Type::Map(Box::new(k.as_ref().into()), Box::new(v.as_ref().into())),
             _ => unreachable!("cannot lower `ValueTy` {}", ty),
         }
     }
@@ -111,12 +142,14 @@ pub enum Tracking {
 }
 
 /// Represents an input stream in an RTLola specification.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct InputStream {
     /// The name of the stream.
     pub name: String,
     /// The type of the stream.
     pub ty: Type,
+    /// The assumption every new value of the stream has to satisfy; it only accesses the stream itself.
+    pub assumption: Option<Expression>,
     /// What streams depend, i.e., access values of this stream.
     pub dependent_streams: Vec<Tracking>,
     /// Which sliding windows aggregate values of this stream.
@@ -154,6 +187,8 @@ pub struct OutputStream {
     pub reference: StreamReference,
     /// The activation condition, which indicates when this stream needs to be evaluated.  Will be empty if the stream has a fixed frequency.
     pub ac: Option<Activation<StreamReference>>,
+    /// Indicates that the stream was derived by the frontend, e.g., for past-time operators, and should not be reported.
+    pub hidden: bool,
 }
 
 /// Wrapper for output streams providing additional information specific to timedriven streams.
@@ -177,14 +212,61 @@ pub struct EventDrivenStream {
 }
 
 /// Wrapper for output streams that are actually triggers.  Provides additional information specific to triggers.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Trigger {
+    /// The name of the trigger, if it is declared as `trigger <name> := ...`.
+    pub name: Option<String>,
+    /// The severity of the trigger, which defaults to `TriggerSeverity::Warning`.
+    pub severity: TriggerSeverity,
     /// The trigger message that is supposed to be conveyed to the user if the trigger reports a violation.
     pub message: String,
+    /// The parts of the message, which interpolate the values of expressions at the time of the violation.
+    pub message_parts: Vec<MessagePart>,
     /// A reference to the output stream representing the trigger.
     pub reference: StreamReference,
     /// The index of the trigger.
     pub trigger_idx: usize,
+    /// Whether the trigger reports the robustness of its condition, i.e., the signed distance to its violation.
+    pub robustness: bool,
+    /// The options suppressing repeated reports of the trigger.
+    pub options: TriggerOptions,
+}
+
+impl Trigger {
+    /// Returns the expressions whose values are interpolated into the message.
+    pub fn placeholders(&self) -> impl Iterator<Item = &Expression> {
+        self.message_parts.iter().filter_map(|part| match part {
+            MessagePart::Text(_) => None,
+            MessagePart::Placeholder(expr) => Some(expr),
+        })
+    }
+}
+
+/// Options suppressing repeated reports of a trigger.  Suppressed reports are still counted in the statistics.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct TriggerOptions {
+    /// Report only when the condition becomes true rather than whenever it holds.
+    pub rising_edge: bool,
+    /// After a report, the condition needs to be false for this duration before the trigger reports again.  Implies `rising_edge`.
+    pub rearm: Option<Duration>,
+    /// Report at most the given number of times per interval.
+    pub limit: Option<(u32, Duration)>,
+}
+
+impl TriggerOptions {
+    /// Returns true if the trigger reports only when its condition becomes true.
+    pub fn is_edge_triggered(&self) -> bool {
+        self.rising_edge || self.rearm.is_some()
+    }
+}
+
+/// Represents a part of a trigger message.
+#[derive(Debug, PartialEq, Clone)]
+pub enum MessagePart {
+    /// Literal text.
+    Text(String),
+    /// An expression whose value is interpolated into the message.
+    Placeholder(Expression),
 }
 
 /// Represents an expression.
@@ -207,6 +289,9 @@ pub enum ExpressionKind {
     /// Binary: 1st argument -> lhs, 2nd argument -> rhs
     /// n-ary: kth argument -> kth operand
     ArithLog(ArithLogOp, Vec<Expression>, Type),
+    /// Applying an arithmetic operation where at least one operand is optional, e.g., `a.get() + b.get()`
+    /// The result is `None` if one of the operands is `None`, otherwise, it is the result of the operation as in `ArithLog`.
+    OptionalArithLog(ArithLogOp, Vec<Expression>, Type),
     /// Accessing another stream with a potentially 0 offset
     /// 1st argument -> default
     OffsetLookup {
@@ -232,6 +317,17 @@ pub enum ExpressionKind {
     Tuple(Vec<Expression>),
     /// Represents an access to a specific tuple element.  The second argument indicates the index of the accessed element while the first produces the accessed tuple.
     TupleAccess(Box<Expression>, usize),
+    /// Evaluates a value once and binds it to a local variable for the evaluation of the body
+    Let {
+        /// The index of the local variable, unique within the specification
+        variable: usize,
+        /// The expression producing the bound value
+        value: Box<Expression>,
+        /// The expression accessing the local variable
+        body: Box<Expression>,
+    },
+    /// Accessing the value of a local variable bound by an enclosing `Let`
+    LocalVariable(usize),
     /// A function call with its monomorphic type
     /// Argumentes never need to be coerced, @see `Expression::Convert`.
     Function(String, Vec<Expression>, Type),
@@ -266,6 +362,12 @@ pub enum Constant {
     Int(i64),
     #[allow(missing_docs)]
     Float(f64),
+    /// A duration in nanoseconds
+    Duration(i64),
+    /// An IPv4 network given by its network address and prefix length
+    IPv4Net(Ipv4Addr, u8),
+    /// An IPv6 network given by its network address and prefix length
+    IPv6Net(Ipv6Addr, u8),
 }
 
 /// Contains information regarding the dependency between two streams which occurs due to a lookup expression.
@@ -668,13 +770,19 @@ impl Type {
             Type::Float(FloatTy::F16) => Some(ValSize(2)),
             Type::Float(FloatTy::F32) => Some(ValSize(4)),
             Type::Float(FloatTy::F64) => Some(ValSize(8)),
+            Type::Duration | Type::Timestamp => Some(ValSize(8)),
+            Type::IPv4Addr => Some(ValSize(4)),
+            Type::IPv6Addr => Some(ValSize(16)),
+            Type::IPv4Net => Some(ValSize(5)),
+            Type::IPv6Net => Some(ValSize(17)),
             Type::Option(_) => unimplemented!("Size of option not determined, yet."),
             Type::Tuple(t) => {
                 let size = t.iter().map(|t| Type::size(t).unwrap().0).sum();
                 Some(ValSize(size))
             }
             Type::String | Type::Bytes => unimplemented!("Size of Strings not determined, yet."),
-            Type::Function(_, _) => None,
+            // collections have no static bound on their number of elements
+            Type::Set(_) | Type::List(_) | Type::Map(_, _) | Type::Function(_, _) => None,
         }
     }
 }
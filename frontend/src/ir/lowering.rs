@@ -13,6 +13,7 @@ use crate::parse::NodeId;
 use crate::ty::StreamTy;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::net::IpAddr;
 use std::{rc::Rc, time::Duration};
 
 use crate::analysis::graph_based_analysis::evaluation_order::{EvalOrder, EvaluationOrderResult};
@@ -41,6 +42,10 @@ pub(crate) struct Lowering<'a> {
     tr: &'a TrackingRequirements,
     ir: RTLolaIR,
     ri: &'a RequiredInputs,
+    /// The lowered values of let bindings, substituted where the bound name is used.
+    let_bindings: HashMap<NodeId, ir::Expression>,
+    /// The number of local variables bound by destructuring let expressions so far
+    local_variables: usize,
 }
 
 impl<'a> Lowering<'a> {
@@ -67,6 +72,8 @@ impl<'a> Lowering<'a> {
             tr: &analysis_result.graph_analysis_result.tracking_requirements,
             ir,
             ri: &analysis_result.graph_analysis_result.input_dependencies,
+            let_bindings: HashMap::new(),
+            local_variables: 0,
         }
     }
 
@@ -114,10 +121,12 @@ impl<'a> Lowering<'a> {
         let layer = self.get_layer(nid);
 
         let trackings = self.collect_tracking_info(nid, None);
+        let assumption = input.assumption.as_ref().map(|expr| self.lower_stream_expression(expr, &ir::Type::Bool));
 
         let input = ir::InputStream {
             name: input.name.name.clone(),
             ty: self.lower_node_type(nid),
+            assumption,
             dependent_streams: trackings,
             dependent_windows: Vec::new(),
             layer,
@@ -140,7 +149,9 @@ impl<'a> Lowering<'a> {
     }
 
     fn lower_trigger(&mut self, trigger: &ast::Trigger) {
-        let name = if let Some(msg) = trigger.message.as_ref() {
+        let name = if let Some(ident) = trigger.name.as_ref() {
+            ident.name.clone()
+        } else if let Some(msg) = trigger.message.as_ref() {
             format!("trigger_{}", msg.clone().replace(" ", "_"))
         } else {
             String::from("trigger")
@@ -151,10 +162,20 @@ impl<'a> Lowering<'a> {
         let reference = StreamReference::OutRef(self.ir.outputs.len());
         let mut outgoing_dependencies = Vec::new();
         self.find_dependencies(&trigger.expression, &mut outgoing_dependencies);
+        trigger.placeholders().for_each(|expr| self.find_dependencies(expr, &mut outgoing_dependencies));
+        let message_parts = trigger
+            .message_parts
+            .iter()
+            .map(|part| match part {
+                ast::MessagePart::Text(text) => ir::MessagePart::Text(text.clone()),
+                ast::MessagePart::Placeholder(expr) => ir::MessagePart::Placeholder(self.lower_expression(expr).0),
+            })
+            .collect();
         let input_dependencies = self.gather_dependent_inputs(trigger.id);
-        let ac = match self.check_time_driven(trigger.id, reference) {
+        let time_driven = self.check_time_driven(trigger.id, reference);
+        let ac = match time_driven {
             None => Some(self.tt.get_acti_cond(trigger.id).clone()),
-            Some(_tds) => None,
+            Some(_) => None,
         };
         let output = ir::OutputStream {
             name,
@@ -168,14 +189,20 @@ impl<'a> Lowering<'a> {
             outgoing_dependencies,
             input_dependencies,
             ac,
+            hidden: false,
         };
         self.ir.outputs.push(output);
         let trig = ir::Trigger {
+            name: trigger.name.as_ref().map(|ident| ident.name.clone()),
+            severity: trigger.severity.unwrap_or_default(),
             message: trigger.message.clone().unwrap_or_else(|| format!("{}", trigger.expression)),
+            message_parts,
             reference,
             trigger_idx: self.ir.triggers.len(),
+            robustness: self.ast.annotations.iter().any(|annotation| annotation.name.name == "robustness"),
+            options: self.lower_trigger_options(&trigger.options),
         };
-        match self.check_time_driven(trigger.id, reference) {
+        match time_driven {
             None => self.ir.event_driven.push(EventDrivenStream { reference }),
             Some(tds) => self.ir.time_driven.push(tds),
         }
@@ -225,6 +252,7 @@ impl<'a> Lowering<'a> {
             reference,
             input_dependencies: Vec::new(),
             ac,
+            hidden: ast_output.hidden,
         };
 
         let debug_clone = output.clone();
@@ -303,6 +331,9 @@ impl<'a> Lowering<'a> {
                 ExpressionKind::Ite(cond, cons, alt) => {
                     pre.chain(recursion(cond)).chain(recursion(cons)).chain(recursion(alt)).chain(post()).collect()
                 }
+                ExpressionKind::Let(_, value, body) => {
+                    pre.chain(recursion(value)).chain(recursion(body)).chain(post()).collect()
+                }
                 ExpressionKind::ParenthesizedExpression(_, e, _) => {
                     pre.chain(Lowering::collect_expression(e, map, filter, pre_order)).chain(post()).collect()
                 }
@@ -352,7 +383,7 @@ impl<'a> Lowering<'a> {
             StreamAccess(e, _) | Unary(_, e) | ParenthesizedExpression(_, e, _) | Field(e, _) => {
                 self.find_dependencies(e, deps)
             }
-            Default(left, right) | Binary(_, left, right) => {
+            Default(left, right) | Binary(_, left, right) | Let(_, left, right) => {
                 self.find_dependencies(left, deps);
                 self.find_dependencies(right, deps);
             }
@@ -414,11 +445,33 @@ impl<'a> Lowering<'a> {
 
     fn lower_duration(&self, duration: &ast::Expression) -> Duration {
         let exact_duration = duration.parse_duration().expect("Duration literal needs to be a duration specification.");
+        Self::lower_exact_duration(exact_duration)
+    }
+
+    fn lower_exact_duration(exact_duration: UOM_Time) -> Duration {
         Duration::from_nanos(
             exact_duration.get::<nanosecond>().to_integer().to_u64().expect("Period [ns] too large for u64!"),
         )
     }
 
+    fn lower_trigger_options(&self, options: &[ast::TriggerOption]) -> ir::TriggerOptions {
+        let lower_duration = |duration: &ast::Literal| {
+            Self::lower_exact_duration(duration.parse_duration().expect("checked by the verifier"))
+        };
+        let mut result = ir::TriggerOptions::default();
+        for option in options {
+            match option {
+                ast::TriggerOption::RisingEdge(_) => result.rising_edge = true,
+                ast::TriggerOption::Rearm(duration) => result.rearm = Some(lower_duration(duration)),
+                ast::TriggerOption::Limit(count, interval) => {
+                    result.limit =
+                        Some((count.parse_numeric().expect("checked by the verifier"), lower_duration(interval)))
+                }
+            }
+        }
+        result
+    }
+
     fn lower_storage_req(&self, req: StorageRequirement) -> MemorizationBound {
         match req {
             StorageRequirement::Finite(b) => MemorizationBound::Bounded(b),
@@ -446,6 +499,7 @@ impl<'a> Lowering<'a> {
 
     fn lower_expression(&mut self, expr: &ast::Expression) -> (ir::Expression, ir::Type) {
         let result_type = self.lower_node_type(expr.id);
+        let unit_conversion = self.tt.get_unit_conversion(expr.id);
 
         let expr = match &expr.kind {
             ExpressionKind::Lit(l) => ir::Expression::new(
@@ -471,6 +525,10 @@ impl<'a> Lowering<'a> {
                             self.lower_node_type(output.id),
                         ),
                     ),
+                    Declaration::Let(binding) => (
+                        self.lower_node_type(binding.id),
+                        self.let_bindings.get(&binding.id).expect("bindings are lowered before their uses").clone(),
+                    ),
                     Declaration::Const(constant) => {
                         let node_type = self.lower_node_type(constant.id);
                         (
@@ -518,24 +576,52 @@ impl<'a> Lowering<'a> {
             }
             ExpressionKind::Binary(ast_op, lhs, rhs) => {
                 let ir_op = Lowering::lower_bin_op(*ast_op);
-
-                self.lower_arith_log(expr.id, ir_op, &[lhs, rhs], result_type.clone(), |resolved_poly_types| {
-                    use crate::ast::BinOp::*;
-                    match ast_op {
-                        Add | Sub | Mul | Div | Rem | Pow | Eq | Lt | Le | Ne | Ge | Gt | BitAnd | BitOr | BitXor => {
-                            assert_eq!(resolved_poly_types.len(), 1);
-                            let arg_ty = resolved_poly_types[0].clone();
-                            vec![arg_ty.clone(), arg_ty]
-                        }
-                        And | Or => vec![ir::Type::Bool, ir::Type::Bool],
-                        Shl | Shr => {
-                            assert_eq!(resolved_poly_types.len(), 2);
-                            let lhs_ty = resolved_poly_types[0].clone();
-                            let rhs_ty = resolved_poly_types[1].clone();
-                            vec![lhs_ty, rhs_ty]
+                let operand_types = vec![self.lower_node_type(lhs.id), self.lower_node_type(rhs.id)];
+                let is_optional = |ty: &ir::Type| matches!(ty, ir::Type::Option(_));
+                let optional = operand_types.iter().any(is_optional);
+
+                let expr =
+                    self.lower_arith_log(expr.id, ir_op, &[lhs, rhs], result_type.clone(), |resolved_poly_types| {
+                        use crate::ast::BinOp::*;
+                        match ast_op {
+                            // arithmetic on optional values, see `BinOp::get_optional_func_decl`
+                            _ if optional => {
+                                assert_eq!(resolved_poly_types.len(), 1);
+                                let arg_ty = &resolved_poly_types[0];
+                                operand_types
+                                    .iter()
+                                    .map(|ty| {
+                                        if is_optional(ty) {
+                                            ir::Type::Option(arg_ty.clone().into())
+                                        } else {
+                                            arg_ty.clone()
+                                        }
+                                    })
+                                    .collect()
+                            }
+                            // arithmetic on time values is not generic, see `BinOp::get_time_func_decl`
+                            Add | Sub if resolved_poly_types.is_empty() => operand_types,
+                            Add | Sub | Mul | Div | Rem | Pow | Eq | Lt | Le | Ne | Ge | Gt | BitAnd | BitOr
+                            | BitXor => {
+                                assert_eq!(resolved_poly_types.len(), 1);
+                                let arg_ty = resolved_poly_types[0].clone();
+                                vec![arg_ty.clone(), arg_ty]
+                            }
+                            And | Or => vec![ir::Type::Bool, ir::Type::Bool],
+                            Shl | Shr => {
+                                assert_eq!(resolved_poly_types.len(), 2);
+                                let lhs_ty = resolved_poly_types[0].clone();
+                                let rhs_ty = resolved_poly_types[1].clone();
+                                vec![lhs_ty, rhs_ty]
+                            }
                         }
+                    });
+                match expr.kind {
+                    ir::ExpressionKind::ArithLog(op, args, ty) if optional => {
+                        ir::Expression::new(ir::ExpressionKind::OptionalArithLog(op, args, ty), expr.ty)
                     }
-                })
+                    _ => expr,
+                }
             }
             ExpressionKind::Unary(ast_op, operand) => {
                 let ir_op = Lowering::lower_un_op(*ast_op);
@@ -550,6 +636,33 @@ impl<'a> Lowering<'a> {
                     }]
                 })
             }
+            ExpressionKind::Let(pattern, value, body) => {
+                let (value, value_type) = self.lower_expression(value);
+                match pattern {
+                    ast::LetPattern::Ident(binding) => {
+                        self.let_bindings.insert(binding.id, value);
+                        self.lower_stream_expression(body, &result_type)
+                    }
+                    ast::LetPattern::Tuple(bindings) => {
+                        // the tuple is bound to a local variable, so that it is evaluated once for all components
+                        let variable = self.local_variables;
+                        self.local_variables += 1;
+                        let local = ir::Expression::new(ir::ExpressionKind::LocalVariable(variable), value_type);
+                        for (num, binding) in bindings.iter().enumerate() {
+                            let access = ir::Expression::new(
+                                ir::ExpressionKind::TupleAccess(local.clone().into(), num),
+                                self.lower_node_type(binding.id),
+                            );
+                            self.let_bindings.insert(binding.id, access);
+                        }
+                        let body = self.lower_stream_expression(body, &result_type);
+                        ir::Expression::new(
+                            ir::ExpressionKind::Let { variable, value: Box::new(value), body: Box::new(body) },
+                            result_type.clone(),
+                        )
+                    }
+                }
+            }
             ExpressionKind::Ite(cond, cons, alt) => {
                 let (cond_expr, _) = self.lower_expression(cond);
                 let mut args = self.handle_func_args(&[result_type.clone(), result_type.clone()], &[cons, alt]);
@@ -635,14 +748,31 @@ impl<'a> Lowering<'a> {
                 }
             }
             ExpressionKind::Field(expr, ident) => {
-                let num: usize = ident.name.parse::<usize>().expect("checked in AST verifier");
+                let (num, _) = self.tt.get_value_type(expr.id).get_field(&ident.name).expect("checked by type checker");
                 ir::Expression::new(
                     ir::ExpressionKind::TupleAccess(self.lower_expression(expr).0.into(), num),
                     result_type.clone(),
                 )
             }
         };
-        (expr, result_type)
+        match unit_conversion {
+            Some(factor) => (Lowering::lower_unit_conversion(expr, factor), result_type),
+            None => (expr, result_type),
+        }
+    }
+
+    /// Converts a value into the unit required by its context by multiplying it with the given factor.
+    fn lower_unit_conversion(expr: ir::Expression, factor: f64) -> ir::Expression {
+        let ty = expr.ty.clone();
+        let (factor_ty, kind): (ir::Type, fn(_, _, _) -> _) = match &ty {
+            // e.g., `x.offset(by: -1)` converted within optional arithmetic
+            ir::Type::Option(inner) => ((**inner).clone(), ir::ExpressionKind::OptionalArithLog),
+            _ => (ty.clone(), ir::ExpressionKind::ArithLog),
+        };
+        let factor =
+            ir::Expression::new(ir::ExpressionKind::LoadConstant(ir::Constant::Float(factor)), factor_ty.clone());
+        let fun_ty = ir::Type::Function(vec![ty.clone(), factor_ty], Box::new(ty.clone()));
+        ir::Expression::new(kind(ir::ArithLogOp::Mul, vec![expr, factor], fun_ty), ty)
     }
 
     /// Handles arithmetic-logic operations.
@@ -727,20 +857,22 @@ impl<'a> Lowering<'a> {
         let expected_type = self.lower_node_type(nid);
         match &lit.kind {
             LitKind::Str(s) | LitKind::RawStr(s) => ir::Constant::Str(s.clone()),
-            LitKind::Numeric(_, unit) => {
-                assert!(unit.is_none());
-                match expected_type {
-                    ir::Type::Float(_) => {
-                        ir::Constant::Float(lit.parse_numeric::<f64>().expect("checked by type checker"))
-                    }
-                    ir::Type::UInt(_) => {
-                        ir::Constant::UInt(lit.parse_numeric::<u64>().expect("checked by type checker"))
-                    }
-                    ir::Type::Int(_) => ir::Constant::Int(lit.parse_numeric::<i64>().expect("checked by type checker")),
-                    _ => unreachable!("checked by type checker {}", expected_type),
-                }
+            LitKind::Numeric(_, Some(_)) => {
+                assert_eq!(expected_type, ir::Type::Duration);
+                let duration = lit.parse_duration().expect("checked by type checker");
+                ir::Constant::Duration(duration.get::<nanosecond>().to_integer())
             }
+            LitKind::Numeric(_, None) => match expected_type {
+                ir::Type::Float(_) => ir::Constant::Float(lit.parse_numeric::<f64>().expect("checked by type checker")),
+                ir::Type::UInt(_) => ir::Constant::UInt(lit.parse_numeric::<u64>().expect("checked by type checker")),
+                ir::Type::Int(_) => ir::Constant::Int(lit.parse_numeric::<i64>().expect("checked by type checker")),
+                _ => unreachable!("checked by type checker {}", expected_type),
+            },
             LitKind::Bool(b) => ir::Constant::Bool(*b),
+            LitKind::Subnet(_) => match lit.parse_subnet().expect("checked by type checker") {
+                (IpAddr::V4(addr), prefix) => ir::Constant::IPv4Net(addr, prefix),
+                (IpAddr::V6(addr), prefix) => ir::Constant::IPv6Net(addr, prefix),
+            },
         }
     }
 
@@ -853,6 +985,7 @@ impl<'a> Lowering<'a> {
             Declaration::Out(out) => self.get_ref_for_stream(out.id),
             Declaration::Param(_) | Declaration::Const(_) => unimplemented!(),
             Declaration::Type(_) | Declaration::Func(_) => unreachable!("Types and functions are not streams."),
+            Declaration::Let(_) => unreachable!("checked by naming analysis"),
             Declaration::ParamOut(_) => unreachable!(),
         }
     }
@@ -897,6 +1030,57 @@ mod tests {
         check_stream_number(&ir, 1, 2, 0, 2, 0, 2);
     }
 
+    #[test]
+    fn lower_paced_triggers() {
+        let ir = spec_to_ir(
+            "input a: Int32\ninput b: Bool\ntrigger @1Hz a.hold().defaults(to: 0) > 50\ntrigger @(a ∧ b) a < 30 ∧ b",
+        );
+        check_stream_number(&ir, 2, 2, 1, 1, 0, 2);
+        let schedule = ir.compute_schedule().expect("schedule of periodic trigger");
+        assert!(schedule.deadlines.iter().any(|deadline| deadline.due.contains(&ir.triggers[0].reference.out_ix())));
+        let conjunction = Activation::Conjunction(vec![
+            Activation::Stream(StreamReference::InRef(0)),
+            Activation::Stream(StreamReference::InRef(1)),
+        ]);
+        assert_eq!(ir.outputs[1].ac, Some(conjunction));
+    }
+
+    #[test]
+    fn lower_input_assumption() {
+        let ir = spec_to_ir("input a: Int32 assume a > 0\ninput b: Float64\noutput c := b");
+        check_stream_number(&ir, 2, 1, 0, 1, 0, 0);
+        let assumption = ir.inputs[0].assumption.as_ref().expect("input has an assumption");
+        assert_eq!(assumption.ty, Type::Bool);
+        assert!(ir.inputs[1].assumption.is_none());
+    }
+
+    #[test]
+    fn lower_named_triggers() {
+        let ir = spec_to_ir(
+            "input a: Int32\ntrigger too_high: critical := a > 50\ntrigger too_low @1Hz := a.hold().defaults(to: 0) < 30\ntrigger a = 40",
+        );
+        check_stream_number(&ir, 1, 3, 1, 2, 0, 3);
+        assert_eq!(ir.triggers[0].name.as_deref(), Some("too_high"));
+        assert_eq!(ir.triggers[0].severity, TriggerSeverity::Critical);
+        assert_eq!(ir.outputs[ir.triggers[0].reference.out_ix()].name, "too_high");
+        assert_eq!(ir.triggers[1].name.as_deref(), Some("too_low"));
+        assert_eq!(ir.triggers[1].severity, TriggerSeverity::Warning);
+        assert_eq!(ir.triggers[2].name, None);
+        assert_eq!(ir.triggers[2].severity, TriggerSeverity::Warning);
+    }
+
+    #[test]
+    fn lower_trigger_options() {
+        let ir = spec_to_ir("input a: Int32\ntrigger a > 50 with rising, limit: 3 per 1min\ntrigger a < 0 with rearm: 500ms\ntrigger a = 0");
+        assert_eq!(
+            ir.triggers[0].options,
+            TriggerOptions { rising_edge: true, rearm: None, limit: Some((3, Duration::from_secs(60))) }
+        );
+        assert_eq!(ir.triggers[1].options.rearm, Some(Duration::from_millis(500)));
+        assert!(ir.triggers[1].options.is_edge_triggered());
+        assert_eq!(ir.triggers[2].options, TriggerOptions::default());
+    }
+
     #[test]
     fn lower_one_output_event() {
         let ir = spec_to_ir("output a: Int32 := 34");
@@ -993,6 +1177,35 @@ mod tests {
         assert_eq!("cast<Float64,Float32>(In(0))", format!("{}", expr))
     }
 
+    #[test]
+    fn lower_unit_conversion() {
+        let ir = spec_to_ir("input a: Float64<ft>\ninput b: Float64<m>\noutput c: Float64<m> := a\noutput d := b - a");
+        assert_eq!(ir.outputs[0].ty, Type::Float(crate::ty::FloatTy::F64));
+        assert_eq!("*(In(0),0.3048) : [(Float64,Float64) -> Float64]", format!("{}", ir.outputs[0].expr));
+        assert!(format!("{}", ir.outputs[1].expr).contains("*(In(0),0.3048)"));
+    }
+
+    #[test]
+    fn lower_tuple_destructuring() {
+        let ir = spec_to_ir("input pos: (Float64, Float64)\noutput d: Float64 := let (x, y) = pos in x - y");
+        // the tuple is bound once and both components access the local variable
+        assert_eq!(
+            "let $0 = In(0) in -($0.0,$0.1) : [(Float64,Float64) -> Float64]",
+            format!("{}", ir.outputs[0].expr)
+        );
+    }
+
+    #[test]
+    fn lower_signal_operators() {
+        let ir = spec_to_ir("input a: Float64\noutput d := a.derivative()\noutput l := a.lowpass(cutoff: 2Hz)");
+        assert_eq!(ir.outputs.len(), 5);
+        assert!(ir.outputs.iter().all(|o| o.ty == Type::Float(crate::ty::FloatTy::F64) || o.ty == Type::Timestamp));
+        // the operand, the time stream, and the filters keep their previous value in addition to the current one
+        assert_eq!(ir.inputs[0].memory_bound, MemorizationBound::Bounded(2));
+        assert!(ir.outputs[..2].iter().all(|o| o.memory_bound == MemorizationBound::Bounded(1)));
+        assert!(ir.outputs[2..].iter().all(|o| o.memory_bound == MemorizationBound::Bounded(2)));
+    }
+
     #[ignore] // Needs to be adapted to new lowering.
     #[test]
     fn lower_function_expression_regex() {
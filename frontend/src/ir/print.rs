@@ -24,7 +24,7 @@ impl Display for Expression {
             ExpressionKind::Ite { condition, consequence, alternative, .. } => {
                 write!(f, "if {} then {} else {}", condition, consequence, alternative)
             }
-            ExpressionKind::ArithLog(op, args, ty) => {
+            ExpressionKind::ArithLog(op, args, ty) | ExpressionKind::OptionalArithLog(op, args, ty) => {
                 write_delim_list(f, args, &format!("{}(", op), &format!(") : [{}]", ty), ",")
             }
             ExpressionKind::WindowLookup(wr) => write!(f, "{}", wr),
@@ -36,6 +36,8 @@ impl Display for Expression {
                 StreamAccessKind::Optional => write!(f, "{}.get()", sr),
             },
             ExpressionKind::TupleAccess(expr, num) => write!(f, "{}.{}", expr, num),
+            ExpressionKind::Let { variable, value, body } => write!(f, "let ${} = {} in {}", variable, value, body),
+            ExpressionKind::LocalVariable(variable) => write!(f, "${}", variable),
         }
     }
 }
@@ -47,7 +49,10 @@ impl Display for Constant {
             Constant::UInt(u) => write!(f, "{}", u),
             Constant::Int(i) => write!(f, "{}", i),
             Constant::Float(fl) => write!(f, "{}", fl),
+            Constant::Duration(ns) => write!(f, "{}ns", ns),
             Constant::Str(s) => write!(f, "{}", s),
+            Constant::IPv4Net(addr, prefix) => write!(f, "{}/{}", addr, prefix),
+            Constant::IPv6Net(addr, prefix) => write!(f, "{}/{}", addr, prefix),
         }
     }
 }
@@ -101,7 +106,16 @@ impl Display for Type {
             Type::Tuple(elems) => write_delim_list(f, elems, "(", ")", ","),
             Type::String => write!(f, "String"),
             Type::Bytes => write!(f, "Bytes"),
+            Type::Duration => write!(f, "Duration"),
+            Type::Timestamp => write!(f, "Timestamp"),
+            Type::IPv4Addr => write!(f, "IPv4Addr"),
+            Type::IPv6Addr => write!(f, "IPv6Addr"),
+            Type::IPv4Net => write!(f, "IPv4Net"),
+            Type::IPv6Net => write!(f, "IPv6Net"),
             Type::Option(inner) => write!(f, "Option<{}>", inner),
+            Type::Set(inner) => write!(f, "Set<{}>", inner),
+            Type::List(inner) => write!(f, "List<{}>", inner),
+            Type::Map(key, value) => write!(f, "Map<{},{}>", key, value),
             Type::Bool => write!(f, "Bool"),
         }
     }
@@ -0,0 +1,886 @@
+//! A static range analysis over the numeric values of streams.
+//!
+//! Ranges are represented as intervals over `f64` whose bounds are either inclusive or exclusive.
+//! The value ranges of input streams are derived from their types and declared assumptions.
+//! Booleans are represented by the interval from `0` (false) to `1` (true).
+//! The ranges of output streams are computed by an abstract interpretation of their expressions until a fixed point is reached.
+//! Based on these ranges, the analysis warns about possible divisions by zero, overflows of narrow integer types, and triggers that never or always fire.
+
+use crate::ast::RTLolaAst;
+use crate::ir::{ArithLogOp, Constant, Expression, ExpressionKind, InputStream, RTLolaIR, StreamReference, Type};
+use crate::ir::{IntTy, StreamAccessKind, UIntTy, WindowOperation};
+use crate::parse::Span;
+use crate::reporting::{Handler, LabeledSpan};
+use std::fmt::{Display, Formatter, Result};
+
+/// A bound of an `Interval`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bound {
+    pub(crate) value: f64,
+    pub(crate) inclusive: bool,
+}
+
+impl Bound {
+    fn inclusive(value: f64) -> Self {
+        Bound { value, inclusive: true }
+    }
+
+    fn exclusive(value: f64) -> Self {
+        Bound { value, inclusive: false }
+    }
+}
+
+/// A possibly empty or unbounded interval of numeric values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Interval {
+    pub(crate) lo: Bound,
+    pub(crate) hi: Bound,
+}
+
+impl Interval {
+    /// The interval containing all values
+    pub(crate) fn full() -> Self {
+        Interval { lo: Bound::inclusive(f64::NEG_INFINITY), hi: Bound::inclusive(f64::INFINITY) }
+    }
+
+    /// The interval containing no value
+    pub(crate) fn empty() -> Self {
+        Interval { lo: Bound::exclusive(f64::INFINITY), hi: Bound::exclusive(f64::NEG_INFINITY) }
+    }
+
+    /// The interval containing exactly the given value
+    pub(crate) fn singleton(value: f64) -> Self {
+        Interval { lo: Bound::inclusive(value), hi: Bound::inclusive(value) }
+    }
+
+    /// The values representable by the given type; non-numeric types are unbounded
+    pub(crate) fn of_type(ty: &Type) -> Self {
+        let (lo, hi) = match ty {
+            Type::Int(IntTy::I8) => (i8::MIN as f64, i8::MAX as f64),
+            Type::Int(IntTy::I16) => (i16::MIN as f64, i16::MAX as f64),
+            Type::Int(IntTy::I32) => (i32::MIN as f64, i32::MAX as f64),
+            Type::Int(IntTy::I64) => (i64::MIN as f64, i64::MAX as f64),
+            Type::UInt(UIntTy::U8) => (0.0, u8::MAX as f64),
+            Type::UInt(UIntTy::U16) => (0.0, u16::MAX as f64),
+            Type::UInt(UIntTy::U32) => (0.0, u32::MAX as f64),
+            Type::UInt(UIntTy::U64) => (0.0, u64::MAX as f64),
+            Type::Bool => (0.0, 1.0),
+            _ => return Interval::full(),
+        };
+        Interval::closed(lo, hi)
+    }
+
+    fn closed(lo: f64, hi: f64) -> Self {
+        Interval { lo: Bound::inclusive(lo), hi: Bound::inclusive(hi) }
+    }
+
+    /// The interval of a boolean that is known to be `value`, or of an unknown boolean
+    fn boolean(value: Option<bool>) -> Self {
+        match value {
+            Some(value) => Interval::singleton(if value { 1.0 } else { 0.0 }),
+            None => Interval::closed(0.0, 1.0),
+        }
+    }
+
+    /// Whether the interval represents a boolean that is known to be `value`
+    pub(crate) fn is_certainly(&self, value: bool) -> bool {
+        *self == Interval::boolean(Some(value))
+    }
+
+    pub(crate) fn contains(&self, value: f64) -> bool {
+        (self.lo.value < value || (self.lo.value == value && self.lo.inclusive))
+            && (value < self.hi.value || (value == self.hi.value && self.hi.inclusive))
+    }
+
+    /// Whether all values of the interval are contained in `other`
+    pub(crate) fn is_within(&self, other: &Interval) -> bool {
+        self.is_empty() || self.intersect(other) == *self
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lo.value > self.hi.value || (self.lo.value == self.hi.value && !(self.lo.inclusive && self.hi.inclusive))
+    }
+
+    /// The values contained in both intervals
+    pub(crate) fn intersect(&self, other: &Interval) -> Interval {
+        let lo = if self.lo.value > other.lo.value || (self.lo.value == other.lo.value && !self.lo.inclusive) {
+            self.lo
+        } else {
+            other.lo
+        };
+        let hi = if self.hi.value < other.hi.value || (self.hi.value == other.hi.value && !self.hi.inclusive) {
+            self.hi
+        } else {
+            other.hi
+        };
+        Interval { lo, hi }
+    }
+
+    /// The smallest interval containing both intervals
+    pub(crate) fn hull(&self, other: &Interval) -> Interval {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let lo = if self.lo.value < other.lo.value || (self.lo.value == other.lo.value && self.lo.inclusive) {
+            self.lo
+        } else {
+            other.lo
+        };
+        let hi = if self.hi.value > other.hi.value || (self.hi.value == other.hi.value && self.hi.inclusive) {
+            self.hi
+        } else {
+            other.hi
+        };
+        Interval { lo, hi }
+    }
+
+    /// Makes exclusive bounds inclusive, assuming that the interval only contains integers
+    fn tighten_integer(&self) -> Interval {
+        if self.is_empty() {
+            return *self;
+        }
+        let lo = if self.lo.inclusive { self.lo.value.ceil() } else { self.lo.value.floor() + 1.0 };
+        let hi = if self.hi.inclusive { self.hi.value.floor() } else { self.hi.value.ceil() - 1.0 };
+        Interval::closed(lo, hi)
+    }
+
+    fn neg(&self) -> Interval {
+        Interval {
+            lo: Bound { value: -self.hi.value, inclusive: self.hi.inclusive },
+            hi: Bound { value: -self.lo.value, inclusive: self.lo.inclusive },
+        }
+    }
+
+    fn add(&self, other: &Interval) -> Interval {
+        Interval::closed(self.lo.value + other.lo.value, self.hi.value + other.hi.value)
+    }
+
+    fn sub(&self, other: &Interval) -> Interval {
+        Interval::closed(self.lo.value - other.hi.value, self.hi.value - other.lo.value)
+    }
+
+    /// The smallest interval containing the results of applying `op` to all combinations of bounds
+    fn corners(&self, other: &Interval, op: impl Fn(f64, f64) -> f64) -> Option<Interval> {
+        let results = [
+            op(self.lo.value, other.lo.value),
+            op(self.lo.value, other.hi.value),
+            op(self.hi.value, other.lo.value),
+            op(self.hi.value, other.hi.value),
+        ];
+        if results.iter().any(|result| result.is_nan()) {
+            return None;
+        }
+        let lo = results.iter().cloned().fold(f64::INFINITY, f64::min);
+        let hi = results.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        Some(Interval::closed(lo, hi))
+    }
+
+    fn mul(&self, other: &Interval) -> Interval {
+        // `0 * inf` only occurs for unbounded intervals, where zero is the product of the finite values
+        self.corners(other, |a, b| if a == 0.0 || b == 0.0 { 0.0 } else { a * b }).unwrap_or_else(Interval::full)
+    }
+
+    /// Divides by an interval; integer division truncates towards zero
+    fn div(&self, other: &Interval, integer: bool) -> Interval {
+        if other.contains(0.0) {
+            return Interval::full();
+        }
+        match self.corners(other, |a, b| a / b) {
+            Some(quotients) if integer => quotients.hull(&Interval::singleton(0.0)),
+            Some(quotients) => quotients,
+            None => Interval::full(),
+        }
+    }
+
+    /// The remainder has the sign of the dividend and is smaller in magnitude than the divisor
+    fn rem(&self, other: &Interval) -> Interval {
+        let magnitude = other.lo.value.abs().max(other.hi.value.abs());
+        if self.lo.value >= 0.0 {
+            Interval::closed(0.0, self.hi.value.min(magnitude))
+        } else if self.hi.value <= 0.0 {
+            Interval::closed(self.lo.value.max(-magnitude), 0.0)
+        } else {
+            Interval::closed(-magnitude, magnitude)
+        }
+    }
+
+    fn and(&self, other: &Interval) -> Interval {
+        Interval::closed(self.lo.value.min(other.lo.value), self.hi.value.min(other.hi.value))
+    }
+
+    fn or(&self, other: &Interval) -> Interval {
+        Interval::closed(self.lo.value.max(other.lo.value), self.hi.value.max(other.hi.value))
+    }
+
+    fn not(&self) -> Interval {
+        Interval::closed(1.0 - self.hi.value, 1.0 - self.lo.value)
+    }
+
+    /// Whether every value of the interval is smaller than every value of `other`
+    fn certainly_lt(&self, other: &Interval) -> bool {
+        self.hi.value < other.lo.value
+            || (self.hi.value == other.lo.value && !(self.hi.inclusive && other.lo.inclusive))
+    }
+
+    /// Whether every value of the interval is smaller than or equal to every value of `other`
+    fn certainly_le(&self, other: &Interval) -> bool {
+        self.hi.value <= other.lo.value
+    }
+
+    /// The boolean resulting from comparing values of the intervals
+    fn compare(op: ArithLogOp, lhs: &Interval, rhs: &Interval) -> Interval {
+        use ArithLogOp::*;
+        let known = match op {
+            Lt | Gt => {
+                let (lhs, rhs) = if op == Lt { (lhs, rhs) } else { (rhs, lhs) };
+                if lhs.certainly_lt(rhs) {
+                    Some(true)
+                } else if rhs.certainly_le(lhs) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Le | Ge => {
+                let (lhs, rhs) = if op == Le { (lhs, rhs) } else { (rhs, lhs) };
+                if lhs.certainly_le(rhs) {
+                    Some(true)
+                } else if rhs.certainly_lt(lhs) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Eq | Ne => {
+                let equal = if lhs.intersect(rhs).is_empty() {
+                    Some(false)
+                } else if lhs.lo.value == lhs.hi.value && lhs == rhs {
+                    Some(true)
+                } else {
+                    None
+                };
+                if op == Eq {
+                    equal
+                } else {
+                    equal.map(|equal| !equal)
+                }
+            }
+            _ => None,
+        };
+        Interval::boolean(known)
+    }
+
+    /// The values `x` satisfying `x op value`
+    fn satisfying(op: ArithLogOp, value: f64) -> Interval {
+        use ArithLogOp::*;
+        let full = Interval::full();
+        match op {
+            Eq => Interval::singleton(value),
+            Lt => Interval { hi: Bound::exclusive(value), ..full },
+            Le => Interval { hi: Bound::inclusive(value), ..full },
+            Gt => Interval { lo: Bound::exclusive(value), ..full },
+            Ge => Interval { lo: Bound::inclusive(value), ..full },
+            _ => full,
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.is_empty() {
+            return write!(f, "{{}}");
+        }
+        write!(
+            f,
+            "{}{}, {}{}",
+            if self.lo.inclusive && self.lo.value.is_finite() { "[" } else { "(" },
+            self.lo.value,
+            self.hi.value,
+            if self.hi.inclusive && self.hi.value.is_finite() { "]" } else { ")" }
+        )
+    }
+}
+
+/// Computes the values an input stream can assume, considering its type and its assumption.
+pub(crate) fn assumed_range(input: &InputStream) -> Interval {
+    let range = Interval::of_type(&input.ty);
+    match input.assumption.as_ref() {
+        Some(assumption) => range.intersect(&constrain(assumption, input.reference)),
+        None => range,
+    }
+}
+
+/// Warns about assumptions of input streams that cannot be satisfied by any value.
+pub(crate) fn check_assumptions(ir: &RTLolaIR, spec: &RTLolaAst, handler: &Handler) {
+    for input in &ir.inputs {
+        if input.assumption.is_some() && assumed_range(input).is_empty() {
+            warn_about(
+                ir,
+                spec,
+                handler,
+                input.reference,
+                &format!(
+                    "the assumption of input `{}` can never hold, every value of `{}` will be rejected",
+                    input.name, input.name
+                ),
+                "no value satisfies this assumption",
+            );
+        }
+    }
+}
+
+/// Warns about unsatisfiable assumptions, possible runtime errors, and triggers that never or always fire.
+/// The warnings point to the declarations in `spec`, from which `ir` was lowered.
+pub(crate) fn analyze(ir: &RTLolaIR, spec: &RTLolaAst, handler: &Handler) {
+    check_assumptions(ir, spec, handler);
+    RangeAnalysis::run(ir).report(spec, handler);
+}
+
+/// The span of the expression defining the given stream, or of the assumption of an input stream.
+fn span_of(ir: &RTLolaIR, spec: &RTLolaAst, stream: StreamReference) -> Option<Span> {
+    match stream {
+        StreamReference::InRef(ix) => spec
+            .inputs
+            .iter()
+            .find(|input| input.name.name == ir.inputs[ix].name)
+            .map(|input| input.assumption.as_ref().map_or(input.span, |assumption| assumption.span)),
+        StreamReference::OutRef(ix) => match ir.triggers.iter().position(|trigger| trigger.reference == stream) {
+            // triggers are lowered in the order of the specification
+            Some(position) => spec.trigger.get(position).map(|trigger| trigger.expression.span),
+            None => spec
+                .outputs
+                .iter()
+                .find(|output| output.name.name == ir.outputs[ix].name)
+                .map(|output| output.expression.span),
+        },
+    }
+}
+
+/// Emits a warning, pointing to the given stream if its declaration is found.
+fn warn_about(ir: &RTLolaIR, spec: &RTLolaAst, handler: &Handler, stream: StreamReference, message: &str, label: &str) {
+    match span_of(ir, spec, stream) {
+        Some(span) => handler.warn_with_span(message, LabeledSpan::new(span, label, true)),
+        None => handler.warn(message),
+    }
+}
+
+/// A possible runtime error detected by the range analysis
+#[derive(Debug, Clone, PartialEq)]
+enum Finding {
+    DivisionByZero,
+    Overflow(Type),
+}
+
+/// Knowledge about the current values of streams under which an expression is evaluated, e.g., in the branches of an if-then-else
+#[derive(Debug, Clone, Default)]
+struct Facts {
+    ranges: Vec<(StreamReference, Interval)>,
+    nonzero: Vec<StreamReference>,
+}
+
+impl Facts {
+    /// The facts known if the condition evaluates to `holds`
+    fn of(condition: &Expression, holds: bool) -> Facts {
+        use ArithLogOp::*;
+        match &condition.kind {
+            ExpressionKind::ArithLog(Not, args, _) => Facts::of(&args[0], !holds),
+            ExpressionKind::ArithLog(And, args, _) if holds => {
+                Facts::of(&args[0], true).and(&Facts::of(&args[1], true))
+            }
+            ExpressionKind::ArithLog(Or, args, _) if !holds => {
+                Facts::of(&args[0], false).and(&Facts::of(&args[1], false))
+            }
+            ExpressionKind::ArithLog(op @ (Eq | Ne | Lt | Le | Gt | Ge), args, _) => {
+                let op = if holds { *op } else { negate(*op) };
+                let (target, op, value) =
+                    if let (Some(target), Some(value)) = (stream_of(&args[0]), constant_value(&args[1])) {
+                        (target, op, value)
+                    } else if let (Some(value), Some(target)) = (constant_value(&args[0]), stream_of(&args[1])) {
+                        (target, flip(op), value)
+                    } else {
+                        return Facts::default();
+                    };
+                let mut facts = Facts::default();
+                match op {
+                    Ne if value == 0.0 => facts.nonzero.push(target),
+                    Ne => {}
+                    op => facts.ranges.push((target, Interval::satisfying(op, value))),
+                }
+                facts
+            }
+            _ => Facts::default(),
+        }
+    }
+
+    fn and(&self, other: &Facts) -> Facts {
+        Facts {
+            ranges: self.ranges.iter().chain(&other.ranges).cloned().collect(),
+            nonzero: self.nonzero.iter().chain(&other.nonzero).cloned().collect(),
+        }
+    }
+
+    fn refine(&self, target: StreamReference, range: Interval) -> Interval {
+        self.ranges.iter().filter(|(sr, _)| *sr == target).fold(range, |range, (_, fact)| range.intersect(fact))
+    }
+
+    fn is_nonzero(&self, expr: &Expression) -> bool {
+        stream_of(expr).is_some_and(|target| self.nonzero.contains(&target))
+    }
+}
+
+/// The ranges of all streams of a specification
+pub(crate) struct RangeAnalysis<'a> {
+    ir: &'a RTLolaIR,
+    inputs: Vec<Interval>,
+    outputs: Vec<Interval>,
+    // Facts about inputs guaranteed by their assumptions
+    assumed: Facts,
+}
+
+/// The number of rounds after which the ranges of streams that did not stabilize are widened to their types
+const WIDENING_ROUND: usize = 8;
+
+impl<'a> RangeAnalysis<'a> {
+    /// Computes the ranges of all output streams as the least fixed point of their expressions.
+    pub(crate) fn run(ir: &'a RTLolaIR) -> Self {
+        let assumed = ir
+            .inputs
+            .iter()
+            .filter_map(|input| input.assumption.as_ref())
+            .fold(Facts::default(), |facts, assumption| facts.and(&Facts::of(assumption, true)));
+        let mut analysis = RangeAnalysis {
+            ir,
+            inputs: ir.inputs.iter().map(assumed_range).collect(),
+            outputs: vec![Interval::empty(); ir.outputs.len()],
+            assumed,
+        };
+        let mut round = 0;
+        loop {
+            let mut changed = false;
+            for (ix, output) in ir.outputs.iter().enumerate() {
+                let range = analysis.outputs[ix].hull(&analysis.eval(&output.expr, &analysis.assumed, &mut Vec::new()));
+                if range != analysis.outputs[ix] {
+                    changed = true;
+                    analysis.outputs[ix] =
+                        if round < WIDENING_ROUND { range } else { range.hull(&Interval::of_type(&output.ty)) };
+                }
+            }
+            if !changed {
+                return analysis;
+            }
+            round += 1;
+        }
+    }
+
+    /// The range of values of the given stream
+    pub(crate) fn range_of(&self, stream: StreamReference) -> Interval {
+        match stream {
+            StreamReference::InRef(ix) => self.inputs[ix],
+            StreamReference::OutRef(ix) => self.outputs[ix],
+        }
+    }
+
+    fn report(&self, spec: &RTLolaAst, handler: &Handler) {
+        for (ix, output) in self.ir.outputs.iter().enumerate() {
+            let trigger = self.ir.triggers.iter().find(|trigger| trigger.reference.out_ix() == ix);
+            let subject = match trigger {
+                Some(trigger) => match &trigger.name {
+                    Some(name) => format!("trigger `{}`", name),
+                    None => format!("trigger \"{}\"", trigger.message),
+                },
+                None => format!("stream `{}`", output.name),
+            };
+            let mut findings = Vec::new();
+            self.eval(&output.expr, &self.assumed, &mut findings);
+            let mut reported = Vec::new();
+            for finding in findings {
+                if reported.contains(&finding) {
+                    continue;
+                }
+                let warn =
+                    |message: &str, label: &str| warn_about(self.ir, spec, handler, output.reference, message, label);
+                match &finding {
+                    Finding::DivisionByZero => {
+                        warn(&format!("possible division by zero in {}", subject), "the divisor may be zero")
+                    }
+                    Finding::Overflow(ty) => warn(
+                        &format!("possible overflow of `{}` in {}", ty, subject),
+                        &format!("the result may exceed the range of `{}`", ty),
+                    ),
+                }
+                reported.push(finding);
+            }
+            if trigger.is_some() {
+                let warn =
+                    |message: &str, label: &str| warn_about(self.ir, spec, handler, output.reference, message, label);
+                if self.outputs[ix].is_certainly(false) {
+                    warn(&format!("{} can never fire", subject), "this condition is always false");
+                } else if self.outputs[ix].is_certainly(true) {
+                    warn(&format!("{} fires whenever it is evaluated", subject), "this condition is always true");
+                }
+            }
+        }
+    }
+
+    /// Over-approximates the values of the expression, recording possible runtime errors in `findings`.
+    fn eval(&self, expr: &Expression, facts: &Facts, findings: &mut Vec<Finding>) -> Interval {
+        use ExpressionKind::*;
+        let range = match &expr.kind {
+            LoadConstant(Constant::Bool(value)) => Interval::boolean(Some(*value)),
+            LoadConstant(_) => constant_value(expr).map_or_else(Interval::full, Interval::singleton),
+            ArithLog(op, args, _) | OptionalArithLog(op, args, _) => {
+                self.eval_arith_log(*op, args, &expr.ty, facts, findings)
+            }
+            StreamAccess(target, StreamAccessKind::Sync) => facts.refine(*target, self.range_of(*target)),
+            StreamAccess(target, _) | OffsetLookup { target, .. } => self.range_of(*target),
+            WindowLookup(window) => {
+                let window = self.ir.get_window(*window);
+                match window.op {
+                    WindowOperation::Count => Interval::closed(0.0, f64::INFINITY),
+                    WindowOperation::Min | WindowOperation::Max | WindowOperation::Average => {
+                        self.range_of(window.target)
+                    }
+                    WindowOperation::Conjunction | WindowOperation::Disjunction => Interval::boolean(None),
+                    _ => Interval::full(),
+                }
+            }
+            Ite { condition, consequence, alternative } => {
+                let condition_range = self.eval(condition, facts, findings);
+                let mut range = Interval::empty();
+                if !condition_range.is_empty() && !condition_range.is_certainly(false) {
+                    range = range.hull(&self.eval(consequence, &facts.and(&Facts::of(condition, true)), findings));
+                }
+                if !condition_range.is_empty() && !condition_range.is_certainly(true) {
+                    range = range.hull(&self.eval(alternative, &facts.and(&Facts::of(condition, false)), findings));
+                }
+                range
+            }
+            Function(name, args, _) => {
+                let args: Vec<Interval> = args.iter().map(|arg| self.eval(arg, facts, findings)).collect();
+                match (name.as_str(), args.as_slice()) {
+                    (_, args) if args.iter().any(Interval::is_empty) => Interval::empty(),
+                    ("abs", [arg]) if arg.lo.value >= 0.0 => *arg,
+                    ("abs", [arg]) if arg.hi.value <= 0.0 => arg.neg(),
+                    ("abs", [arg]) => Interval::closed(0.0, arg.hi.value.max(-arg.lo.value)),
+                    ("min", [lhs, rhs]) => {
+                        Interval::closed(lhs.lo.value.min(rhs.lo.value), lhs.hi.value.min(rhs.hi.value))
+                    }
+                    ("max", [lhs, rhs]) => {
+                        Interval::closed(lhs.lo.value.max(rhs.lo.value), lhs.hi.value.max(rhs.hi.value))
+                    }
+                    ("clamp", [arg, lo, hi]) => Interval::closed(
+                        arg.lo.value.max(lo.lo.value).min(hi.lo.value),
+                        arg.hi.value.max(lo.hi.value).min(hi.hi.value),
+                    ),
+                    _ => Interval::full(),
+                }
+            }
+            Convert { expr, .. } => self.eval(expr, facts, findings),
+            Default { expr, default } => self.eval(expr, facts, findings).hull(&self.eval(default, facts, findings)),
+            Tuple(elements) => {
+                elements.iter().for_each(|element| {
+                    self.eval(element, facts, findings);
+                });
+                Interval::full()
+            }
+            TupleAccess(inner, _) => {
+                self.eval(inner, facts, findings);
+                Interval::full()
+            }
+            Let { value, body, .. } => {
+                self.eval(value, facts, findings);
+                self.eval(body, facts, findings)
+            }
+            LocalVariable(_) => Interval::full(),
+        };
+        let range = if matches!(expr.ty, Type::Int(_) | Type::UInt(_)) { range.tighten_integer() } else { range };
+        // values exceeding an integer type wrap around
+        let bounds = Interval::of_type(&expr.ty);
+        if range.is_within(&bounds) {
+            range
+        } else {
+            bounds
+        }
+    }
+
+    fn eval_arith_log(
+        &self,
+        op: ArithLogOp,
+        args: &[Expression],
+        ty: &Type,
+        facts: &Facts,
+        findings: &mut Vec<Finding>,
+    ) -> Interval {
+        use ArithLogOp::*;
+        match op {
+            And | Or => {
+                let lhs = self.eval(&args[0], facts, findings);
+                // the right operand is only evaluated if the left one does not determine the result
+                if lhs.is_empty() || lhs.is_certainly(op == Or) {
+                    return lhs;
+                }
+                let rhs = self.eval(&args[1], &facts.and(&Facts::of(&args[0], op == And)), findings);
+                if rhs.is_empty() {
+                    rhs
+                } else if op == And {
+                    lhs.and(&rhs)
+                } else {
+                    lhs.or(&rhs)
+                }
+            }
+            _ => {
+                let operands: Vec<Interval> = args.iter().map(|arg| self.eval(arg, facts, findings)).collect();
+                if operands.iter().any(Interval::is_empty) {
+                    return Interval::empty();
+                }
+                let integer = matches!(ty, Type::Int(_) | Type::UInt(_));
+                let result = match op {
+                    Not => operands[0].not(),
+                    Neg => operands[0].neg(),
+                    Add => operands[0].add(&operands[1]),
+                    Sub => operands[0].sub(&operands[1]),
+                    Mul => operands[0].mul(&operands[1]),
+                    Div | Rem => {
+                        // integers panic on a zero divisor, floats on dividing zero by zero
+                        if operands[1].contains(0.0)
+                            && !facts.is_nonzero(&args[1])
+                            && (integer || operands[0].contains(0.0))
+                        {
+                            findings.push(Finding::DivisionByZero);
+                        }
+                        if op == Div {
+                            operands[0].div(&operands[1], integer)
+                        } else {
+                            operands[0].rem(&operands[1])
+                        }
+                    }
+                    Eq | Ne | Lt | Le | Gt | Ge => Interval::compare(op, &operands[0], &operands[1]),
+                    Pow | BitAnd | BitOr | BitXor | BitNot | Shl | Shr => Interval::of_type(ty),
+                    And | Or => unreachable!("handled above"),
+                };
+                if matches!(op, Neg | Add | Sub | Mul) && is_narrow(ty) && !result.is_within(&Interval::of_type(ty)) {
+                    findings.push(Finding::Overflow(ty.clone()));
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Integer types with less than 64 bits
+fn is_narrow(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Int(IntTy::I8)
+            | Type::Int(IntTy::I16)
+            | Type::Int(IntTy::I32)
+            | Type::UInt(UIntTy::U8)
+            | Type::UInt(UIntTy::U16)
+            | Type::UInt(UIntTy::U32)
+    )
+}
+
+/// Over-approximates the values of `target` satisfying the condition.
+fn constrain(condition: &Expression, target: StreamReference) -> Interval {
+    use ArithLogOp::*;
+    match &condition.kind {
+        ExpressionKind::LoadConstant(Constant::Bool(true)) => Interval::full(),
+        ExpressionKind::LoadConstant(Constant::Bool(false)) => Interval::empty(),
+        ExpressionKind::ArithLog(And, args, _) => {
+            args.iter().fold(Interval::full(), |acc, arg| acc.intersect(&constrain(arg, target)))
+        }
+        ExpressionKind::ArithLog(Or, args, _) => {
+            args.iter().fold(Interval::empty(), |acc, arg| acc.hull(&constrain(arg, target)))
+        }
+        ExpressionKind::ArithLog(op @ (Eq | Lt | Le | Gt | Ge), args, _) => {
+            if let (Some(true), Some(value)) = (stream_of(&args[0]).map(|sr| sr == target), constant_value(&args[1])) {
+                Interval::satisfying(*op, value)
+            } else if let (Some(value), Some(true)) =
+                (constant_value(&args[0]), stream_of(&args[1]).map(|sr| sr == target))
+            {
+                Interval::satisfying(flip(*op), value)
+            } else {
+                Interval::full()
+            }
+        }
+        _ => Interval::full(),
+    }
+}
+
+/// The comparison resulting from swapping the operands
+fn flip(op: ArithLogOp) -> ArithLogOp {
+    use ArithLogOp::*;
+    match op {
+        Lt => Gt,
+        Le => Ge,
+        Gt => Lt,
+        Ge => Le,
+        op => op,
+    }
+}
+
+/// The comparison holding if the given one does not
+fn negate(op: ArithLogOp) -> ArithLogOp {
+    use ArithLogOp::*;
+    match op {
+        Eq => Ne,
+        Ne => Eq,
+        Lt => Ge,
+        Le => Gt,
+        Gt => Le,
+        Ge => Lt,
+        op => op,
+    }
+}
+
+/// The stream whose current value is the value of the expression
+fn stream_of(expr: &Expression) -> Option<StreamReference> {
+    match &expr.kind {
+        ExpressionKind::StreamAccess(sr, StreamAccessKind::Sync) => Some(*sr),
+        ExpressionKind::Convert { expr, .. } => stream_of(expr),
+        _ => None,
+    }
+}
+
+/// The numeric value of a constant expression
+pub(crate) fn constant_value(expr: &Expression) -> Option<f64> {
+    match &expr.kind {
+        ExpressionKind::LoadConstant(Constant::Int(i)) => Some(*i as f64),
+        ExpressionKind::LoadConstant(Constant::UInt(u)) => Some(*u as f64),
+        ExpressionKind::LoadConstant(Constant::Float(f)) => Some(*f),
+        ExpressionKind::ArithLog(ArithLogOp::Neg, args, _) => constant_value(&args[0]).map(|value| -value),
+        ExpressionKind::Convert { expr, .. } => constant_value(expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::SourceMapper;
+    use crate::FrontendConfig;
+    use std::path::PathBuf;
+
+    fn lowered(spec: &str) -> (RTLolaAst, RTLolaIR) {
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = crate::parse::parse(spec, &handler, FrontendConfig::default()).expect("spec was invalid");
+        let report = crate::analysis::analyze(&ast, &handler, FrontendConfig::default()).expect("spec was invalid");
+        let ir = crate::ir::lowering::Lowering::new(&ast, &report).lower();
+        (ast, ir)
+    }
+
+    /// Returns the handler to which the range analysis reported its warnings
+    fn analyzed(spec: &str) -> Handler {
+        let (ast, ir) = lowered(spec);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        analyze(&ir, &ast, &handler);
+        handler
+    }
+
+    fn range_of_first_input(spec: &str) -> Interval {
+        let ir = crate::parse("stdin", spec, FrontendConfig::default()).expect("spec was invalid");
+        assumed_range(&ir.inputs[0])
+    }
+
+    #[test]
+    fn assumed_ranges() {
+        let range = range_of_first_input("input alt: Float64 assume alt >= 0.0 && alt < 10000.0");
+        assert_eq!(range, Interval { lo: Bound::inclusive(0.0), hi: Bound::exclusive(10000.0) });
+        let range = range_of_first_input("input a: Int64 assume -5 <= a && a != 0");
+        assert_eq!(range, Interval { lo: Bound::inclusive(-5.0), hi: Bound::inclusive(i64::MAX as f64) });
+        let range = range_of_first_input("input a: UInt64 assume a < 3 || a == 10");
+        assert_eq!(range, Interval { lo: Bound::inclusive(0.0), hi: Bound::inclusive(10.0) });
+        let range = range_of_first_input("input a: UInt64 assume a < 3 && a > 3");
+        assert!(range.is_empty());
+        let range = range_of_first_input("input a: Float64");
+        assert_eq!(range, Interval::full());
+    }
+
+    #[test]
+    fn warn_about_unsatisfiable_assumption() {
+        let num_warnings = |spec: &str| analyzed(spec).emitted_warnings();
+        assert_eq!(num_warnings("input a: Float64 assume a < 0.0 && a > 1.0"), 1);
+        assert_eq!(num_warnings("input a: UInt8 assume a < 0"), 1);
+        assert_eq!(num_warnings("input a: Float64 assume a <= 0.0 && a >= 0.0"), 0);
+    }
+
+    fn num_warnings(spec: &str) -> usize {
+        analyzed(spec).emitted_warnings()
+    }
+
+    #[test]
+    fn warnings_point_to_streams() {
+        let spec = "input a: Int64\ninput b: Int64 assume b < 0 && b > 0\noutput c := a / b\ntrigger a > 5 && a < 3";
+        let (ast, ir) = lowered(spec);
+        let text = |stream| {
+            let span = span_of(&ir, &ast, stream).expect("stream is declared");
+            &spec[span.start..span.end]
+        };
+        assert_eq!(text(StreamReference::InRef(1)), "b < 0 && b > 0");
+        assert_eq!(text(StreamReference::OutRef(0)), "a / b");
+        assert_eq!(text(ir.triggers[0].reference), "a > 5 && a < 3");
+    }
+
+    #[test]
+    fn warn_about_division_by_zero() {
+        assert_eq!(num_warnings("input a: Int64\ninput b: Int64\noutput c := a / b"), 1);
+        assert_eq!(num_warnings("input a: Int64\ninput b: Int64 assume b != 0\noutput c := a / b"), 0);
+        assert_eq!(num_warnings("input a: Int64\ninput b: Int64 assume b > 0\noutput c := a % (b - 1)"), 1);
+        assert_eq!(num_warnings("input a: Int64\ninput b: Int64\noutput c := if b = 0 then 0 else a / b"), 0);
+        assert_eq!(num_warnings("input a: Int64\ninput b: Int64\noutput c := b != 0 && a / b > 2"), 0);
+        assert_eq!(num_warnings("input a: Float64 assume a >= 1.0\noutput c := 1.0 / a"), 0);
+        assert_eq!(num_warnings("input a: Float64\noutput c := 1.0 / a"), 0);
+        assert_eq!(num_warnings("input a: Float64\noutput c := a / a"), 1);
+    }
+
+    #[test]
+    fn warn_about_narrow_overflow() {
+        assert_eq!(num_warnings("input a: UInt8\noutput b: UInt8 := a + 1"), 1);
+        assert_eq!(num_warnings("input a: UInt8 assume a < 255\noutput b: UInt8 := a + 1"), 0);
+        assert_eq!(num_warnings("input a: Int16 assume a >= -100 && a <= 100\noutput b: Int16 := a * a"), 0);
+        assert_eq!(num_warnings("input a: Int16 assume a >= -200 && a <= 200\noutput b: Int16 := a * a"), 1);
+        assert_eq!(num_warnings("input a: Int64\noutput b: Int64 := a + 1"), 0);
+        assert_eq!(num_warnings("import math\ninput a: UInt8\noutput b: UInt8 := clamp(a, 0, 254) + 1"), 0);
+    }
+
+    #[test]
+    fn warn_about_vacuous_triggers() {
+        assert_eq!(num_warnings("input a: UInt16 assume a < 100\ntrigger a > 300"), 1);
+        assert_eq!(num_warnings("input a: UInt8\ntrigger a >= 0"), 1);
+        assert_eq!(num_warnings("input a: Float64 assume a > 0.0\ntrigger low := a < 0.0 || a = 0.0"), 1);
+        assert_eq!(num_warnings("input a: Float64\ntrigger a > 3.0"), 0);
+    }
+
+    #[test]
+    fn output_ranges() {
+        let ir = crate::parse(
+            "stdin",
+            "input a: UInt8\noutput b := if a > 10 then 10 else a\noutput c: UInt64 := c.offset(by: -1).defaults(to: 0) + 1",
+            FrontendConfig::default(),
+        )
+        .expect("spec was invalid");
+        let analysis = RangeAnalysis::run(&ir);
+        assert_eq!(analysis.range_of(StreamReference::OutRef(0)), Interval::closed(0.0, 10.0));
+        assert_eq!(analysis.range_of(StreamReference::OutRef(1)), Interval::of_type(&Type::UInt(UIntTy::U64)));
+    }
+
+    #[test]
+    fn interval_operations() {
+        let a = Interval::satisfying(ArithLogOp::Ge, 1.0);
+        let b = Interval::satisfying(ArithLogOp::Lt, 1.0);
+        assert!(a.intersect(&b).is_empty());
+        assert_eq!(a.hull(&b), Interval::full());
+        assert_eq!(format!("{}", a.intersect(&Interval::satisfying(ArithLogOp::Le, 2.0))), "[1, 2]");
+        assert_eq!(format!("{}", b), "(-inf, 1)");
+        assert_eq!(format!("{}", Interval::empty()), "{}");
+        let c = Interval::closed(-2.0, 3.0);
+        assert_eq!(c.mul(&c), Interval::closed(-6.0, 9.0));
+        assert_eq!(Interval::closed(7.0, 9.0).div(&Interval::closed(2.0, 4.0), true), Interval::closed(0.0, 4.5));
+        assert_eq!(Interval::closed(-7.0, 9.0).rem(&Interval::closed(2.0, 4.0)), Interval::closed(-4.0, 4.0));
+        assert!(Interval::compare(ArithLogOp::Lt, &b, &a).is_certainly(true));
+        assert!(Interval::compare(ArithLogOp::Eq, &a, &b).is_certainly(false));
+    }
+}
@@ -1,5 +1,6 @@
 //! Parser for the RTLola language.
 
+#![recursion_limit = "256"] // the declarations of the standard library exceed the default for `lazy_static!`
 #![forbid(unused_must_use)] // disallow discarding errors
 #![warn(
     missing_docs,
@@ -20,6 +21,7 @@ pub mod ir;
 mod parse;
 mod reporting;
 mod stdlib;
+mod stl;
 pub mod ty;
 
 #[cfg(test)]
@@ -80,7 +82,34 @@ pub fn parse(filename: &str, spec_str: &str, config: FrontendConfig) -> Result<R
     };
 
     let analysis_result = analysis::analyze(&spec, &handler, config);
-    analysis_result
+    let ir = analysis_result
         .map(|report| ir::lowering::Lowering::new(&spec, &report).lower())
-        .map_err(|_| "Analysis failed due to errors in the specification".to_string())
+        .map_err(|_| "Analysis failed due to errors in the specification".to_string())?;
+    ir::range::analyze(&ir, &spec, &handler);
+    Ok(ir)
+}
+
+/**
+Translates bounded Signal Temporal Logic (STL) requirements into a RTLola specification.
+
+The string passed in as `stl_str` should be the content of the file specified by `filename`, containing one requirement per line, e.g., `alt_gear: G[0,10](alt > 5 -> F[0,2] gear_down)`.
+All streams of the resulting specification are evaluated periodically with the sampling `frequency` in Hz, which has to divide 1000Hz.
+Each requirement becomes a trigger reporting its violation.
+Requirements with future operators can only be monitored offline as their verdicts are delayed; such requirements are reported as warnings.
+*/
+pub fn import_stl(filename: &str, stl_str: &str, frequency: u32) -> Result<RTLolaAst, String> {
+    let mapper = parse::SourceMapper::new(std::path::PathBuf::from(filename), stl_str);
+    let handler = reporting::Handler::new(mapper);
+    let spec_str = match stl::translate(stl_str, &handler, frequency) {
+        Ok(spec_str) => spec_str,
+        Err(e) => return Err(format!("error: invalid syntax:\n{}", e)),
+    };
+    if handler.contains_error() {
+        return Err("Translation failed due to errors in the requirements".to_string());
+    }
+
+    let mapper = parse::SourceMapper::new(std::path::PathBuf::from(filename), &spec_str);
+    let handler = reporting::Handler::new(mapper);
+    parse::parse(&spec_str, &handler, FrontendConfig::default())
+        .map_err(|e| format!("error: invalid translation:\n{}", e))
 }
@@ -1,5 +1,7 @@
 //! This module contains the parser for the Lola Language.
 
+use super::ast::expansion::Instantiation;
+use super::ast::past_time::PastTimeDesugaring;
 use super::ast::*;
 use crate::reporting::{Handler, LabeledSpan};
 use crate::FrontendConfig;
@@ -8,7 +10,7 @@ use pest::iterators::{Pair, Pairs};
 use pest::prec_climber::{Assoc, Operator, PrecClimber};
 use pest::Parser;
 use pest_derive::Parser;
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
 
 #[derive(Parser)]
 #[grammar = "lola.pest"]
@@ -31,6 +33,7 @@ lazy_static! {
         use self::Rule::*;
 
         PrecClimber::new(vec![
+            Operator::new(Since, Left),
             Operator::new(Or, Left),
             Operator::new(And, Left),
             Operator::new(BitOr, Left),
@@ -64,12 +67,18 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         assert!(pairs.clone().count() == 1, "Spec must not be empty.");
         let spec_pair = pairs.next().unwrap();
         assert!(spec_pair.as_rule() == Rule::Spec);
+        let mut templates: HashMap<String, Pair<'_, Rule>> = HashMap::new();
+        let mut instances = Vec::new();
         for pair in spec_pair.into_inner() {
             match pair.as_rule() {
                 Rule::ImportStmt => {
                     let import = self.parse_import(pair);
                     self.spec.imports.push(import);
                 }
+                Rule::Annotation => {
+                    let annotation = self.parse_annotation(pair);
+                    self.spec.annotations.push(annotation);
+                }
                 Rule::ConstantStream => {
                     let constant = self.parse_constant(pair);
                     self.spec.constants.push(Rc::new(constant));
@@ -90,11 +99,118 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                     let type_decl = self.parse_type_declaration(pair);
                     self.spec.type_declarations.push(type_decl);
                 }
+                Rule::TemplateDecl => {
+                    let name =
+                        self.parse_ident(&pair.clone().into_inner().next().expect("mismatch between grammar and AST"));
+                    if let Some(previous) = templates.get(&name.name) {
+                        let mut builder = self.handler.build_error_with_span(
+                            &format!("the template `{}` is defined multiple times", name.name),
+                            LabeledSpan::new(name.span, &format!("`{}` redefined here", name.name), true),
+                        );
+                        builder.add_span_with_label(
+                            previous.as_span().into(),
+                            &format!("previous definition of the template `{}` here", name.name),
+                            false,
+                        );
+                        builder.emit();
+                    } else {
+                        templates.insert(name.name, pair);
+                    }
+                }
+                Rule::TemplateInstance => instances.push(pair),
                 Rule::EOI => {}
                 _ => unreachable!(),
             }
         }
-        Ok(self.spec)
+        for instance in instances {
+            self.instantiate_template(&templates, instance);
+        }
+        let mut spec = std::mem::replace(&mut self.spec, RTLolaAst::new());
+        PastTimeDesugaring::new(self.handler, &|| self.next_id()).desugar(&mut spec);
+        Ok(spec)
+    }
+
+    /**
+     * Expands a `Rule::TemplateInstance` into the streams declared in the instantiated template.
+     * The template parameters are replaced by the given streams, and the declared streams are prefixed with the
+     * instance name, e.g., `high` becomes `motor_1::high`.
+     */
+    fn instantiate_template(&mut self, templates: &HashMap<String, Pair<'_, Rule>>, pair: Pair<'_, Rule>) {
+        assert_eq!(pair.as_rule(), Rule::TemplateInstance);
+        let site: Span = pair.as_span().into();
+        let mut pairs = pair.into_inner();
+        let template_name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+        let args: Vec<Ident> = pairs
+            .next()
+            .expect("mismatch between grammar and AST")
+            .into_inner()
+            .map(|arg| self.parse_ident(&arg))
+            .collect();
+        let instance_name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+
+        let template = match templates.get(&template_name.name) {
+            Some(template) => template,
+            None => {
+                self.handler.error_with_span(
+                    &format!("cannot find template `{}`", template_name.name),
+                    LabeledSpan::new(template_name.span, "not found in this scope", true),
+                );
+                return;
+            }
+        };
+        let mut template_pairs = template.clone().into_inner();
+        template_pairs.next().expect("mismatch between grammar and AST");
+        let params: Vec<Ident> = template_pairs
+            .next()
+            .expect("mismatch between grammar and AST")
+            .into_inner()
+            .map(|param| self.parse_ident(&param))
+            .collect();
+        if params.len() != args.len() {
+            self.handler.error_with_span(
+                &format!(
+                    "template `{}` expects {} argument(s), but {} were given",
+                    template_name.name,
+                    params.len(),
+                    args.len()
+                ),
+                LabeledSpan::new(site, &format!("expected {} argument(s)", params.len()), true),
+            );
+            return;
+        }
+
+        let mut renaming: HashMap<String, String> =
+            params.into_iter().zip(args).map(|(param, arg)| (param.name, arg.name)).collect();
+        // output streams and named triggers
+        for declaration in template_pairs.clone() {
+            let first = match declaration.as_rule() {
+                Rule::OutputStream | Rule::Trigger => {
+                    declaration.into_inner().next().expect("mismatch between grammar and AST")
+                }
+                _ => continue,
+            };
+            if first.as_rule() == Rule::Ident {
+                let name = self.parse_ident(&first);
+                renaming.insert(name.name.clone(), format!("{}::{}", instance_name.name, name.name));
+            }
+        }
+        let offset = self.handler.register_expansion(&template_name.name, template.as_span().into(), site);
+        let mut instantiation = Instantiation::new(renaming, offset);
+        for declaration in template_pairs {
+            match declaration.as_rule() {
+                Rule::OutputStream => {
+                    let mut output = self.parse_output(declaration);
+                    instantiation.instantiate_output(&mut output);
+                    self.spec.outputs.push(Rc::new(output));
+                }
+                Rule::Trigger => {
+                    let mut trigger = self.parse_trigger(declaration);
+                    instantiation.instantiate_trigger(&mut trigger);
+                    self.spec.trigger.push(Rc::new(trigger));
+                }
+                _ => unreachable!(),
+            }
+        }
     }
 
     fn parse_import(&self, pair: Pair<Rule>) -> Import {
@@ -105,6 +221,14 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         Import { name, id: self.next_id(), span }
     }
 
+    fn parse_annotation(&self, pair: Pair<Rule>) -> Annotation {
+        assert_eq!(pair.as_rule(), Rule::Annotation);
+        let span = pair.as_span().into();
+        let mut pairs = pair.into_inner();
+        let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+        Annotation { name, id: self.next_id(), span }
+    }
+
     /**
      * Transforms a `Rule::ConstantStream` into `Constant` AST node.
      * Panics if input is not `Rule::ConstantStream`.
@@ -130,11 +254,12 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
      * - `Rule::Ident`
      * - (`Rule::ParamList`)?
      * - `Rule::Type`
+     * - (`Rule::InputAssumption`)?
      */
     fn parse_inputs(&self, pair: Pair<'_, Rule>) -> Vec<Input> {
         assert_eq!(pair.as_rule(), Rule::InputStream);
         let mut inputs = Vec::new();
-        let mut pairs = pair.into_inner();
+        let mut pairs = pair.into_inner().peekable();
         while let Some(pair) = pairs.next() {
             let start = pair.as_span().start();
             let name = self.parse_ident(&pair);
@@ -155,12 +280,22 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
             } else {
                 Vec::new()
             };
-            let end = pair.as_span().end();
+            let mut end = pair.as_span().end();
             let ty = self.parse_type(pair);
+            let assumption = match pairs.peek() {
+                Some(pair) if pair.as_rule() == Rule::InputAssumption => {
+                    let pair = pairs.next().expect("checked by peek");
+                    end = pair.as_span().end();
+                    let expr = pair.into_inner().next().expect("mismatch between grammar and AST");
+                    Some(self.build_expression_ast(expr.into_inner()))
+                }
+                _ => None,
+            };
             inputs.push(Input {
                 id: self.next_id(),
                 name,
                 params: params.into_iter().map(Rc::new).collect(),
+                assumption,
                 ty,
                 span: Span { start, end },
             })
@@ -261,6 +396,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
             termination,
             expression,
             span,
+            hidden: false,
         }
     }
 
@@ -357,6 +493,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         let mut pairs = pair.into_inner();
 
         let mut name = None;
+        let mut severity = None;
         let mut message = None;
 
         let mut pair = pairs.next().expect("mismatch between grammar and AST");
@@ -365,14 +502,74 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
             name = Some(self.parse_ident(&pair));
             pair = pairs.next().expect("mismatch between grammar and AST");
         }
+
+        if let Rule::TriggerSeverity = pair.as_rule() {
+            severity = Some(match pair.as_str() {
+                "info" => TriggerSeverity::Info,
+                "warning" => TriggerSeverity::Warning,
+                "critical" => TriggerSeverity::Critical,
+                _ => unreachable!(),
+            });
+            pair = pairs.next().expect("mismatch between grammar and AST");
+        }
+
+        // Parse the `@ [Expr]` part of trigger declaration
+        let extend = if let Rule::ActivationCondition = pair.as_rule() {
+            let span: Span = pair.as_span().into();
+            let expr = self.build_expression_ast(pair.into_inner());
+            pair = pairs.next().expect("mismatch between grammar and AST");
+            ActivationCondition { expr: Some(expr), id: self.next_id(), span }
+        } else {
+            ActivationCondition { expr: None, id: self.next_id(), span: Span::unknown() }
+        };
+
         let expression = self.build_expression_ast(pair.into_inner());
 
-        if let Some(pair) = pairs.next() {
-            assert_eq!(pair.as_rule(), Rule::String);
-            message = Some(pair.as_str().to_string());
+        let mut message_parts = Vec::new();
+        let mut options = Vec::new();
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::TriggerMessage => {
+                    let quoted = pair.as_str();
+                    message = Some(quoted[1..quoted.len() - 1].to_string());
+                    for part in pair.into_inner() {
+                        match part.as_rule() {
+                            Rule::MessageText => message_parts
+                                .push(MessagePart::Text(part.as_str().replace("{{", "{").replace("}}", "}"))),
+                            Rule::Placeholder => {
+                                let expr = part.into_inner().next().expect("mismatch between grammar and AST");
+                                message_parts
+                                    .push(MessagePart::Placeholder(self.build_expression_ast(expr.into_inner())));
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+                Rule::TriggerOptions => {
+                    options.extend(pair.into_inner().map(|option| self.parse_trigger_option(option)))
+                }
+                _ => unreachable!(),
+            }
         }
 
-        Trigger { id: self.next_id(), name, expression, message, span }
+        Trigger { id: self.next_id(), name, severity, extend, expression, message, message_parts, options, span }
+    }
+
+    fn parse_trigger_option(&self, pair: Pair<'_, Rule>) -> TriggerOption {
+        match pair.as_rule() {
+            Rule::RisingEdge => TriggerOption::RisingEdge(pair.as_span().into()),
+            Rule::Rearm => {
+                let duration = pair.into_inner().next().expect("mismatch between grammar and AST");
+                TriggerOption::Rearm(self.parse_literal(duration))
+            }
+            Rule::RateLimit => {
+                let mut pairs = pair.into_inner();
+                let count = self.parse_literal(pairs.next().expect("mismatch between grammar and AST"));
+                let interval = self.parse_literal(pairs.next().expect("mismatch between grammar and AST"));
+                TriggerOption::Limit(count, interval)
+            }
+            _ => unreachable!(),
+        }
     }
 
     /**
@@ -385,6 +582,14 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         Ident::new(name, pair.as_span().into())
     }
 
+    /**
+     * Transforms a `Rule::Ident` in a let pattern into a `LetBinding` AST node.
+     */
+    fn parse_let_binding(&self, pair: &Pair<'_, Rule>) -> LetBinding {
+        let name = self.parse_ident(pair);
+        LetBinding { span: name.span, name, id: self.next_id() }
+    }
+
     /**
      * Transforms a `Rule::TypeDecl` into `TypeDeclaration` AST node.
      * Panics if input is not `Rule::TypeDecl`.
@@ -423,6 +628,19 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                     return Type::new_simple(self.next_id(), pair.as_str().to_string(), pair.as_span().into());
                 }
                 Rule::Type => tuple.push(self.parse_type(pair)),
+                Rule::NamedTupleType => {
+                    let span = pair.as_span();
+                    let fields = pair
+                        .into_inner()
+                        .map(|field| {
+                            let mut pairs = field.into_inner();
+                            let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+                            let ty = self.parse_type(pairs.next().expect("mismatch between grammar and AST"));
+                            (name, ty)
+                        })
+                        .collect();
+                    return Type::new_named_tuple(self.next_id(), fields, span.into());
+                }
                 Rule::Optional => {
                     let span = pair.as_span();
                     let inner =
@@ -430,6 +648,63 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                     let inner_ty = Type::new_simple(self.next_id(), inner.as_str().to_string(), inner.as_span().into());
                     return Type::new_optional(self.next_id(), inner_ty, span.into());
                 }
+                Rule::SetType => {
+                    let span = pair.as_span();
+                    let inner =
+                        pair.into_inner().next().expect("mismatch between grammar and AST: first argument is a type");
+                    let element_ty = self.parse_type(inner);
+                    return Type::new_set(self.next_id(), element_ty, span.into());
+                }
+                Rule::ListType => {
+                    let span = pair.as_span();
+                    let inner =
+                        pair.into_inner().next().expect("mismatch between grammar and AST: first argument is a type");
+                    let element_ty = self.parse_type(inner);
+                    return Type::new_list(self.next_id(), element_ty, span.into());
+                }
+                Rule::MapType => {
+                    let span = pair.as_span();
+                    let mut pairs = pair.into_inner();
+                    let key_ty = self.parse_type(pairs.next().expect("mismatch between grammar and AST"));
+                    let value_ty = self.parse_type(pairs.next().expect("mismatch between grammar and AST"));
+                    return Type::new_map(self.next_id(), key_ty, value_ty, span.into());
+                }
+                Rule::UnitType => {
+                    let span = pair.as_span();
+                    let mut pairs = pair.into_inner();
+                    let name = pairs.next().expect("mismatch between grammar and AST: first argument is a type");
+                    let ty = Type::new_simple(self.next_id(), name.as_str().to_string(), name.as_span().into());
+                    // factors following a `/` have negated exponents, e.g., `m/s^2` is `m^1 * s^-2`
+                    let mut unit = Vec::new();
+                    let mut sign = 1;
+                    for pair in pairs {
+                        match pair.as_rule() {
+                            Rule::UnitOp => sign = if pair.as_str() == "/" { -1 } else { 1 },
+                            Rule::UnitFactor => {
+                                let mut factor = pair.into_inner();
+                                let symbol = factor.next().expect("mismatch between grammar and AST");
+                                let exponent = match factor.next() {
+                                    Some(exp) => exp.as_str().parse::<i8>().map(i32::from).unwrap_or_else(|_| {
+                                        self.handler.error_with_span(
+                                            "unit exponent out of range",
+                                            LabeledSpan::new(
+                                                exp.as_span().into(),
+                                                "the exponents of units have to be between -128 and 127",
+                                                true,
+                                            ),
+                                        );
+                                        1
+                                    }),
+                                    None => 1,
+                                };
+                                let symbol = Ident::new(symbol.as_str().to_string(), symbol.as_span().into());
+                                unit.push((symbol, sign * exponent));
+                            }
+                            _ => unreachable!("mismatch between grammar and AST"),
+                        }
+                    }
+                    return Type::new_unit(self.next_id(), ty, unit, span.into());
+                }
                 _ => unreachable!("{:?} is not a type, ensured by grammar", pair.as_rule()),
             }
         }
@@ -465,6 +740,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
 
                 Literal::new_numeric(self.next_id(), str_rep, unit, span.into())
             }
+            Rule::SubnetLiteral => Literal::new_subnet(self.next_id(), inner.as_str(), inner.as_span().into()),
             Rule::True => Literal::new_bool(self.next_id(), true, inner.as_span().into()),
             Rule::False => Literal::new_bool(self.next_id(), false, inner.as_span().into()),
             _ => unreachable!(),
@@ -524,6 +800,15 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                 // Reduce function combining `Expression`s to `Expression`s with the correct precs
                 let span = Span { start: lhs.span.start, end: rhs.span.end };
                 let op = match op.as_rule() {
+                    // Temporal, desugared after parsing
+                    Rule::Since => {
+                        let name = FunctionName::new("since".to_string(), &[None, None]);
+                        return Expression::new(
+                            self.next_id(),
+                            ExpressionKind::Function(name, Vec::new(), vec![Box::new(lhs), Box::new(rhs)]),
+                            span,
+                        );
+                    }
                     // Arithmetic
                     Rule::Add => BinOp::Add,
                     Rule::Subtract => BinOp::Sub,
@@ -584,6 +869,21 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                     }
                                 }
                             }
+                            // access to a named tuple field
+                            ExpressionKind::Ident(ident) => {
+                                let binop_expr =
+                                    Expression::new(self.next_id(), ExpressionKind::Field(inner, ident), binop_span);
+                                match unop {
+                                    None => return binop_expr,
+                                    Some(unop) => {
+                                        return Expression::new(
+                                            self.next_id(),
+                                            ExpressionKind::Unary(unop, Box::new(binop_expr)),
+                                            span,
+                                        )
+                                    }
+                                }
+                            }
                             ExpressionKind::Function(name, types, args) => {
                                 // match for builtin function names and transform them into appropriate AST nodes
                                 let signature = name.as_string();
@@ -798,6 +1098,24 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                     span.into(),
                 )
             }
+            Rule::LetExpr => {
+                let mut children = pair.into_inner();
+                let pattern = children.next().expect("A let expression needs a pattern.");
+                let pattern = match pattern.as_rule() {
+                    Rule::Ident => LetPattern::Ident(Rc::new(self.parse_let_binding(&pattern))),
+                    Rule::LetTuplePattern => LetPattern::Tuple(
+                        pattern.into_inner().map(|ident| Rc::new(self.parse_let_binding(&ident))).collect(),
+                    ),
+                    _ => unreachable!("{:?} is not a let pattern, ensured by grammar", pattern.as_rule()),
+                };
+                let mut children = self.parse_vec_of_expressions(children);
+                assert_eq!(children.len(), 2, "A let expression needs a value and a body.");
+                Expression::new(
+                    self.next_id(),
+                    ExpressionKind::Let(pattern, children.remove(0), children.remove(0)),
+                    span.into(),
+                )
+            }
             Rule::Tuple => {
                 let elements = self.parse_vec_of_expressions(pair.into_inner());
                 assert!(elements.len() != 1, "Tuples may not have exactly one element.");
@@ -938,6 +1256,11 @@ impl SourceMapper {
         SourceMapper { path, content: content.to_string() }
     }
 
+    /// Returns the length of the mapped specification in bytes
+    pub(crate) fn len(&self) -> usize {
+        self.content.len()
+    }
+
     #[allow(dead_code)]
     pub(crate) fn get_line(&self, span: Span) -> Option<CodeLine> {
         let mut byte_offset = 0;
@@ -1137,7 +1460,9 @@ mod tests {
                         NotEqual(11, 13, []),
                         Ident(14, 17, []),
                     ]),
-                    String(19, 31, []),
+                    TriggerMessage(18, 32, [
+                        MessageText(19, 31, []),
+                    ]),
                 ]),
             ]
         };
@@ -1310,6 +1635,184 @@ mod tests {
         cmp_ast_spec(&ast, spec);
     }
 
+    #[test]
+    fn build_named_tuple() {
+        let spec = "input pos: (lat: Float64, lon: Float64)\noutput s: Float64 := pos.lat\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+    }
+
+    #[test]
+    fn build_let_expression() {
+        let spec = "input pos: (Float64, Float64)\noutput s: Float64 := let (x, y) = pos in let d = x - y in d * d\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+    }
+
+    #[test]
+    fn let_keyword_needs_word_boundary() {
+        let spec = "input a: Int64\noutput o := letx = a in x\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        assert!(parse(spec, &handler, FrontendConfig::default()).is_err());
+    }
+
+    #[test]
+    fn since_keyword_needs_word_boundary() {
+        let spec = "input a: Bool\ninput _x: Bool\noutput o := a since_x\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        assert!(parse(spec, &handler, FrontendConfig::default()).is_err());
+    }
+
+    #[test]
+    fn build_template_instantiation() {
+        let spec = "input rpm_1: Int64\ninput rpm_2: Int64\ntemplate motor_check(rpm) {\n  output high := rpm > 5000\n  output alarm := high && rpm.offset(by: -1).defaults(to: 0) > 5000\n}\ninstantiate motor_check(rpm_1) as motor_1\ninstantiate motor_check(rpm_2) as motor_2\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        let outputs: Vec<String> = ast.outputs.iter().map(|o| format!("{} := {}", o.name.name, o.expression)).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                "motor_1::high := rpm_1 > 5000",
+                "motor_1::alarm := motor_1::high ∧ rpm_1.offset(by: -1).defaults(to: 0) > 5000",
+                "motor_2::high := rpm_2 > 5000",
+                "motor_2::alarm := motor_2::high ∧ rpm_2.offset(by: -1).defaults(to: 0) > 5000",
+            ]
+        );
+        // the spans of instantiated streams are moved behind the specification
+        assert!(ast.outputs.iter().all(|o| o.span.start > spec.len()));
+        assert!(ast.outputs[0].span.end < ast.outputs[2].span.start);
+    }
+
+    #[test]
+    fn template_instantiation_with_named_trigger() {
+        let spec = "input rpm_1: Int64\ninput rpm_2: Int64\ntemplate motor_check(rpm) {\n  trigger overspeed: warning := rpm > 5000\n}\ninstantiate motor_check(rpm_1) as motor_1\ninstantiate motor_check(rpm_2) as motor_2\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        let triggers: Vec<String> = ast
+            .trigger
+            .iter()
+            .map(|t| format!("{} := {}", t.name.as_ref().expect("trigger is named").name, t.expression))
+            .collect();
+        assert_eq!(triggers, vec!["motor_1::overspeed := rpm_1 > 5000", "motor_2::overspeed := rpm_2 > 5000"]);
+        let mut naming = crate::analysis::naming::NamingAnalysis::new(&handler, FrontendConfig::default());
+        naming.check(&ast);
+        assert!(!handler.contains_error());
+    }
+
+    #[test]
+    fn template_instantiation_shadowing() {
+        let spec = "input a: Int64\ntemplate t(x) {\n  output o(x: Int64) := x\n  output p := let x = 1 in x\n  trigger x > 5\n}\ninstantiate t(a) as i\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        assert_eq!(format!("{}", ast.outputs[0].expression), "x");
+        assert_eq!(format!("{}", ast.outputs[1].expression), "let x = 1 in x");
+        assert_eq!(format!("{}", ast.trigger[0].expression), "a > 5");
+    }
+
+    #[test]
+    fn faulty_template_instantiation() {
+        let spec = "input a: Int64\ntemplate t(x, y) {\n  output o := x + y\n}\ntemplate t(x) {\n  output o := x\n}\ninstantiate t(a) as i\ninstantiate s(a) as j\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        // duplicate template, wrong number of arguments, and unknown template
+        assert_eq!(handler.emitted_errors(), 3);
+        assert!(ast.outputs.is_empty());
+    }
+
+    #[test]
+    fn build_past_time_operators() {
+        let spec = "input a: Bool\ninput b: Bool\noutput o := once(a) ∧ historically(b)\noutput s := a since b ∨ once(a)\noutput w @1Hz := once(a ∧ b, within: 5s)\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        let outputs: Vec<String> = ast.outputs.iter().map(|o| format!("{} := {}", o.name.name, o.expression)).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                "o := once(a) ∧ historically(b)",
+                "s := since(a, b ∨ once(a))",
+                "w := (a ∧ b).aggregate(over: 5s, using: ∃)",
+                "once(a) := a ∨ once(a).offset(by: -1).defaults(to: false)",
+                "historically(b) := b ∧ historically(b).offset(by: -1).defaults(to: true)",
+                "since(a, b ∨ once(a)) := b ∨ once(a) ∨ a ∧ since(a, b ∨ once(a)).offset(by: -1).defaults(to: false)",
+                "(a ∧ b) := a ∧ b",
+            ]
+        );
+        assert_eq!(ast.outputs.iter().filter(|o| o.hidden).count(), 4);
+    }
+
+    #[test]
+    fn print_past_time_operators() {
+        let spec = "input a: Bool\ninput b: Bool\noutput s @1Hz := a since b ∨ once(a)\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        let derived: Vec<String> =
+            ast.outputs.iter().filter(|o| o.hidden).map(|o| format!("{} := {}", o.name.name, o.expression)).collect();
+        assert_eq!(
+            derived,
+            vec![
+                "once(a) := a ∨ once(a).offset(by: -1).defaults(to: false)",
+                "since(a, b ∨ once(a)) := b ∨ once(a) ∨ a ∧ since(a, b ∨ once(a)).offset(by: -1).defaults(to: false)",
+            ]
+        );
+        assert!(ast.outputs.iter().filter(|o| o.hidden).all(|o| o.extend.expr.is_some()));
+
+        // hidden streams are not printed, but derived again from the printed operators
+        let printed = ast.to_string();
+        assert!(!printed.contains("output once(a)"));
+        let reparsed = parse(&printed, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        assert_eq!(reparsed.to_string(), printed);
+        assert_eq!(reparsed.outputs.len(), ast.outputs.len());
+    }
+
+    #[test]
+    fn faulty_past_time_operators() {
+        let spec = "input a: Bool\noutput o(p: Bool) := once(a ∧ p)\noutput l := let x = a in historically(x)\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let _ = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(handler.emitted_errors(), 2);
+    }
+
+    #[test]
+    fn build_signal_operators() {
+        let spec =
+            "input a: Float64\ninput b: Float64\noutput d := a.derivative()\noutput e := ema(a + b, alpha: 0.1)\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        let outputs: Vec<String> = ast.outputs.iter().map(|o| format!("{} := {}", o.name.name, o.expression)).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                "d := a.derivative()",
+                "e := ema(a + b, alpha: 0.1)",
+                "now() @ a := now()",
+                "a.derivative() := if (now() - now() @ a.offset(by: -1).defaults(to: now())).seconds() > 0.0 then (cast(a) - cast(a.offset(by: -1).defaults(to: a))) / (now() - now() @ a.offset(by: -1).defaults(to: now())).seconds() else a.derivative().offset(by: -1).defaults(to: 0.0)",
+                "(a + b) := a + b",
+                "ema(a + b, alpha: 0.1) := 0.1 * (a + b) + (1.0 - 0.1) * ema(a + b, alpha: 0.1).offset(by: -1).defaults(to: (a + b))",
+            ]
+        );
+        // the time stream is evaluated together with the operand
+        assert_eq!(ast.outputs[2].extend.expr.as_ref().map(|expr| expr.to_string()), Some("a".to_string()));
+        assert_eq!(ast.outputs.iter().filter(|o| o.hidden).count(), 4);
+    }
+
+    #[test]
+    fn faulty_signal_operators() {
+        let spec = "input a: Float64\noutput e := a.ema(alpha: 1.5)\noutput l := a.lowpass(cutoff: 2)\noutput d := let x = a in x.derivative()\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let _ = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(handler.emitted_errors(), 3);
+    }
+
     #[test]
     fn parse_string() {
         let spec = r#"constant s: String := "a string with \n newline"
@@ -1336,6 +1839,129 @@ mod tests {
         cmp_ast_spec(&ast, spec);
     }
 
+    #[test]
+    fn parse_annotation() {
+        let spec = "#![robustness]\nimport math\ninput in: UInt8\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+    }
+
+    #[test]
+    fn parse_paced_trigger() {
+        let spec =
+            "input a: Int32\ntrigger @ 1Hz a.hold().defaults(to: 0) > 50 \"too high\"\ntrigger @ (a ∧ b) a < 30\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+    }
+
+    #[test]
+    fn parse_trigger_message_placeholders() {
+        let spec =
+            "input gps: UInt8\ninput alt: Float64\ntrigger gps < 2 \"only {gps} satellites, alt={alt.hold()} {{m}}\"\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+        let parts: Vec<String> = ast.trigger[0]
+            .message_parts
+            .iter()
+            .map(|part| match part {
+                MessagePart::Text(text) => text.clone(),
+                MessagePart::Placeholder(expr) => format!("<{}>", expr),
+            })
+            .collect();
+        assert_eq!(parts, vec!["only ", "<gps>", " satellites, alt=", "<alt.hold()>", " {m}"]);
+    }
+
+    #[test]
+    fn parse_named_trigger() {
+        let spec = "input gps: UInt8\ntrigger gps_lost: critical @ 1Hz := gps.hold().defaults(to: 0) < 3 \"lost gps\"\ntrigger few_satellites := gps < 5\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+        assert_eq!(ast.trigger[0].name.as_ref().map(|ident| ident.name.as_str()), Some("gps_lost"));
+        assert_eq!(ast.trigger[0].severity, Some(TriggerSeverity::Critical));
+        assert_eq!(ast.trigger[1].severity, None);
+    }
+
+    #[test]
+    fn parse_input_assumption() {
+        let spec = "input alt: Float64 assume alt ≥ 0.0 ∧ alt < 10000.0\ninput a: Int64\ninput b: Int64 assume b ≠ 0\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+        assert!(ast.inputs[0].assumption.is_some());
+        assert!(ast.inputs[1].assumption.is_none());
+        assert!(ast.inputs[2].assumption.is_some());
+    }
+
+    #[test]
+    fn parse_unit_type() {
+        let spec = "input alt: Float64<ft>\ninput acc: Float32<m/s^2>\noutput rate: Float64<1/s> := 1.0\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+        match &ast.inputs[1].ty.kind {
+            TypeKind::Unit(_, unit) => {
+                let unit: Vec<(&str, i32)> = unit.iter().map(|(symbol, exp)| (symbol.name.as_str(), *exp)).collect();
+                assert_eq!(unit, vec![("m", 1), ("s", -2)]);
+            }
+            kind => panic!("expected a type with unit, found {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn parse_subnet_literals() {
+        let spec = "input IPv6::source: IPv6Addr\ninput ab::cd: UInt8\noutput a := 10.0.0.0/8\noutput b := fe80::/10\noutput c := ::ffff:0.0.0.0/96\noutput d := IPv6::source.in_subnet(::/0)\noutput e := ab::cd / 2\noutput f := 1.5 / 2.0\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+        for output in &ast.outputs[..3] {
+            assert!(matches!(&output.expression.kind, ExpressionKind::Lit(Literal { kind: LitKind::Subnet(_), .. })));
+        }
+        assert!(matches!(&ast.outputs[4].expression.kind, ExpressionKind::Binary(BinOp::Div, _, _)));
+        assert!(matches!(&ast.outputs[5].expression.kind, ExpressionKind::Binary(BinOp::Div, _, _)));
+    }
+
+    #[test]
+    fn parse_division_of_instance_streams() {
+        let spec = "input ab::cd: UInt8
+input cafe::o: UInt8
+output e := ab::cd/2
+output f := cafe::o/2
+output g := fe80::/10
+";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(!handler.contains_error());
+        for output in &ast.outputs[..2] {
+            assert!(matches!(&output.expression.kind, ExpressionKind::Binary(BinOp::Div, _, _)));
+        }
+        assert!(matches!(
+            &ast.outputs[2].expression.kind,
+            ExpressionKind::Lit(Literal { kind: LitKind::Subnet(_), .. })
+        ));
+    }
+
+    #[test]
+    fn parse_unit_exponent_out_of_range() {
+        let spec = "input alt: Float64<m^99999999999>\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let _ = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(handler.emitted_errors(), 1);
+    }
+
+    #[test]
+    fn parse_trigger_options() {
+        let spec = "input w_spd: Float64\ntrigger w_spd > 14.0 \"too windy\" with rising, rearm: 10s, limit: 3 per 1min\ntrigger gusts := w_spd > 20.0 with limit: 1 per 1s\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        cmp_ast_spec(&ast, spec);
+        assert_eq!(ast.trigger[0].options.len(), 3);
+        assert_eq!(ast.trigger[1].options.len(), 1);
+    }
+
     #[test]
     fn parse_max() {
         let spec = "import math\ninput a: Int32\ninput b: Int32\noutput maxres: Int32 := max<Int32>(a, b)\n";
@@ -17,6 +17,22 @@ pub(crate) struct Handler {
     warning_count: RefCell<usize>,
     emitter: RefCell<Box<dyn Emitter>>,
     mapper: SourceMapper,
+    expansions: RefCell<Vec<Expansion>>,
+}
+
+/// The instantiation of a template, whose streams have been assigned spans behind the end of the specification.
+#[derive(Debug, Clone)]
+struct Expansion {
+    /// The name of the instantiated template
+    name: String,
+    /// The first position assigned to the instantiated streams
+    start: usize,
+    /// The last position assigned to the instantiated streams
+    end: usize,
+    /// The span of the template declaration
+    template: Span,
+    /// The span of the instantiation
+    site: Span,
 }
 
 impl Handler {
@@ -26,6 +42,7 @@ impl Handler {
             warning_count: RefCell::new(0),
             emitter: RefCell::new(Box::new(StderrEmitter::new())),
             mapper,
+            expansions: RefCell::new(Vec::new()),
         }
     }
 
@@ -42,8 +59,45 @@ impl Handler {
         *self.warning_count.borrow()
     }
 
+    /// Registers the instantiation of template `name`, which is declared at `template`, at `site`.
+    /// Returns the offset that has to be added to the spans of the instantiated streams.
+    /// This way, diagnostics can be traced back to the instantiation they originate from.
+    pub(crate) fn register_expansion(&self, name: &str, template: Span, site: Span) -> usize {
+        let mut expansions = self.expansions.borrow_mut();
+        let start = expansions.last().map_or(self.mapper.len(), |expansion| expansion.end) + 1;
+        let end = start + (template.end - template.start);
+        expansions.push(Expansion { name: name.to_string(), start, end, template, site });
+        start - template.start
+    }
+
+    /// Maps spans of instantiated streams back to the template declaration and adds a label to the instantiation.
+    fn resolve_expansions(&self, diagnostic: &Diagnostic) -> Diagnostic {
+        let expansions = self.expansions.borrow();
+        let mut resolved = diagnostic.clone();
+        let mut sites: Vec<&Expansion> = Vec::new();
+        for labeled in &mut resolved.span {
+            let span = labeled.span;
+            if let Some(expansion) = expansions.iter().find(|e| e.start <= span.start && span.start <= e.end) {
+                let offset = expansion.start - expansion.template.start;
+                labeled.span = Span { start: span.start - offset, end: span.end - offset };
+                if !sites.iter().any(|site| site.start == expansion.start) {
+                    sites.push(expansion);
+                }
+            }
+        }
+        for expansion in sites {
+            resolved.span.push(LabeledSpan::new(
+                expansion.site,
+                &format!("in this instantiation of template `{}`", expansion.name),
+                false,
+            ));
+        }
+        resolved
+    }
+
     /// Displays diagnostic to user
     fn emit(&self, diagnostic: &Diagnostic) {
+        let diagnostic = &self.resolve_expansions(diagnostic);
         if diagnostic.is_error() {
             let mut count = self.error_count.borrow_mut();
             *count += 1;
@@ -473,3 +527,34 @@ impl ColoredLine {
         self.strings.push(ColoredString { string: string.to_owned(), color })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn resolve_expanded_spans() {
+        let spec = "input a: Int64\ntemplate t(x) { output o := x }\ninstantiate t(a) as i\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let template = Span { start: 15, end: 46 };
+        let site = Span { start: 47, end: 68 };
+        let offset = handler.register_expansion("t", template, site);
+        assert!(template.start + offset > spec.len());
+
+        let expanded = Span { start: 31 + offset, end: 37 + offset };
+        let diagnostic = Diagnostic {
+            level: Error,
+            message: "an error".to_string(),
+            span: vec![LabeledSpan::new(expanded, "here", true)],
+            children: vec![],
+            sort_spans: true,
+        };
+        let resolved = handler.resolve_expansions(&diagnostic);
+        assert_eq!(resolved.span.len(), 2);
+        assert_eq!(resolved.span[0].span, Span { start: 31, end: 37 });
+        assert!(resolved.span[0].primary);
+        assert_eq!(resolved.span[1].span, site);
+        assert!(!resolved.span[1].primary);
+    }
+}
@@ -69,6 +69,49 @@ impl BinOp {
             },
         }
     }
+
+    /// Returns the declaration of an arithmetic operation on optional operands, e.g., `+<T: Numeric>(_: T?, _: T) -> T?`.
+    /// The result has no value if one of the operands has no value.
+    pub(crate) fn get_optional_func_decl(self, left_optional: bool, right_optional: bool) -> FuncDecl {
+        assert!(left_optional || right_optional);
+        let param = |optional: bool| {
+            if optional {
+                ValueTy::Option(ValueTy::Param(0, "T".to_string()).into())
+            } else {
+                ValueTy::Param(0, "T".to_string())
+            }
+        };
+        FuncDecl {
+            name: FunctionName::new(format!("{}", self), &[None, None]),
+            generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+            parameters: vec![param(left_optional), param(right_optional)],
+            return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+        }
+    }
+
+    /// Returns the declaration of `+` and `-` if one of the operands is a time value, i.e., a `Timestamp` or `Duration`.
+    /// In contrast to arithmetic on numbers, the operand types may differ, e.g., `-(_: Timestamp, _: Duration) -> Timestamp`.
+    pub(crate) fn get_time_func_decl(self, left: Option<&ValueTy>, right: Option<&ValueTy>) -> Option<FuncDecl> {
+        use self::BinOp::*;
+        use ValueTy::{Duration, Timestamp};
+        let (parameters, return_type) = match (self, left, right) {
+            (Sub, Some(Timestamp), Some(Duration)) => (vec![Timestamp, Duration], Timestamp),
+            (Add, Some(Timestamp), _) => (vec![Timestamp, Duration], Timestamp),
+            (Add, _, Some(Timestamp)) => (vec![Duration, Timestamp], Timestamp),
+            (Sub, Some(Timestamp), _) | (Sub, _, Some(Timestamp)) => (vec![Timestamp, Timestamp], Duration),
+            (Add, Some(Duration), _)
+            | (Add, _, Some(Duration))
+            | (Sub, Some(Duration), _)
+            | (Sub, _, Some(Duration)) => (vec![Duration, Duration], Duration),
+            _ => return None,
+        };
+        Some(FuncDecl {
+            name: FunctionName::new(format!("{}", self), &[None, None]),
+            generics: vec![],
+            parameters,
+            return_type,
+        })
+    }
 }
 
 impl UnOp {
@@ -152,6 +195,104 @@ lazy_static! {
         parameters: vec![ValueTy::Param(0, "T".to_string())],
         return_type: ValueTy::Param(0, "T".to_string()),
     };
+    // fn tan<T: FloatingPoint>(T) -> T
+    static ref TAN: FuncDecl = FuncDecl {
+        name: FunctionName::new("tan".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn asin<T: FloatingPoint>(T) -> T
+    static ref ASIN: FuncDecl = FuncDecl {
+        name: FunctionName::new("asin".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn acos<T: FloatingPoint>(T) -> T
+    static ref ACOS: FuncDecl = FuncDecl {
+        name: FunctionName::new("acos".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn exp<T: FloatingPoint>(T) -> T
+    static ref EXP: FuncDecl = FuncDecl {
+        name: FunctionName::new("exp".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn ln<T: FloatingPoint>(T) -> T
+    static ref LN: FuncDecl = FuncDecl {
+        name: FunctionName::new("ln".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn log10<T: FloatingPoint>(T) -> T
+    static ref LOG10: FuncDecl = FuncDecl {
+        name: FunctionName::new("log10".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn floor<T: FloatingPoint>(T) -> T
+    static ref FLOOR: FuncDecl = FuncDecl {
+        name: FunctionName::new("floor".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn ceil<T: FloatingPoint>(T) -> T
+    static ref CEIL: FuncDecl = FuncDecl {
+        name: FunctionName::new("ceil".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn round<T: FloatingPoint>(T) -> T
+    static ref ROUND: FuncDecl = FuncDecl {
+        name: FunctionName::new("round".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn atan2<T: FloatingPoint>(T, T) -> T
+    static ref ATAN2: FuncDecl = FuncDecl {
+        name: FunctionName::new("atan2".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn hypot<T: FloatingPoint>(T, T) -> T
+    static ref HYPOT: FuncDecl = FuncDecl {
+        name: FunctionName::new("hypot".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn pow<T: FloatingPoint>(T, T) -> T
+    static ref POW: FuncDecl = FuncDecl {
+        name: FunctionName::new("pow".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn clamp<T: Numeric>(T, T, T) -> T
+    static ref CLAMP: FuncDecl = FuncDecl {
+        name: FunctionName::new("clamp".to_string(), &[None, None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![
+            ValueTy::Param(0, "T".to_string()),
+            ValueTy::Param(0, "T".to_string()),
+            ValueTy::Param(0, "T".to_string()),
+        ],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    /// The floating-point functions of the math module besides `sqrt`, `sin`, `cos`, and `arctan`
+    static ref FLOAT_FUNCTIONS: Vec<&'static FuncDecl> =
+        vec![&TAN, &ASIN, &ACOS, &EXP, &LN, &LOG10, &FLOOR, &CEIL, &ROUND, &ATAN2, &HYPOT, &POW];
 
     // fn matches(String, regex: String) -> Bool
     static ref MATCHES_STRING_REGEX: FuncDecl = FuncDecl {
@@ -169,6 +310,139 @@ lazy_static! {
         return_type: ValueTy::Bool,
     };
 
+    // fn len(String) -> UInt64
+    static ref STRING_LEN: FuncDecl = FuncDecl {
+        name: FunctionName::new("len".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+    // fn concat(String, String) -> String
+    static ref STRING_CONCAT: FuncDecl = FuncDecl {
+        name: FunctionName::new("concat".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::String,
+    };
+    // fn substring(String, from: UInt64, to: UInt64) -> String?
+    static ref STRING_SUBSTRING: FuncDecl = FuncDecl {
+        name: FunctionName::new("substring".to_string(), &[None, Some("from".to_string()), Some("to".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::UInt(UIntTy::U64), ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::String.into()),
+    };
+    // fn starts_with(String, String) -> Bool
+    static ref STRING_STARTS_WITH: FuncDecl = FuncDecl {
+        name: FunctionName::new("starts_with".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+    // fn ends_with(String, String) -> Bool
+    static ref STRING_ENDS_WITH: FuncDecl = FuncDecl {
+        name: FunctionName::new("ends_with".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+    // fn contains(String, String) -> Bool
+    static ref STRING_CONTAINS: FuncDecl = FuncDecl {
+        name: FunctionName::new("contains".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+    // fn to_lower(String) -> String
+    static ref STRING_TO_LOWER: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_lower".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::String,
+    };
+    // fn to_upper(String) -> String
+    static ref STRING_TO_UPPER: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_upper".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::String,
+    };
+    // fn split(String, String) -> List<String>
+    static ref STRING_SPLIT: FuncDecl = FuncDecl {
+        name: FunctionName::new("split".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::List(ValueTy::String.into()),
+    };
+    // fn parse_int(String) -> Int64?
+    static ref STRING_PARSE_INT: FuncDecl = FuncDecl {
+        name: FunctionName::new("parse_int".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::Option(ValueTy::Int(IntTy::I64).into()),
+    };
+    // fn parse_uint(String) -> UInt64?
+    static ref STRING_PARSE_UINT: FuncDecl = FuncDecl {
+        name: FunctionName::new("parse_uint".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::Option(ValueTy::UInt(UIntTy::U64).into()),
+    };
+    // fn parse_float(String) -> Float64?
+    static ref STRING_PARSE_FLOAT: FuncDecl = FuncDecl {
+        name: FunctionName::new("parse_float".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::Option(ValueTy::Float(FloatTy::F64).into()),
+    };
+    /// The functions of the string module, all of them take the string as first argument
+    static ref STRING_FUNCTIONS: Vec<&'static FuncDecl> = vec![
+        &STRING_LEN,
+        &STRING_CONCAT,
+        &STRING_SUBSTRING,
+        &STRING_STARTS_WITH,
+        &STRING_ENDS_WITH,
+        &STRING_CONTAINS,
+        &STRING_TO_LOWER,
+        &STRING_TO_UPPER,
+        &STRING_SPLIT,
+        &STRING_PARSE_INT,
+        &STRING_PARSE_UINT,
+        &STRING_PARSE_FLOAT,
+    ];
+
+    // fn capture(String, regex: String, group: UInt64) -> String?
+    static ref CAPTURE_STRING_REGEX: FuncDecl = FuncDecl {
+        name: FunctionName::new("capture".to_string(), &[None, Some("regex".to_string()), Some("group".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::String.into()),
+    };
+
+    // fn capture(Bytes, regex: String, group: UInt64) -> Bytes?
+    static ref CAPTURE_BYTES_REGEX: FuncDecl = FuncDecl {
+        name: FunctionName::new("capture".to_string(), &[None, Some("regex".to_string()), Some("group".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::String, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Bytes.into()),
+    };
+
+    // fn captures(String, regex: String) -> (String?, ..)
+    // the return type has one component per capture group and is determined by the type checker
+    static ref CAPTURES_STRING_REGEX: FuncDecl = FuncDecl {
+        name: FunctionName::new("captures".to_string(), &[None, Some("regex".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Tuple(vec![]),
+    };
+
+    // fn captures(Bytes, regex: String) -> (Bytes?, ..)
+    static ref CAPTURES_BYTES_REGEX: FuncDecl = FuncDecl {
+        name: FunctionName::new("captures".to_string(), &[None, Some("regex".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::String],
+        return_type: ValueTy::Tuple(vec![]),
+    };
+
     /// fn cast<T: Numeric, U: Numeric>(T) -> U
     /// allows for arbitrary conversion of numeric types T -> U
     static ref CAST: FuncDecl = FuncDecl {
@@ -187,14 +461,317 @@ lazy_static! {
         parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
         return_type: ValueTy::Option( ValueTy::UInt(UIntTy::U8).into() ),
     };
+
+    // fn distance(Float64, Float64, Float64, Float64) -> Float64
+    // the haversine distance in meters between two positions given by latitude and longitude in degrees
+    static ref GEO_DISTANCE: FuncDecl = FuncDecl {
+        name: FunctionName::new("distance".to_string(), &[None, None, None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Float(FloatTy::F64); 4],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+    // fn bearing(Float64, Float64, Float64, Float64) -> Float64
+    // the initial bearing in degrees from the first to the second position
+    static ref GEO_BEARING: FuncDecl = FuncDecl {
+        name: FunctionName::new("bearing".to_string(), &[None, None, None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Float(FloatTy::F64); 4],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+    // fn within(Float64, Float64, polygon: String) -> Bool
+    // the polygon is a string literal or constant of the form "lat lon, lat lon, lat lon, ..."
+    static ref GEO_WITHIN: FuncDecl = FuncDecl {
+        name: FunctionName::new("within".to_string(), &[None, None, Some("polygon".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Float(FloatTy::F64), ValueTy::Float(FloatTy::F64), ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+    // fn enu(Float64, Float64, Float64, Float64, Float64, Float64) -> (east: Float64, north: Float64, up: Float64)
+    // the local coordinates in meters of a position with altitude relative to a reference position with altitude
+    static ref GEO_ENU: FuncDecl = FuncDecl {
+        name: FunctionName::new("enu".to_string(), &[None, None, None, None, None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Float(FloatTy::F64); 6],
+        return_type: ValueTy::NamedTuple(
+            ["east", "north", "up"].iter().map(|name| (name.to_string(), ValueTy::Float(FloatTy::F64))).collect()
+        ),
+    };
+
+    // fn len(Bytes) -> UInt64
+    static ref BYTES_LEN: FuncDecl = FuncDecl {
+        name: FunctionName::new("len".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+    // fn slice(Bytes, from: UInt64, to: UInt64) -> Bytes?
+    static ref BYTES_SLICE: FuncDecl = FuncDecl {
+        name: FunctionName::new("slice".to_string(), &[None, Some("from".to_string()), Some("to".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64), ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Bytes.into()),
+    };
+    // fn to_hex(Bytes) -> String
+    static ref BYTES_TO_HEX: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_hex".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes],
+        return_type: ValueTy::String,
+    };
+    // fn find(Bytes, String) -> UInt64?
+    // the pattern is given as string and matched against its utf-8 encoding
+    static ref BYTES_FIND: FuncDecl = FuncDecl {
+        name: FunctionName::new("find".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::String],
+        return_type: ValueTy::Option(ValueTy::UInt(UIntTy::U64).into()),
+    };
+    // fn starts_with(Bytes, String) -> Bool
+    static ref BYTES_STARTS_WITH: FuncDecl = FuncDecl {
+        name: FunctionName::new("starts_with".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+    /// fn read_<int>_<endianness>(Bytes, at: UInt64) -> <int>?, e.g., `read_u16_be`
+    /// decodes an integer from the bytes starting at the given index
+    static ref BYTES_READ: Vec<FuncDecl> = {
+        let ints = [
+            ("u16", ValueTy::UInt(UIntTy::U16)),
+            ("u32", ValueTy::UInt(UIntTy::U32)),
+            ("u64", ValueTy::UInt(UIntTy::U64)),
+            ("i16", ValueTy::Int(IntTy::I16)),
+            ("i32", ValueTy::Int(IntTy::I32)),
+            ("i64", ValueTy::Int(IntTy::I64)),
+        ];
+        ints.iter()
+            .flat_map(|(name, ty)| {
+                ["be", "le"].iter().map(move |endianness| FuncDecl {
+                    name: FunctionName::new(format!("read_{}_{}", name, endianness), &[None, Some("at".to_string())]),
+                    generics: vec![],
+                    parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+                    return_type: ValueTy::Option(ty.clone().into()),
+                })
+            })
+            .collect()
+    };
+
+    static ref IPV4_METHODS: Vec<FuncDecl> = address_methods(ValueTy::IPv4Addr, ValueTy::IPv4Net);
+    static ref IPV6_METHODS: Vec<FuncDecl> = address_methods(ValueTy::IPv6Addr, ValueTy::IPv6Net);
+
+    // fn Set<T>() -> Set<T>
+    static ref SET_EMPTY: FuncDecl = FuncDecl {
+        name: FunctionName::new("Set".to_string(), &[]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![],
+        return_type: ValueTy::Set(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn insert<T>(Set<T>, T) -> Set<T>
+    static ref SET_INSERT: FuncDecl = FuncDecl {
+        name: FunctionName::new("insert".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Set(ValueTy::Param(0, "T".to_string()).into()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Set(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn contains<T>(Set<T>, T) -> Bool
+    static ref SET_CONTAINS: FuncDecl = FuncDecl {
+        name: FunctionName::new("contains".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Set(ValueTy::Param(0, "T".to_string()).into()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Bool,
+    };
+    // fn len<T>(Set<T>) -> UInt64
+    static ref SET_LEN: FuncDecl = FuncDecl {
+        name: FunctionName::new("len".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Set(ValueTy::Param(0, "T".to_string()).into())],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    // fn at<T>(List<T>, UInt64) -> T?
+    static ref LIST_AT: FuncDecl = FuncDecl {
+        name: FunctionName::new("at".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::List(ValueTy::Param(0, "T".to_string()).into()), ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn len<T>(List<T>) -> UInt64
+    static ref LIST_LEN: FuncDecl = FuncDecl {
+        name: FunctionName::new("len".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::List(ValueTy::Param(0, "T".to_string()).into())],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    // fn Map<K, V>() -> Map<K, V>
+    static ref MAP_EMPTY: FuncDecl = FuncDecl {
+        name: FunctionName::new("Map".to_string(), &[]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained), ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![],
+        return_type: ValueTy::Map(ValueTy::Param(0, "K".to_string()).into(), ValueTy::Param(1, "V".to_string()).into()),
+    };
+    // fn insert<K, V>(Map<K, V>, key: K, value: V) -> Map<K, V>
+    static ref MAP_INSERT: FuncDecl = FuncDecl {
+        name: FunctionName::new("insert".to_string(), &[None, Some("key".to_string()), Some("value".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained), ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![
+            ValueTy::Map(ValueTy::Param(0, "K".to_string()).into(), ValueTy::Param(1, "V".to_string()).into()),
+            ValueTy::Param(0, "K".to_string()),
+            ValueTy::Param(1, "V".to_string()),
+        ],
+        return_type: ValueTy::Map(ValueTy::Param(0, "K".to_string()).into(), ValueTy::Param(1, "V".to_string()).into()),
+    };
+    // fn contains<K, V>(Map<K, V>, key: K) -> Bool
+    static ref MAP_CONTAINS: FuncDecl = FuncDecl {
+        name: FunctionName::new("contains".to_string(), &[None, Some("key".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained), ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![
+            ValueTy::Map(ValueTy::Param(0, "K".to_string()).into(), ValueTy::Param(1, "V".to_string()).into()),
+            ValueTy::Param(0, "K".to_string()),
+        ],
+        return_type: ValueTy::Bool,
+    };
+    // fn get<K, V>(Map<K, V>, key: K) -> V?
+    static ref MAP_GET: FuncDecl = FuncDecl {
+        name: FunctionName::new("get".to_string(), &[None, Some("key".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained), ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![
+            ValueTy::Map(ValueTy::Param(0, "K".to_string()).into(), ValueTy::Param(1, "V".to_string()).into()),
+            ValueTy::Param(0, "K".to_string()),
+        ],
+        return_type: ValueTy::Option(ValueTy::Param(1, "V".to_string()).into()),
+    };
+    // fn len<K, V>(Map<K, V>) -> UInt64
+    static ref MAP_LEN: FuncDecl = FuncDecl {
+        name: FunctionName::new("len".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained), ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![
+            ValueTy::Map(ValueTy::Param(0, "K".to_string()).into(), ValueTy::Param(1, "V".to_string()).into()),
+        ],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    // fn is_some<T>(T?) -> Bool
+    static ref OPTION_IS_SOME: FuncDecl = FuncDecl {
+        name: FunctionName::new("is_some".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Option(ValueTy::Param(0, "T".to_string()).into())],
+        return_type: ValueTy::Bool,
+    };
+    // fn is_none<T>(T?) -> Bool
+    static ref OPTION_IS_NONE: FuncDecl = FuncDecl {
+        name: FunctionName::new("is_none".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Option(ValueTy::Param(0, "T".to_string()).into())],
+        return_type: ValueTy::Bool,
+    };
+    // fn or<T>(T?, T?) -> T?
+    static ref OPTION_OR: FuncDecl = FuncDecl {
+        name: FunctionName::new("or".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![
+            ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+            ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+        ],
+        return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn unwrap_or<T>(T?, T) -> T
+    static ref OPTION_UNWRAP_OR: FuncDecl = FuncDecl {
+        name: FunctionName::new("unwrap_or".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn now() -> Timestamp
+    static ref NOW: FuncDecl = FuncDecl {
+        name: FunctionName::new("now".to_string(), &[]),
+        generics: vec![],
+        parameters: vec![],
+        return_type: ValueTy::Timestamp,
+    };
+    // fn seconds(Duration) -> Float64
+    static ref DURATION_SECONDS: FuncDecl = FuncDecl {
+        name: FunctionName::new("seconds".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Duration],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+    // fn seconds(Timestamp) -> Float64
+    static ref TIMESTAMP_SECONDS: FuncDecl = FuncDecl {
+        name: FunctionName::new("seconds".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Timestamp],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
 }
 
 pub(crate) fn import_implicit_module(fun_scope: &mut ScopedDecl) {
     fun_scope.add_fun_decl(&CAST);
+    fun_scope.add_fun_decl(&SET_EMPTY);
+    fun_scope.add_fun_decl(&MAP_EMPTY);
+    fun_scope.add_fun_decl(&NOW);
+}
+
+/// The methods of an address type:
+/// fn in_subnet(Addr, Net) -> Bool, where the network has the same address family, e.g., `10.0.0.0/8`
+/// fn is_private(Addr) -> Bool
+/// fn is_loopback(Addr) -> Bool
+/// fn is_multicast(Addr) -> Bool
+/// fn to_string(Addr) -> String
+fn address_methods(address: ValueTy, network: ValueTy) -> Vec<FuncDecl> {
+    let method = |name: &str, parameters: Vec<ValueTy>, return_type: ValueTy| FuncDecl {
+        name: FunctionName::new(name.to_string(), &vec![None; parameters.len()]),
+        generics: vec![],
+        parameters,
+        return_type,
+    };
+    vec![
+        method("in_subnet", vec![address.clone(), network], ValueTy::Bool),
+        method("is_private", vec![address.clone()], ValueTy::Bool),
+        method("is_loopback", vec![address.clone()], ValueTy::Bool),
+        method("is_multicast", vec![address.clone()], ValueTy::Bool),
+        method("to_string", vec![address], ValueTy::String),
+    ]
 }
 
 pub(crate) fn import_implicit_method(lookup: &mut MethodLookup) {
     lookup.add(ValueTy::Bytes, &BYTES_AT);
+    lookup.add(ValueTy::Bytes, &BYTES_LEN);
+    lookup.add(ValueTy::Bytes, &BYTES_SLICE);
+    lookup.add(ValueTy::Bytes, &BYTES_TO_HEX);
+    lookup.add(ValueTy::Bytes, &BYTES_FIND);
+    lookup.add(ValueTy::Bytes, &BYTES_STARTS_WITH);
+    for decl in BYTES_READ.iter() {
+        lookup.add(ValueTy::Bytes, decl);
+    }
+
+    lookup.add(SET_EMPTY.return_type.clone(), &SET_INSERT);
+    lookup.add(SET_EMPTY.return_type.clone(), &SET_CONTAINS);
+    lookup.add(SET_EMPTY.return_type.clone(), &SET_LEN);
+
+    lookup.add(LIST_AT.parameters[0].clone(), &LIST_AT);
+    lookup.add(LIST_AT.parameters[0].clone(), &LIST_LEN);
+
+    lookup.add(MAP_EMPTY.return_type.clone(), &MAP_INSERT);
+    lookup.add(MAP_EMPTY.return_type.clone(), &MAP_CONTAINS);
+    lookup.add(MAP_EMPTY.return_type.clone(), &MAP_GET);
+    lookup.add(MAP_EMPTY.return_type.clone(), &MAP_LEN);
+
+    lookup.add(OPTION_IS_SOME.parameters[0].clone(), &OPTION_IS_SOME);
+    lookup.add(OPTION_IS_SOME.parameters[0].clone(), &OPTION_IS_NONE);
+    lookup.add(OPTION_IS_SOME.parameters[0].clone(), &OPTION_OR);
+    lookup.add(OPTION_IS_SOME.parameters[0].clone(), &OPTION_UNWRAP_OR);
+
+    lookup.add(ValueTy::Duration, &DURATION_SECONDS);
+    lookup.add(ValueTy::Timestamp, &TIMESTAMP_SECONDS);
+
+    for decl in IPV4_METHODS.iter() {
+        lookup.add(ValueTy::IPv4Addr, decl);
+    }
+    for decl in IPV6_METHODS.iter() {
+        lookup.add(ValueTy::IPv6Addr, decl);
+    }
 }
 
 pub(crate) fn import_math_module(fun_scope: &mut ScopedDecl) {
@@ -205,10 +782,23 @@ pub(crate) fn import_math_module(fun_scope: &mut ScopedDecl) {
     fun_scope.add_fun_decl(&ARCTAN);
     fun_scope.add_fun_decl(&MIN);
     fun_scope.add_fun_decl(&MAX);
+    for decl in FLOAT_FUNCTIONS.iter() {
+        fun_scope.add_fun_decl(decl);
+    }
+    fun_scope.add_fun_decl(&CLAMP);
+}
+
+pub(crate) fn import_geo_module(fun_scope: &mut ScopedDecl) {
+    fun_scope.add_fun_decl(&GEO_DISTANCE);
+    fun_scope.add_fun_decl(&GEO_BEARING);
+    fun_scope.add_fun_decl(&GEO_WITHIN);
+    fun_scope.add_fun_decl(&GEO_ENU);
 }
 
 pub(crate) fn import_regex_module(fun_scope: &mut ScopedDecl) {
     fun_scope.add_fun_decl(&MATCHES_STRING_REGEX);
+    fun_scope.add_fun_decl(&CAPTURE_STRING_REGEX);
+    fun_scope.add_fun_decl(&CAPTURES_STRING_REGEX);
 }
 
 pub(crate) fn import_math_method(lookup: &mut MethodLookup) {
@@ -251,11 +841,41 @@ pub(crate) fn import_math_method(lookup: &mut MethodLookup) {
     lookup.add(ValueTy::UInt(UIntTy::U32), &MAX);
     lookup.add(ValueTy::UInt(UIntTy::U64), &MIN);
     lookup.add(ValueTy::UInt(UIntTy::U64), &MAX);
+
+    for ty in &[FloatTy::F16, FloatTy::F32, FloatTy::F64] {
+        for decl in FLOAT_FUNCTIONS.iter() {
+            lookup.add(ValueTy::Float(*ty), decl);
+        }
+    }
+    let numeric = [FloatTy::F16, FloatTy::F32, FloatTy::F64]
+        .iter()
+        .map(|ty| ValueTy::Float(*ty))
+        .chain([IntTy::I8, IntTy::I16, IntTy::I32, IntTy::I64].iter().map(|ty| ValueTy::Int(*ty)))
+        .chain([UIntTy::U8, UIntTy::U16, UIntTy::U32, UIntTy::U64].iter().map(|ty| ValueTy::UInt(*ty)));
+    for ty in numeric {
+        lookup.add(ty, &CLAMP);
+    }
+}
+
+pub(crate) fn import_string_module(fun_scope: &mut ScopedDecl) {
+    for decl in STRING_FUNCTIONS.iter() {
+        fun_scope.add_fun_decl(decl);
+    }
+}
+
+pub(crate) fn import_string_method(lookup: &mut MethodLookup) {
+    for decl in STRING_FUNCTIONS.iter() {
+        lookup.add(ValueTy::String, decl);
+    }
 }
 
 pub(crate) fn import_regex_method(lookup: &mut MethodLookup) {
     lookup.add(ValueTy::String, &MATCHES_STRING_REGEX);
     lookup.add(ValueTy::Bytes, &MATCHES_BYTES_REGEX);
+    lookup.add(ValueTy::String, &CAPTURE_STRING_REGEX);
+    lookup.add(ValueTy::Bytes, &CAPTURE_BYTES_REGEX);
+    lookup.add(ValueTy::String, &CAPTURES_STRING_REGEX);
+    lookup.add(ValueTy::Bytes, &CAPTURES_BYTES_REGEX);
 }
 
 pub(crate) struct MethodLookup<'a> {
@@ -267,8 +887,19 @@ impl<'a> MethodLookup<'a> {
         MethodLookup { lookup_table: HashMap::new() }
     }
 
+    /// Generic types are looked up by their type constructor, i.e., `Set<UInt8>` and `Set<?3>` both resolve to the methods declared for `Set<T>`.
+    fn lookup_key(ty: &ValueTy) -> ValueTy {
+        match ty {
+            ValueTy::Option(_) => OPTION_IS_SOME.parameters[0].clone(),
+            ValueTy::Set(_) => SET_EMPTY.return_type.clone(),
+            ValueTy::List(_) => LIST_AT.parameters[0].clone(),
+            ValueTy::Map(_, _) => MAP_EMPTY.return_type.clone(),
+            _ => ty.clone(),
+        }
+    }
+
     pub(crate) fn add(&mut self, ty: ValueTy, decl: &'a FuncDecl) {
-        let entry = self.lookup_table.entry(ty).or_insert_with(HashMap::new);
+        let entry = self.lookup_table.entry(Self::lookup_key(&ty)).or_insert_with(HashMap::new);
         let mut name = decl.name.clone();
         assert!(name.arg_names[0] == None);
         name.arg_names.remove(0);
@@ -278,7 +909,7 @@ impl<'a> MethodLookup<'a> {
     }
 
     pub(crate) fn get(&self, ty: &ValueTy, name: &FunctionName) -> Option<&'a FuncDecl> {
-        self.lookup_table.get(ty).and_then(|func_decls| func_decls.get(&name.to_string())).cloned()
+        self.lookup_table.get(&Self::lookup_key(ty)).and_then(|func_decls| func_decls.get(&name.to_string())).cloned()
         // cloned for dereferencing once && -> &
     }
 }
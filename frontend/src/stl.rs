@@ -0,0 +1,455 @@
+//! This module contains the translation of bounded Signal Temporal Logic (STL) requirements into RTLola.
+//!
+//! All generated streams are evaluated periodically with the sampling frequency.
+//! Every subformula is translated into a stream together with its delay, i.e., the stream computes the verdict
+//! for the point in time that lies the delay in the past.
+//! Temporal operators become sliding windows and operands with different delays are aligned by real-time offsets.
+//! Future operators increase the delay, so requirements containing them can only be monitored offline.
+
+use crate::parse::Span;
+use crate::reporting::{Handler, LabeledSpan};
+use lazy_static::lazy_static;
+use pest::iterators::{Pair, Pairs};
+use pest::prec_climber::{Assoc, Operator, PrecClimber};
+use pest::Parser;
+use pest_derive::Parser;
+use std::collections::BTreeMap;
+
+#[derive(Parser)]
+#[grammar = "stl.pest"]
+struct StlParser;
+
+lazy_static! {
+    static ref PREC_CLIMBER: PrecClimber<Rule> = {
+        use self::Assoc::*;
+        use self::Rule::*;
+
+        PrecClimber::new(vec![
+            Operator::new(Implies, Right),
+            Operator::new(Or, Left),
+            Operator::new(And, Left),
+            Operator::new(Until, Left) | Operator::new(Since, Left),
+        ])
+    };
+}
+
+/// An interval of a temporal operator in milliseconds
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    start: i64,
+    end: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemporalOp {
+    /// `G[a,b]`: globally in the future
+    Globally,
+    /// `F[a,b]`: finally in the future
+    Finally,
+    /// `H[a,b]`: historically in the past
+    Historically,
+    /// `O[a,b]`: once in the past
+    Once,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BoolOp {
+    And,
+    Or,
+    Implies,
+}
+
+#[derive(Debug, Clone)]
+enum Formula {
+    Literal(bool),
+    /// A boolean signal
+    Signal(String, Span),
+    /// A comparison of arithmetic expressions, already translated to RTLola, and the signals it refers to
+    Comparison(String, Vec<(String, Span)>),
+    Not(Box<Formula>),
+    Binary(BoolOp, Box<Formula>, Box<Formula>),
+    Temporal(TemporalOp, Interval, Box<Formula>),
+    Since(Box<Formula>, Box<Formula>),
+}
+
+/// The value type of a signal, which becomes an input stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignalTy {
+    Float,
+    Bool,
+}
+
+/// Translates STL requirements into the text of an RTLola specification.
+/// Errors and requirements that can only be monitored offline are reported to the `handler`.
+pub(crate) fn translate(content: &str, handler: &Handler, frequency: u32) -> Result<String, pest::error::Error<Rule>> {
+    let mut pairs = StlParser::parse(Rule::Spec, content)?;
+    let spec = pairs.next().expect("mismatch between grammar and AST");
+    if frequency == 0 || 1000 % frequency != 0 {
+        handler.error(&format!("the sampling frequency {}Hz does not divide 1000Hz", frequency));
+        return Ok(String::new());
+    }
+    let mut translation = Translation {
+        handler,
+        frequency,
+        period: 1000 / i64::from(frequency),
+        signals: BTreeMap::new(),
+        streams: Vec::new(),
+        triggers: Vec::new(),
+        counter: 0,
+    };
+    for (ix, requirement) in spec.into_inner().filter(|pair| pair.as_rule() == Rule::Requirement).enumerate() {
+        translation.translate_requirement(requirement, ix);
+    }
+    Ok(translation.specification())
+}
+
+struct Translation<'a> {
+    handler: &'a Handler,
+    frequency: u32,
+    /// The sampling period in milliseconds
+    period: i64,
+    signals: BTreeMap<String, (SignalTy, Span)>,
+    streams: Vec<String>,
+    triggers: Vec<String>,
+    /// The number of streams declared for the current requirement
+    counter: usize,
+}
+
+impl<'a> Translation<'a> {
+    fn specification(&self) -> String {
+        let inputs = self.signals.iter().map(|(name, (ty, _))| {
+            let ty = match ty {
+                SignalTy::Float => "Float64",
+                SignalTy::Bool => "Bool",
+            };
+            format!("input {}: {}", name, ty)
+        });
+        let declarations: Vec<String> =
+            inputs.chain(self.streams.iter().cloned()).chain(self.triggers.iter().cloned()).collect();
+        declarations.join("\n") + "\n"
+    }
+
+    fn translate_requirement(&mut self, pair: Pair<'_, Rule>, ix: usize) {
+        let mut pairs = pair.into_inner();
+        let first = pairs.next().expect("mismatch between grammar and AST");
+        let (name, formula) = if first.as_rule() == Rule::Ident {
+            (first.as_str().to_string(), pairs.next().expect("mismatch between grammar and AST"))
+        } else {
+            (format!("requirement_{}", ix), first)
+        };
+        let span: Span = formula.as_span().into();
+        let formula = match self.parse_formula(formula.into_inner()) {
+            Some(formula) => formula,
+            None => return,
+        };
+
+        self.counter = 0;
+        let (stream, delay) = self.translate_formula(&name, &formula);
+        let verdict = self.align(&stream, delay, delay.max(0));
+        let message = if delay > 0 {
+            self.handler.warn_with_span(
+                &format!("requirement `{}` refers to the future and can only be monitored offline", name),
+                LabeledSpan::new(span, &format!("verdicts are delayed by {}", duration(delay)), true),
+            );
+            format!("violation of `{}` {} ago", name, duration(delay))
+        } else {
+            format!("violation of `{}`", name)
+        };
+        self.triggers.push(format!("trigger !({}) \"{}\"", verdict, message));
+    }
+
+    fn parse_formula(&self, pairs: Pairs<'_, Rule>) -> Option<Formula> {
+        PREC_CLIMBER.climb(
+            pairs,
+            |pair| self.parse_unary(pair),
+            |lhs, op, rhs| {
+                let (lhs, rhs) = (Box::new(lhs?), Box::new(rhs?));
+                match op.as_rule() {
+                    Rule::And => Some(Formula::Binary(BoolOp::And, lhs, rhs)),
+                    Rule::Or => Some(Formula::Binary(BoolOp::Or, lhs, rhs)),
+                    Rule::Implies => Some(Formula::Binary(BoolOp::Implies, lhs, rhs)),
+                    Rule::Since => {
+                        if op.clone().into_inner().next().is_some() {
+                            self.handler.error_with_span(
+                                "bounded since operators are not supported",
+                                LabeledSpan::new(op.as_span().into(), "remove the interval", true),
+                            );
+                            return None;
+                        }
+                        Some(Formula::Since(lhs, rhs))
+                    }
+                    Rule::Until => {
+                        self.handler.error_with_span(
+                            "until operators are not supported",
+                            LabeledSpan::new(op.as_span().into(), "cannot be expressed by sliding windows", true),
+                        );
+                        None
+                    }
+                    _ => unreachable!(),
+                }
+            },
+        )
+    }
+
+    fn parse_unary(&self, pair: Pair<'_, Rule>) -> Option<Formula> {
+        match pair.as_rule() {
+            Rule::Formula => self.parse_formula(pair.into_inner()),
+            Rule::Negation => {
+                let inner = pair.into_inner().next().expect("mismatch between grammar and AST");
+                Some(Formula::Not(Box::new(self.parse_unary(inner)?)))
+            }
+            Rule::Temporal => {
+                let mut pairs = pair.into_inner();
+                let op = match pairs.next().expect("mismatch between grammar and AST").as_str() {
+                    "G" => TemporalOp::Globally,
+                    "F" => TemporalOp::Finally,
+                    "H" => TemporalOp::Historically,
+                    "O" => TemporalOp::Once,
+                    _ => unreachable!(),
+                };
+                let interval = self.parse_interval(pairs.next().expect("mismatch between grammar and AST"))?;
+                let inner = self.parse_unary(pairs.next().expect("mismatch between grammar and AST"))?;
+                Some(Formula::Temporal(op, interval, Box::new(inner)))
+            }
+            Rule::BooleanLiteral => Some(Formula::Literal(pair.as_str() == "true")),
+            Rule::Ident => Some(Formula::Signal(pair.as_str().to_string(), pair.as_span().into())),
+            Rule::Comparison => {
+                let mut pairs = pair.into_inner();
+                let mut signals = Vec::new();
+                let lhs = self.parse_arith(pairs.next().expect("mismatch between grammar and AST"), &mut signals);
+                let op = match pairs.next().expect("mismatch between grammar and AST").as_str() {
+                    "=" => "==",
+                    op => op,
+                };
+                let rhs = self.parse_arith(pairs.next().expect("mismatch between grammar and AST"), &mut signals);
+                Some(Formula::Comparison(format!("{} {} {}", lhs, op, rhs), signals))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Translates an arithmetic expression, signals hold their last value
+    fn parse_arith(&self, pair: Pair<'_, Rule>, signals: &mut Vec<(String, Span)>) -> String {
+        match pair.as_rule() {
+            Rule::Arith => pair.into_inner().map(|pair| self.parse_arith(pair, signals)).collect::<Vec<_>>().join(" "),
+            Rule::ArithOp => pair.as_str().to_string(),
+            Rule::Number if pair.as_str().contains('.') => pair.as_str().to_string(),
+            Rule::Number => format!("{}.0", pair.as_str()),
+            Rule::Ident => {
+                signals.push((pair.as_str().to_string(), pair.as_span().into()));
+                format!("{}.hold(or: 0.0)", pair.as_str())
+            }
+            Rule::ParenthesizedArith => {
+                let inner = pair.into_inner().next().expect("mismatch between grammar and AST");
+                format!("({})", self.parse_arith(inner, signals))
+            }
+            Rule::NegatedArith => {
+                let inner = pair.into_inner().next().expect("mismatch between grammar and AST");
+                format!("-{}", self.parse_arith(inner, signals))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn parse_interval(&self, pair: Pair<'_, Rule>) -> Option<Interval> {
+        let span: Span = pair.as_span().into();
+        let mut bounds = pair.into_inner().map(|bound| milliseconds(bound.as_str()));
+        let (start, end) = (bounds.next().expect("mismatch between grammar and AST"), bounds.next()?);
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end && start % self.period == 0 && end % self.period == 0 => {
+                Some(Interval { start, end })
+            }
+            (Some(start), Some(end)) if start > end => {
+                self.handler.error_with_span(
+                    "the lower bound of the interval exceeds the upper bound",
+                    LabeledSpan::new(span, "empty interval", true),
+                );
+                None
+            }
+            _ => {
+                self.handler.error_with_span(
+                    "the bounds of the interval have to be multiples of the sampling period",
+                    LabeledSpan::new(span, &format!("expected multiples of {}", duration(self.period)), true),
+                );
+                None
+            }
+        }
+    }
+
+    /// Declares streams for the formula and its subformulas and returns the name of the stream and its delay.
+    fn translate_formula(&mut self, requirement: &str, formula: &Formula) -> (String, i64) {
+        match formula {
+            Formula::Literal(value) => (self.declare(requirement, value.to_string()), 0),
+            Formula::Signal(name, span) => {
+                self.use_signal(name, SignalTy::Bool, *span);
+                (self.declare(requirement, format!("{}.hold(or: false)", name)), 0)
+            }
+            Formula::Comparison(comparison, signals) => {
+                for (signal, span) in signals {
+                    self.use_signal(signal, SignalTy::Float, *span);
+                }
+                (self.declare(requirement, comparison.clone()), 0)
+            }
+            Formula::Not(inner) => {
+                let (inner, delay) = self.translate_formula(requirement, inner);
+                (self.declare(requirement, format!("!{}", inner)), delay)
+            }
+            Formula::Binary(op, lhs, rhs) => {
+                let (lhs, lhs_delay) = self.translate_formula(requirement, lhs);
+                let (rhs, rhs_delay) = self.translate_formula(requirement, rhs);
+                let delay = lhs_delay.max(rhs_delay);
+                let (lhs, rhs) = (self.align(&lhs, lhs_delay, delay), self.align(&rhs, rhs_delay, delay));
+                let expression = match op {
+                    BoolOp::And => format!("{} ∧ {}", lhs, rhs),
+                    BoolOp::Or => format!("{} ∨ {}", lhs, rhs),
+                    BoolOp::Implies => format!("!{} ∨ {}", lhs, rhs),
+                };
+                (self.declare(requirement, expression), delay)
+            }
+            Formula::Since(lhs, rhs) => {
+                let (lhs, lhs_delay) = self.translate_formula(requirement, lhs);
+                let (rhs, rhs_delay) = self.translate_formula(requirement, rhs);
+                let delay = lhs_delay.max(rhs_delay);
+                let (lhs, rhs) = (self.align(&lhs, lhs_delay, delay), self.align(&rhs, rhs_delay, delay));
+                (self.declare(requirement, format!("{} since {}", lhs, rhs)), delay)
+            }
+            Formula::Temporal(op, interval, inner) => {
+                let (inner, inner_delay) = self.translate_formula(requirement, inner);
+                // the window covers all samples of the interval, including both bounds
+                let length = duration(interval.end - interval.start + self.period);
+                let (aggregation, delay) = match op {
+                    TemporalOp::Globally => ("∀", inner_delay + interval.end),
+                    TemporalOp::Finally => ("∃", inner_delay + interval.end),
+                    TemporalOp::Historically => ("∀", inner_delay - interval.start),
+                    TemporalOp::Once => ("∃", inner_delay - interval.start),
+                };
+                let window = format!("{}.aggregate(over: {}, using: {})", inner, length, aggregation);
+                (self.declare(requirement, window), delay)
+            }
+        }
+    }
+
+    /// Accesses the verdicts of `stream` with delay `from` such that they have the delay `to`.
+    fn align(&self, stream: &str, from: i64, to: i64) -> String {
+        debug_assert!(from <= to);
+        if from == to {
+            stream.to_string()
+        } else {
+            format!("{}.offset(by: -{}).defaults(to: true)", stream, duration(to - from))
+        }
+    }
+
+    fn declare(&mut self, requirement: &str, expression: String) -> String {
+        let name = format!("{}_{}", requirement, self.counter);
+        self.counter += 1;
+        self.streams.push(format!("output {} @{}Hz := {}", name, self.frequency, expression));
+        name
+    }
+
+    fn use_signal(&mut self, name: &str, ty: SignalTy, span: Span) {
+        match self.signals.get(name) {
+            Some((known, _)) if *known != ty => {
+                self.handler.error_with_span(
+                    &format!("the signal `{}` is used both as a number and as a proposition", name),
+                    LabeledSpan::new(span, "conflicting use", true),
+                );
+            }
+            Some(_) => {}
+            None => {
+                self.signals.insert(name.to_string(), (ty, span));
+            }
+        }
+    }
+}
+
+/// Converts a number of seconds into milliseconds, if it is a whole number of milliseconds
+fn milliseconds(seconds: &str) -> Option<i64> {
+    let (int, frac) = match seconds.find('.') {
+        Some(ix) => (&seconds[..ix], &seconds[ix + 1..]),
+        None => (seconds, ""),
+    };
+    if frac.len() > 3 {
+        return None;
+    }
+    let frac = format!("{:0<3}", frac);
+    Some(int.parse::<i64>().ok()? * 1000 + frac.parse::<i64>().ok()?)
+}
+
+/// Formats a number of milliseconds as a duration literal
+fn duration(milliseconds: i64) -> String {
+    if milliseconds % 1000 == 0 {
+        format!("{}s", milliseconds / 1000)
+    } else {
+        format!("{}ms", milliseconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::SourceMapper;
+    use crate::FrontendConfig;
+    use std::path::PathBuf;
+
+    fn translate_with_handler(content: &str) -> (String, Handler) {
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
+        let spec = translate(content, &handler, 10).unwrap_or_else(|e| panic!("{}", e));
+        (spec, handler)
+    }
+
+    #[test]
+    fn translate_future_requirement() {
+        let (spec, handler) = translate_with_handler("alt_gear: G[0,10](alt > 5 -> F[0,2] gear_down)\n");
+        assert_eq!(handler.emitted_errors(), 0);
+        assert_eq!(handler.emitted_warnings(), 1);
+        assert_eq!(
+            spec,
+            "input alt: Float64
+input gear_down: Bool
+output alt_gear_0 @10Hz := alt.hold(or: 0.0) > 5.0
+output alt_gear_1 @10Hz := gear_down.hold(or: false)
+output alt_gear_2 @10Hz := alt_gear_1.aggregate(over: 2100ms, using: ∃)
+output alt_gear_3 @10Hz := !alt_gear_0.offset(by: -2s).defaults(to: true) ∨ alt_gear_2
+output alt_gear_4 @10Hz := alt_gear_3.aggregate(over: 10100ms, using: ∀)
+trigger !(alt_gear_4) \"violation of `alt_gear` 12s ago\"
+"
+        );
+    }
+
+    #[test]
+    fn translate_past_requirement() {
+        let (spec, handler) =
+            translate_with_handler("# comment\nH[0.5,1](speed - limit <= 0.5) | override; safe S reset\n");
+        assert_eq!(handler.emitted_errors(), 0);
+        assert_eq!(handler.emitted_warnings(), 0);
+        assert!(spec.contains("output requirement_0_1 @10Hz := requirement_0_0.aggregate(over: 600ms, using: ∀)"));
+        assert!(spec.contains(
+            "output requirement_0_3 @10Hz := requirement_0_1.offset(by: -500ms).defaults(to: true) ∨ requirement_0_2"
+        ));
+        assert!(spec.contains("trigger !(requirement_0_3) \"violation of `requirement_0`\""));
+        assert!(spec.contains("output requirement_1_2 @10Hz := requirement_1_0 since requirement_1_1"));
+    }
+
+    #[test]
+    fn translation_is_valid_specification() {
+        let (spec, _) = translate_with_handler(
+            "alt_gear: G[0,10](alt > 5 -> F[0,2] gear_down)\nH[0.5,1](speed - limit <= 0.5) | override\nsafe S !reset",
+        );
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), &spec));
+        let ast = crate::parse::parse(&spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        assert!(crate::analysis::analyze(&ast, &handler, FrontendConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn unsupported_requirements() {
+        // until operator, bounds that are no multiple of the sampling period, empty interval, and conflicting signal
+        let (_, handler) = translate_with_handler("a U[0,1] b\nG[0,0.05] a\nF[2,1] a\nx > 1 & x");
+        assert_eq!(handler.emitted_errors(), 4);
+    }
+
+    #[test]
+    fn invalid_syntax() {
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), "G[0,1]"));
+        assert!(translate("G[0,1]", &handler, 10).is_err());
+    }
+}
@@ -4,6 +4,7 @@
 
 pub(crate) mod check;
 pub(crate) mod unifier;
+pub(crate) mod unit;
 
 use crate::parse::NodeId;
 use lazy_static::lazy_static;
@@ -62,10 +63,30 @@ pub enum ValueTy {
     String,
     /// A byte string type.
     Bytes,
+    /// A span of time, e.g., the difference between two timestamps.
+    Duration,
+    /// A point in time, e.g., the result of `now()`.
+    Timestamp,
+    /// An IPv4 address, e.g., `10.0.0.1`.
+    IPv4Addr,
+    /// An IPv6 address, e.g., `fe80::1`.
+    IPv6Addr,
+    /// An IPv4 network, e.g., `10.0.0.0/8`.
+    IPv4Net,
+    /// An IPv6 network, e.g., `fe80::/10`.
+    IPv6Net,
     /// A tuple of value types.
     Tuple(Vec<ValueTy>),
+    /// A tuple of value types whose components can be accessed by name.
+    NamedTuple(Vec<(String, ValueTy)>),
     /// an optional value type, e.g., resulting from accessing a stream with offset -1
     Option(Box<ValueTy>),
+    /// An immutable set of values of the given type.
+    Set(Box<ValueTy>),
+    /// An immutable sequence of values of the given type, e.g., the parts of a split string.
+    List(Box<ValueTy>),
+    /// An immutable map from keys of the first type to values of the second type.
+    Map(Box<ValueTy>, Box<ValueTy>),
     /// Used during type inference
     Infer(ValueVar),
     /// Constraint used during type inference
@@ -236,6 +257,13 @@ impl Activation<NodeId> {
             return true;
         }
         match (self, other) {
+            // a conjunction or disjunction of a single condition is equivalent to the condition itself
+            (Activation::Conjunction(args), _) | (Activation::Disjunction(args), _) if args.len() == 1 => {
+                args[0].implies_valid(other)
+            }
+            (_, Activation::Conjunction(args)) | (_, Activation::Disjunction(args)) if args.len() == 1 => {
+                self.implies_valid(&args[0])
+            }
             (Activation::Conjunction(left), Activation::Conjunction(right)) => {
                 right.iter().all(|cond| left.contains(cond))
             }
@@ -316,6 +344,12 @@ lazy_static! {
         ("Float64", &ValueTy::Float(F64)),
         ("String", &ValueTy::String),
         ("Bytes", &ValueTy::Bytes),
+        ("Duration", &ValueTy::Duration),
+        ("Timestamp", &ValueTy::Timestamp),
+        ("IPv4Addr", &ValueTy::IPv4Addr),
+        ("IPv6Addr", &ValueTy::IPv6Addr),
+        ("IPv4Net", &ValueTy::IPv4Net),
+        ("IPv6Net", &ValueTy::IPv6Net),
     ];
     static ref REDUCED_PRIMITIVE_TYPES: Vec<(&'static str, &'static ValueTy)> = vec![
         ("Bool", &ValueTy::Bool),
@@ -324,6 +358,12 @@ lazy_static! {
         ("Float64", &ValueTy::Float(F64)),
         ("String", &ValueTy::String),
         ("Bytes", &ValueTy::Bytes),
+        ("Duration", &ValueTy::Duration),
+        ("Timestamp", &ValueTy::Timestamp),
+        ("IPv4Addr", &ValueTy::IPv4Addr),
+        ("IPv6Addr", &ValueTy::IPv6Addr),
+        ("IPv4Net", &ValueTy::IPv4Net),
+        ("IPv6Net", &ValueTy::IPv6Net),
     ];
     static ref PRIMITIVE_TYPES_ALIASES: Vec<(&'static str, &'static ValueTy)> =
         vec![("Int", &ValueTy::Int(I64)), ("UInt", &ValueTy::UInt(U64)), ("Float", &ValueTy::Float(F64)),];
@@ -372,7 +412,9 @@ impl ValueTy {
         match self {
             Error => true,
             Tuple(args) => args.iter().any(|el| el.is_error()),
-            Option(ty) => ty.is_error(),
+            NamedTuple(fields) => fields.iter().any(|(_, el)| el.is_error()),
+            Option(ty) | Set(ty) | List(ty) => ty.is_error(),
+            Map(key, value) => key.is_error() || value.is_error(),
             _ => false,
         }
     }
@@ -385,16 +427,43 @@ impl ValueTy {
     pub fn is_primitive(&self) -> bool {
         use self::ValueTy::*;
         match self {
-            Bool | Int(_) | UInt(_) | Float(_) | String | Bytes => true,
+            Bool | Int(_) | UInt(_) | Float(_) | String | Bytes | Duration | Timestamp | IPv4Addr | IPv6Addr
+            | IPv4Net | IPv6Net => true,
             _ => false,
         }
     }
 
+    /**
+    Resolves a field access on a tuple type.
+
+    Components of a tuple can be accessed by their position, fields of a named tuple additionally by their name.
+    Returns the index and the type of the component, or `None` if there is no such field.
+    */
+    pub(crate) fn get_field(&self, field: &str) -> Option<(usize, &ValueTy)> {
+        match self {
+            ValueTy::Tuple(inner) => field.parse::<usize>().ok().and_then(|idx| inner.get(idx).map(|ty| (idx, ty))),
+            ValueTy::NamedTuple(fields) => match fields.iter().position(|(name, _)| name == field) {
+                Some(idx) => Some((idx, &fields[idx].1)),
+                None => field.parse::<usize>().ok().and_then(|idx| fields.get(idx).map(|(_, ty)| (idx, ty))),
+            },
+            _ => None,
+        }
+    }
+
     /// Replaces parameters by the given list
     pub(crate) fn replace_params(&self, infer_vars: &[ValueVar]) -> ValueTy {
         match self {
             &ValueTy::Param(id, _) => ValueTy::Infer(infer_vars[id as usize]),
             ValueTy::Option(t) => ValueTy::Option(t.replace_params(infer_vars).into()),
+            ValueTy::Set(t) => ValueTy::Set(t.replace_params(infer_vars).into()),
+            ValueTy::List(t) => ValueTy::List(t.replace_params(infer_vars).into()),
+            ValueTy::Tuple(elements) => ValueTy::Tuple(elements.iter().map(|t| t.replace_params(infer_vars)).collect()),
+            ValueTy::NamedTuple(fields) => ValueTy::NamedTuple(
+                fields.iter().map(|(name, t)| (name.clone(), t.replace_params(infer_vars))).collect(),
+            ),
+            ValueTy::Map(k, v) => {
+                ValueTy::Map(k.replace_params(infer_vars).into(), v.replace_params(infer_vars).into())
+            }
             ValueTy::Infer(_) | ValueTy::Constr(_) => self.clone(),
             _ if self.is_primitive() => self.clone(),
             _ => unreachable!("replace_param for {}", self),
@@ -406,6 +475,17 @@ impl ValueTy {
         match self {
             &ValueTy::Param(id, _) => generics[id as usize].clone(),
             ValueTy::Option(t) => ValueTy::Option(t.replace_params_with_ty(generics).into()),
+            ValueTy::Set(t) => ValueTy::Set(t.replace_params_with_ty(generics).into()),
+            ValueTy::List(t) => ValueTy::List(t.replace_params_with_ty(generics).into()),
+            ValueTy::Tuple(elements) => {
+                ValueTy::Tuple(elements.iter().map(|t| t.replace_params_with_ty(generics)).collect())
+            }
+            ValueTy::NamedTuple(fields) => ValueTy::NamedTuple(
+                fields.iter().map(|(name, t)| (name.clone(), t.replace_params_with_ty(generics))).collect(),
+            ),
+            ValueTy::Map(k, v) => {
+                ValueTy::Map(k.replace_params_with_ty(generics).into(), v.replace_params_with_ty(generics).into())
+            }
             ValueTy::Infer(_) | ValueTy::Constr(_) => self.clone(),
             _ if self.is_primitive() => self.clone(),
             _ => unreachable!("replace_param for {}", self),
@@ -416,7 +496,13 @@ impl ValueTy {
     pub(crate) fn replace_constr(&self) -> ValueTy {
         match &self {
             ValueTy::Tuple(t) => ValueTy::Tuple(t.iter().map(|el| el.replace_constr()).collect()),
+            ValueTy::NamedTuple(fields) => {
+                ValueTy::NamedTuple(fields.iter().map(|(name, el)| (name.clone(), el.replace_constr())).collect())
+            }
             ValueTy::Option(ty) => ValueTy::Option(ty.replace_constr().into()),
+            ValueTy::Set(ty) => ValueTy::Set(ty.replace_constr().into()),
+            ValueTy::List(ty) => ValueTy::List(ty.replace_constr().into()),
+            ValueTy::Map(key, value) => ValueTy::Map(key.replace_constr().into(), value.replace_constr().into()),
             ValueTy::Constr(c) => match c.has_default() {
                 Some(d) => d,
                 None => ValueTy::Error,
@@ -445,11 +531,24 @@ impl std::fmt::Display for ValueTy {
             ValueTy::Float(F64) => write!(f, "Float64"),
             ValueTy::String => write!(f, "String"),
             ValueTy::Bytes => write!(f, "Bytes"),
+            ValueTy::Duration => write!(f, "Duration"),
+            ValueTy::Timestamp => write!(f, "Timestamp"),
+            ValueTy::IPv4Addr => write!(f, "IPv4Addr"),
+            ValueTy::IPv6Addr => write!(f, "IPv6Addr"),
+            ValueTy::IPv4Net => write!(f, "IPv4Net"),
+            ValueTy::IPv6Net => write!(f, "IPv6Net"),
             ValueTy::Option(ty) => write!(f, "{}?", ty),
+            ValueTy::Set(ty) => write!(f, "Set<{}>", ty),
+            ValueTy::List(ty) => write!(f, "List<{}>", ty),
+            ValueTy::Map(key, value) => write!(f, "Map<{}, {}>", key, value),
             ValueTy::Tuple(inner) => {
                 let joined: Vec<String> = inner.iter().map(|e| format!("{}", e)).collect();
                 write!(f, "({})", joined.join(", "))
             }
+            ValueTy::NamedTuple(fields) => {
+                let joined: Vec<String> = fields.iter().map(|(name, e)| format!("{}: {}", name, e)).collect();
+                write!(f, "({})", joined.join(", "))
+            }
             ValueTy::Infer(id) => write!(f, "?{}", id),
             ValueTy::Constr(constr) => write!(f, "{{{}}}", constr),
             ValueTy::Param(_, name) => write!(f, "{}", name),
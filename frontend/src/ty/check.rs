@@ -7,11 +7,12 @@
 //! * [Ena (union-find package)](https://crates.io/crates/ena)
 
 use super::unifier::{InferError, UnifiableTy, Unifier, ValueUnifier, ValueVar};
+use super::unit::{Unit, UnitTy};
 use super::{Activation, Freq, StreamTy, TypeConstraint, ValueTy};
 use crate::analysis::naming::{Declaration, DeclarationTable};
 use crate::ast::{
-    BinOp, Constant, Expression, ExpressionKind, FunctionName, Input, Literal, Offset, Output, RTLolaAst,
-    StreamAccessKind, Trigger, Type, TypeKind, WindowOperation,
+    ActivationCondition, BinOp, Constant, Expression, ExpressionKind, FunctionName, Input, LetPattern, Literal, Offset,
+    Output, RTLolaAst, StreamAccessKind, Trigger, Type, TypeKind, UnOp, WindowOperation,
 };
 use crate::parse::{NodeId, Span};
 use crate::reporting::{Handler, LabeledSpan};
@@ -22,6 +23,7 @@ use num::traits::ops::inv::Inv;
 use num::Signed;
 use std::{
     collections::{HashMap, HashSet},
+    net::IpAddr,
     rc::Rc,
 };
 use uom::si::frequency::hertz;
@@ -38,6 +40,12 @@ pub(crate) struct TypeAnalysis<'a, 'b, 'c> {
     /// maps function-like nodes (UnOp, BinOp, Func, Method) to the generic parameters
     generic_function_vars: HashMap<NodeId, Vec<ValueVar>>,
     stream_ty: HashMap<NodeId, StreamTy>,
+    /// maps constants, streams, and let bindings to their physical unit
+    units: HashMap<NodeId, UnitTy>,
+    /// outputs whose expression was already checked for units
+    unit_checked: HashSet<NodeId>,
+    /// maps expressions to the factor converting their value into the unit required by their context
+    unit_conversions: HashMap<NodeId, f64>,
 }
 
 #[derive(Debug)]
@@ -46,6 +54,7 @@ pub(crate) struct TypeTable {
     stream_tt: HashMap<NodeId, StreamTy>,
     func_tt: HashMap<NodeId, Vec<ValueTy>>,
     acti_cond: HashMap<NodeId, Activation<crate::ir::StreamReference>>,
+    unit_conversions: HashMap<NodeId, f64>,
 }
 
 impl TypeTable {
@@ -64,6 +73,11 @@ impl TypeTable {
     pub(crate) fn get_acti_cond(&self, nid: NodeId) -> &Activation<crate::ir::StreamReference> {
         &self.acti_cond[&nid]
     }
+
+    /// Returns the factor converting the value of the expression into the unit required by its context, if any.
+    pub(crate) fn get_unit_conversion(&self, nid: NodeId) -> Option<f64> {
+        self.unit_conversions.get(&nid).copied()
+    }
 }
 
 impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
@@ -76,6 +90,9 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             value_vars: HashMap::new(),
             generic_function_vars: HashMap::new(),
             stream_ty: HashMap::new(),
+            units: HashMap::new(),
+            unit_checked: HashSet::new(),
+            unit_conversions: HashMap::new(),
         }
     }
 
@@ -87,6 +104,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
 
         self.assign_types(spec);
+        self.check_units(spec);
 
         let type_table = self.extract_type_table(spec);
         if self.handler.contains_error() {
@@ -99,6 +117,16 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
     fn extract_type_table(&mut self, spec: &'a RTLolaAst) -> TypeTable {
         let value_nids: Vec<NodeId> = self.value_vars.keys().cloned().collect();
         let vtt: HashMap<NodeId, ValueTy> = value_nids.into_iter().map(|nid| (nid, self.get_type(nid))).collect();
+        for output in &spec.outputs {
+            // e.g., the element type of an empty `Set()` that is never inserted into
+            match vtt.get(&output.id) {
+                Some(ty) if ty.is_error() => self.handler.error_with_span(
+                    &format!("could not determine type of `{}`", output.name.name),
+                    LabeledSpan::new(output.name.span, "consider giving a type annotation", true),
+                ),
+                _ => {}
+            }
+        }
         let stt: HashMap<NodeId, StreamTy> = self.stream_ty.clone();
 
         let mut func_tt = HashMap::with_capacity(self.generic_function_vars.len());
@@ -120,7 +148,9 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             .flat_map(|&nid| self.get_activation_condition(spec, nid).map(|ac| (nid, ac)))
             .collect();
 
-        TypeTable { value_tt: vtt, stream_tt: stt, func_tt, acti_cond }
+        let unit_conversions = self.unit_conversions.clone();
+
+        TypeTable { value_tt: vtt, stream_tt: stt, func_tt, acti_cond, unit_conversions }
     }
 
     fn imports(&mut self, spec: &'a RTLolaAst) {
@@ -129,6 +159,9 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             match import.name.name.as_str() {
                 "math" => stdlib::import_math_method(&mut self.method_lookup),
                 "regex" => stdlib::import_regex_method(&mut self.method_lookup),
+                "string" => stdlib::import_string_method(&mut self.method_lookup),
+                // the functions of the geo module are not available as methods
+                "geo" => {}
                 n => self.handler.error_with_span(
                     &format!("unresolved import `{}`", n),
                     LabeledSpan::new(import.name.span, &format!("no `{}` in the root", n), true),
@@ -150,6 +183,11 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 debug!("type inference failed for {}", input);
             });
         }
+        for input in &spec.inputs {
+            self.infer_input_assumption(input).unwrap_or_else(|_| {
+                debug!("type inference failed for {}", input);
+            });
+        }
 
         for output in &spec.outputs {
             self.infer_output(output).unwrap_or_else(|_| {
@@ -175,6 +213,12 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 stream_ty_inference_failed = true;
             });
         }
+        for trigger in &spec.trigger {
+            self.infer_trigger_clock(trigger).unwrap_or_else(|_| {
+                debug!("stream type inference failed for {}", trigger);
+                stream_ty_inference_failed = true;
+            });
+        }
 
         if stream_ty_inference_failed {
             return;
@@ -197,10 +241,12 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             });
         }
         for trigger in &spec.trigger {
-            self.check_output_clock_expression(&self.stream_ty[&trigger.id].clone(), &trigger.expression)
-                .unwrap_or_else(|_| {
+            let stream_ty = self.stream_ty[&trigger.id].clone();
+            for expr in std::iter::once(&trigger.expression).chain(trigger.placeholders()) {
+                self.check_output_clock_expression(&stream_ty, expr).unwrap_or_else(|_| {
                     debug!("stream type check for {}", trigger);
                 });
+            }
         }
     }
 
@@ -244,6 +290,14 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         // stream type
         self.stream_ty.insert(input.id, StreamTy::new_event(Activation::Stream(input.id)));
 
+        // collections are accumulated by output streams and cannot be read from input sources
+        if matches!(self.unifier.get_normalized_type(var), Some(ty) if Self::contains_collection(&ty)) {
+            self.handler.error_with_span(
+                "input streams cannot have collection types",
+                LabeledSpan::new(input.ty.span, "found `Set`, `List`, or `Map` type", true),
+            );
+        }
+
         // determine parameters
         let mut param_types = Vec::new();
         for param in &input.params {
@@ -258,6 +312,24 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         Ok(())
     }
 
+    fn contains_collection(ty: &ValueTy) -> bool {
+        match ty {
+            ValueTy::Set(_) | ValueTy::List(_) | ValueTy::Map(_, _) => true,
+            ValueTy::Option(inner) => Self::contains_collection(inner),
+            ValueTy::Tuple(elements) => elements.iter().any(Self::contains_collection),
+            ValueTy::NamedTuple(fields) => fields.iter().any(|(_, ty)| Self::contains_collection(ty)),
+            _ => false,
+        }
+    }
+
+    /// the assumption of an input has to be a boolean expression
+    fn infer_input_assumption(&mut self, input: &'a Input) -> Result<(), ()> {
+        match input.assumption.as_ref() {
+            Some(assumption) => self.infer_expression(assumption, Some(ValueTy::Bool)),
+            None => Ok(()),
+        }
+    }
+
     /// infers value types if given (`:` expression)
     fn infer_output(&mut self, output: &'a Output) -> Result<(), ()> {
         trace!("infer type for {} (NodeId = {})", output, output.id);
@@ -292,7 +364,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         let frequency;
         let activation;
 
-        if let Ok((f, a)) = self.parse_at_expression(output) {
+        if let Ok((f, a)) = self.parse_at_expression(output.id, &output.extend) {
             frequency = f;
             activation = a;
         } else {
@@ -333,7 +405,8 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         // stream type
                         inner.push(output.id)
                     }
-                    Declaration::Param(_) => {}
+                    // the stream type of a let binding is determined by its value
+                    Declaration::Param(_) | Declaration::Let(_) => {}
                     Declaration::Type(_) | Declaration::Func(_) | Declaration::ParamOut(_) => {
                         unreachable!("ensured by naming analysis {:?}", decl)
                     }
@@ -381,6 +454,10 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             Field(base, _) => {
                 self.infer_stream_ty_from_expression(&base, inner);
             }
+            Let(_, value, body) => {
+                self.infer_stream_ty_from_expression(value, inner);
+                self.infer_stream_ty_from_expression(body, inner);
+            }
             ParenthesizedExpression(_, expr, _) => {
                 self.infer_stream_ty_from_expression(&expr, inner);
             }
@@ -504,7 +581,8 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         let out_ty = &self.stream_ty[&output.id];
                         self.check_stream_types_are_compatible(stream_ty, out_ty, expr.span)?;
                     }
-                    Declaration::Param(_) => {}
+                    // the stream type of a let binding is determined by its value
+                    Declaration::Param(_) | Declaration::Let(_) => {}
                     Declaration::Type(_) | Declaration::Func(_) | Declaration::ParamOut(_) => {
                         unreachable!("ensured by naming analysis {:?}", decl)
                     }
@@ -591,6 +669,10 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             Field(base, _) => {
                 self.check_output_clock_expression(stream_ty, base)?;
             }
+            Let(_, value, body) => {
+                self.check_output_clock_expression(stream_ty, value)?;
+                self.check_output_clock_expression(stream_ty, body)?;
+            }
             ParenthesizedExpression(_, expr, _) => {
                 self.check_output_clock_expression(stream_ty, expr)?;
             }
@@ -691,8 +773,12 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
     }
 
-    fn parse_at_expression(&mut self, output: &'a Output) -> Result<(Option<Freq>, Option<Activation<NodeId>>), ()> {
-        if let Some(expr) = &output.extend.expr {
+    fn parse_at_expression(
+        &mut self,
+        stream_id: NodeId,
+        extend: &'a ActivationCondition,
+    ) -> Result<(Option<Freq>, Option<Activation<NodeId>>), ()> {
+        if let Some(expr) = &extend.expr {
             match &expr.kind {
                 ExpressionKind::Lit(_) => match expr.parse_freqspec() {
                     Ok(f) => Ok((Some(Freq::new(f)), None)),
@@ -701,7 +787,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         Err(())
                     }
                 },
-                _ => match self.parse_activation_condition(output.id, expr) {
+                _ => match self.parse_activation_condition(stream_id, expr) {
                     Ok(act) => Ok((None, Some(act))),
                     Err(_) => Err(()),
                 },
@@ -784,6 +870,16 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 // ?ty_var = `ty`
                 self.unifier.unify_var_ty(ty_var, ty).expect("cannot fail as `ty_var` is fresh");
             }
+            TypeKind::NamedTuple(fields) => {
+                let mut inner: Vec<(String, ValueTy)> = Vec::with_capacity(fields.len());
+                for (name, ty) in fields {
+                    inner.push((name.name.clone(), ValueTy::Infer(self.infer_type(ty)?)));
+                }
+                // ?ty_var = `(name1: ty1, name2: ty2, ..)`
+                self.unifier
+                    .unify_var_ty(ty_var, ValueTy::NamedTuple(inner))
+                    .expect("cannot fail as `ty_var` is fresh");
+            }
             TypeKind::Optional(ty) => {
                 self.infer_type(ty)?;
                 let inner = self.value_vars[&ty.id];
@@ -792,6 +888,33 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     .unify_var_ty(ty_var, ValueTy::Option(ValueTy::Infer(inner).into()))
                     .expect("cannot fail as `ty_var` is fresh");
             }
+            TypeKind::Set(ty) => {
+                let element = self.infer_type(ty)?;
+                // ?ty_var = `Set<ty>`
+                self.unifier
+                    .unify_var_ty(ty_var, ValueTy::Set(ValueTy::Infer(element).into()))
+                    .expect("cannot fail as `ty_var` is fresh");
+            }
+            TypeKind::List(ty) => {
+                let element = self.infer_type(ty)?;
+                // ?ty_var = `List<ty>`
+                self.unifier
+                    .unify_var_ty(ty_var, ValueTy::List(ValueTy::Infer(element).into()))
+                    .expect("cannot fail as `ty_var` is fresh");
+            }
+            TypeKind::Map(key, value) => {
+                let key = self.infer_type(key)?;
+                let value = self.infer_type(value)?;
+                // ?ty_var = `Map<key, value>`
+                self.unifier
+                    .unify_var_ty(ty_var, ValueTy::Map(ValueTy::Infer(key).into(), ValueTy::Infer(value).into()))
+                    .expect("cannot fail as `ty_var` is fresh");
+            }
+            TypeKind::Unit(ty, _) => {
+                // units are checked separately, see `check_units`
+                let inner = self.infer_type(ty)?;
+                self.unifier.unify_var_var(ty_var, inner).expect("cannot fail as `ty_var` is fresh");
+            }
         }
         Ok(ty_var)
     }
@@ -866,23 +989,52 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
     fn infer_trigger_expression(&mut self, trigger: &'a Trigger) -> Result<(), ()> {
         trace!("infer type for {}", trigger);
 
-        // for triggers, there are no stream type annotations, so just infer
-        let mut inner = Vec::new();
-        self.infer_stream_ty_from_expression(&trigger.expression, &mut inner);
-        self.stream_ty.insert(trigger.id, StreamTy::Infer(inner));
-
         // make sure that NodeId of trigger is assigned Bool
         let var = self.new_value_var(trigger.id);
         self.unifier.unify_var_ty(var, ValueTy::Bool).expect("cannot fail as `var` is fresh");
 
+        // placeholders in the message can be of any type
+        for expr in trigger.placeholders() {
+            self.infer_expression(expr, None)?;
+        }
+
         self.infer_expression(&trigger.expression, Some(ValueTy::Infer(var)))
     }
 
+    fn infer_trigger_clock(&mut self, trigger: &'a Trigger) -> Result<(), ()> {
+        trace!("infer trigger clock for {} (NodeId = {})", trigger, trigger.id);
+
+        // the pacing of a trigger is either annotated or inferred from its expression
+        let stream_ty = match self.parse_at_expression(trigger.id, &trigger.extend)? {
+            (Some(f), _) => StreamTy::new_periodic(f),
+            (None, Some(act)) => StreamTy::new_event(act),
+            (None, None) => {
+                let mut inner = Vec::new();
+                self.infer_stream_ty_from_expression(&trigger.expression, &mut inner);
+                for expr in trigger.placeholders() {
+                    self.infer_stream_ty_from_expression(expr, &mut inner);
+                }
+                StreamTy::Infer(inner)
+            }
+        };
+        self.stream_ty.insert(trigger.id, stream_ty);
+        Ok(())
+    }
+
     fn get_constraint_for_literal(&self, lit: &Literal) -> Option<ValueTy> {
         use crate::ast::LitKind::*;
         Some(match &lit.kind {
             Str(_) | RawStr(_) => ValueTy::String,
             Bool(_) => ValueTy::Bool,
+            Subnet(_) => match lit.parse_subnet() {
+                Ok((IpAddr::V4(_), _)) => ValueTy::IPv4Net,
+                Ok((IpAddr::V6(_), _)) => ValueTy::IPv6Net,
+                Err(reason) => {
+                    self.handler.error_with_span("invalid subnet", LabeledSpan::new(lit.span, &reason, true));
+                    return None;
+                }
+            },
+            Numeric(_, Some(_)) if lit.parse_duration().is_ok() => ValueTy::Duration,
             Numeric(val, unit) => {
                 if let Some(unit) = unit {
                     self.handler.error_with_span(
@@ -947,6 +1099,13 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         let param_var = self.value_vars[&param.id];
                         self.unifier.unify_var_var(var, param_var).map_err(|err| self.handle_error(err, expr.span))?;
                     }
+                    Declaration::Let(binding) => {
+                        // value type only
+                        let binding_var = self.value_vars[&binding.id];
+                        self.unifier
+                            .unify_var_var(var, binding_var)
+                            .map_err(|err| self.handle_error(err, expr.span))?;
+                    }
                     Declaration::Type(_) | Declaration::Func(_) | Declaration::ParamOut(_) => {
                         unreachable!("ensured by naming analysis {:?}", decl)
                     }
@@ -970,7 +1129,24 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 self.infer_function_application(expr.id, var, expr.span, &op.get_func_decl(), &[], &[appl])?;
             }
             Binary(op, left, right) => {
-                self.infer_function_application(expr.id, var, expr.span, &op.get_func_decl(), &[], &[left, right])?;
+                let fun_decl = match op {
+                    BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem | BinOp::Pow => {
+                        // arithmetic on time values and optional values depends on the operand types, so infer them first
+                        self.infer_expression(left, None)?;
+                        self.infer_expression(right, None)?;
+                        let left_ty = self.unifier.get_normalized_type(self.value_vars[&left.id]);
+                        let right_ty = self.unifier.get_normalized_type(self.value_vars[&right.id]);
+                        let is_optional = |ty: &Option<ValueTy>| matches!(ty, Some(ValueTy::Option(_)));
+                        if is_optional(&left_ty) || is_optional(&right_ty) {
+                            op.get_optional_func_decl(is_optional(&left_ty), is_optional(&right_ty))
+                        } else {
+                            op.get_time_func_decl(left_ty.as_ref(), right_ty.as_ref())
+                                .unwrap_or_else(|| op.get_func_decl())
+                        }
+                    }
+                    _ => op.get_func_decl(),
+                };
+                self.infer_function_application(expr.id, var, expr.span, &fun_decl, &[], &[left, right])?;
             }
             Default(left, right) => {
                 self.infer_expression(left, Some(ValueTy::Option(ValueTy::Infer(var).into())))?;
@@ -1028,6 +1204,10 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 }
 
                 let params: Vec<&Expression> = params.iter().map(Box::as_ref).collect();
+                let fun_decl = self.check_regex_function(fun_decl, &params)?;
+                if fun_decl.name.name.name == "within" {
+                    self.check_polygon(params[2])?;
+                }
 
                 self.infer_function_application(
                     expr.id,
@@ -1037,6 +1217,8 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     types.as_slice(),
                     params.as_slice(),
                 )?;
+
+                self.declarations.insert(expr.id, Declaration::Func(fun_decl));
             }
             Method(base, name, types, params) => {
                 // recursion
@@ -1052,17 +1234,18 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         }
 
                         let parameters: Vec<_> = std::iter::once(base).chain(params).map(Box::as_ref).collect();
+                        let fun_decl = self.check_regex_function(Rc::new(fun_decl.clone()), &parameters)?;
 
                         self.infer_function_application(
                             expr.id,
                             var,
                             expr.span,
-                            fun_decl,
+                            &fun_decl,
                             types.as_slice(),
                             parameters.as_slice(),
                         )?;
 
-                        self.declarations.insert(expr.id, Declaration::Func(Rc::new(fun_decl.clone())));
+                        self.declarations.insert(expr.id, Declaration::Func(fun_decl));
                     } else {
                         self.handler.error_with_span(
                             &format!("unknown method `{}`", name),
@@ -1103,22 +1286,23 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 let infered = ty.normalize_ty(&mut self.unifier);
 
                 debug!("{} {}", base, infered);
-                match infered {
-                    ValueTy::Tuple(inner) => {
-                        let num: usize = ident.name.parse::<usize>().expect("checked in AST verifier");
-                        if num >= inner.len() {
-                            self.handler.error_with_span(
-                                &format!("Try to access tuple at position {}", num),
-                                LabeledSpan::new(ident.span, "", true),
-                            );
-                            return Err(());
-                        }
-                        // ?var = inner[num]
+                match (infered.get_field(&ident.name), &infered) {
+                    (Some((_, field_ty)), _) => {
+                        // ?var = field_ty
                         self.unifier
-                            .unify_var_ty(var, inner[num].clone())
+                            .unify_var_ty(var, field_ty.clone())
                             .map_err(|err| self.handle_error(err, expr.span))?;
                     }
-                    _ => {
+                    (None, ValueTy::Tuple(_)) | (None, ValueTy::NamedTuple(_))
+                        if ident.name.parse::<usize>().is_ok() =>
+                    {
+                        self.handler.error_with_span(
+                            &format!("Try to access tuple at position {}", ident.name),
+                            LabeledSpan::new(ident.span, "", true),
+                        );
+                        return Err(());
+                    }
+                    (None, _) => {
                         self.handler.error_with_span(
                             &format!("Type `{}` has no field `{}`", infered, ident.name),
                             LabeledSpan::new(ident.span, "unknown field", true),
@@ -1127,6 +1311,30 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     }
                 }
             }
+            Let(pattern, value, body) => {
+                // recursion
+                self.infer_expression(value, None)?;
+                let value_var = self.value_vars[&value.id];
+                match pattern {
+                    LetPattern::Ident(binding) => {
+                        // ?binding = ?value
+                        let binding_var = self.new_value_var(binding.id);
+                        self.unifier
+                            .unify_var_var(binding_var, value_var)
+                            .map_err(|err| self.handle_error(err, value.span))?;
+                    }
+                    LetPattern::Tuple(bindings) => {
+                        let components: Vec<ValueTy> =
+                            bindings.iter().map(|binding| ValueTy::Infer(self.new_value_var(binding.id))).collect();
+                        // ?value = (?binding1, ?binding2, ..)
+                        self.unifier
+                            .unify_var_ty(value_var, ValueTy::Tuple(components))
+                            .map_err(|err| self.handle_error(err, value.span))?;
+                    }
+                }
+                // ?var = ?body
+                self.infer_expression(body, Some(ValueTy::Infer(var)))?;
+            }
             ParenthesizedExpression(_, expr, _) => {
                 self.infer_expression(expr, target)?;
                 self.unifier
@@ -1275,6 +1483,123 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
     }
 
+    /// Validates the regular expression of the functions of the `regex` module at specification time and determines
+    /// the return type of `captures`, which is a tuple with an optional value for each capture group.
+    fn check_regex_function(&mut self, fun_decl: Rc<FuncDecl>, params: &[&Expression]) -> Result<Rc<FuncDecl>, ()> {
+        let name = fun_decl.name.name.name.as_str();
+        if !matches!(name, "matches" | "capture" | "captures")
+            || fun_decl.name.arg_names.get(1).and_then(Option::as_ref).map(|arg| arg.name.as_str()) != Some("regex")
+        {
+            return Ok(fun_decl);
+        }
+        let source = match &params[1].kind {
+            ExpressionKind::Lit(Literal { kind: crate::ast::LitKind::Str(source), .. })
+            | ExpressionKind::Lit(Literal { kind: crate::ast::LitKind::RawStr(source), .. }) => source,
+            _ => {
+                self.handler.error_with_span(
+                    "the regular expression has to be a string literal",
+                    LabeledSpan::new(params[1].span, "expected a string literal", true),
+                );
+                return Err(());
+            }
+        };
+        let groups = if fun_decl.parameters[0] == ValueTy::Bytes {
+            regex::bytes::Regex::new(source).map(|re| re.captures_len())
+        } else {
+            regex::Regex::new(source).map(|re| re.captures_len())
+        };
+        // the first group is the whole match
+        let groups = match groups {
+            Ok(groups) => groups - 1,
+            Err(err) => {
+                self.handler.error_with_span(
+                    "invalid regular expression",
+                    LabeledSpan::new(params[1].span, &err.to_string(), true),
+                );
+                return Err(());
+            }
+        };
+        match name {
+            "capture" => {
+                if let Some(group) = Self::integer_literal(params[2]).filter(|&group| group as usize > groups) {
+                    self.handler.error_with_span(
+                        &format!("the regular expression has no capture group {}", group),
+                        LabeledSpan::new(params[2].span, &format!("the expression has {} groups", groups), true),
+                    );
+                    return Err(());
+                }
+                Ok(fun_decl)
+            }
+            "captures" if groups == 0 => {
+                self.handler.error_with_span(
+                    "the regular expression has no capture groups",
+                    LabeledSpan::new(params[1].span, "expected at least one group", true),
+                );
+                Err(())
+            }
+            "captures" => {
+                let group = ValueTy::Option(fun_decl.parameters[0].clone().into());
+                Ok(Rc::new(FuncDecl { return_type: ValueTy::Tuple(vec![group; groups]), ..(*fun_decl).clone() }))
+            }
+            _ => Ok(fun_decl),
+        }
+    }
+
+    /// Validates the polygon of `within`, a string literal or constant of the form `"lat lon, lat lon, lat lon, ..."`.
+    fn check_polygon(&mut self, polygon: &Expression) -> Result<(), ()> {
+        let source = match self.string_constant(polygon) {
+            Some(source) => source,
+            None => {
+                self.handler.error_with_span(
+                    "the polygon has to be a string literal or constant",
+                    LabeledSpan::new(polygon.span, "expected a string literal", true),
+                );
+                return Err(());
+            }
+        };
+        let vertices: Option<Vec<(f64, f64)>> = source
+            .split(',')
+            .map(|vertex| {
+                let coordinates: Vec<f64> = vertex.split_whitespace().map(str::parse).collect::<Result<_, _>>().ok()?;
+                match coordinates.as_slice() {
+                    &[lat, lon] if lat.abs() <= 90.0 && lon.abs() <= 180.0 => Some((lat, lon)),
+                    _ => None,
+                }
+            })
+            .collect();
+        match vertices {
+            Some(vertices) if vertices.len() >= 3 => Ok(()),
+            _ => {
+                self.handler.error_with_span(
+                    "invalid polygon",
+                    LabeledSpan::new(
+                        polygon.span,
+                        "expected at least three vertices of the form `lat lon`, separated by commas",
+                        true,
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Resolves a string literal or the value of a string constant.
+    fn string_constant(&self, expr: &Expression) -> Option<String> {
+        use crate::ast::LitKind::*;
+        let literal = match &expr.kind {
+            ExpressionKind::Lit(literal) => literal,
+            ExpressionKind::Ident(_) => match &self.declarations[&expr.id] {
+                Declaration::Const(constant) => &constant.literal,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match &literal.kind {
+            Str(source) | RawStr(source) => Some(source.clone()),
+            _ => None,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn infer_function_application(
         &mut self,
@@ -1342,6 +1667,9 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
         for input in &spec.inputs {
             debug!("{} has type {}", input, self.unifier.get_normalized_type(self.value_vars[&input.id]).unwrap());
+            if let Some(assumption) = input.assumption.as_ref() {
+                self.check_literal_sizes(assumption);
+            }
         }
         for output in &spec.outputs {
             debug!("{} has type {}", output, self.unifier.get_normalized_type(self.value_vars[&output.id]).unwrap());
@@ -1349,6 +1677,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
         for trigger in &spec.trigger {
             self.check_literal_sizes(&trigger.expression);
+            trigger.placeholders().for_each(|expr| self.check_literal_sizes(expr));
         }
     }
 
@@ -1421,6 +1750,381 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         })
     }
 
+    /// Checks the physical units of all streams, e.g., `Float64<m/s>`, and records the scaling factors converting
+    /// values between compatible units, e.g., from `ft` into `m`.
+    fn check_units(&mut self, spec: &RTLolaAst) {
+        for constant in &spec.constants {
+            let unit = match &constant.ty {
+                Some(ty) => self.declared_unit(ty),
+                None => None,
+            };
+            // constants without a unit adapt to their context like literals
+            self.units.insert(constant.id, unit.map_or(UnitTy::Any, UnitTy::Known));
+        }
+        for input in &spec.inputs {
+            let unit = self.declared_unit(&input.ty).unwrap_or_else(Unit::dimensionless);
+            self.units.insert(input.id, UnitTy::Known(unit));
+        }
+        for output in &spec.outputs {
+            if let Some(unit) = self.declared_unit(&output.ty) {
+                self.units.insert(output.id, UnitTy::Known(unit));
+            }
+        }
+        for input in &spec.inputs {
+            if let Some(assumption) = input.assumption.as_ref() {
+                let _ = self.check_expression_unit(assumption);
+            }
+        }
+        for output in &spec.outputs {
+            self.check_output_unit(output);
+        }
+        for trigger in &spec.trigger {
+            let _ = self.check_expression_unit(&trigger.expression);
+            for expr in trigger.placeholders() {
+                let _ = self.check_expression_unit(expr);
+            }
+        }
+    }
+
+    /// Returns the unit annotated on a floating-point type, e.g., `Float64<m/s>`.
+    fn declared_unit(&mut self, ty: &Type) -> Option<Unit> {
+        match &ty.kind {
+            TypeKind::Unit(inner, factors) => {
+                if !matches!(self.get_type(inner.id), ValueTy::Float(_)) {
+                    self.handler.error_with_span(
+                        "units can only be attached to floating-point types",
+                        LabeledSpan::new(ty.span, &format!("`{}` is not a floating-point type", inner), true),
+                    );
+                    return None;
+                }
+                let mut unit = Unit::dimensionless();
+                for (symbol, exponent) in factors {
+                    match Unit::lookup(&symbol.name) {
+                        Some(factor) => {
+                            unit = self
+                                .unit_in_range(factor.powi(*exponent).and_then(|factor| unit.mul(&factor)), ty.span)
+                                .ok()?
+                        }
+                        None => {
+                            self.handler.error_with_span(
+                                &format!("unknown unit `{}`", symbol.name),
+                                LabeledSpan::new(symbol.span, "not a known unit", true),
+                            );
+                            return None;
+                        }
+                    }
+                }
+                Some(unit)
+            }
+            TypeKind::Tuple(elements) => {
+                elements.iter().for_each(|element| self.reject_nested_unit(element));
+                None
+            }
+            TypeKind::NamedTuple(fields) => {
+                fields.iter().for_each(|(_, element)| self.reject_nested_unit(element));
+                None
+            }
+            TypeKind::Optional(inner) | TypeKind::Set(inner) | TypeKind::List(inner) => {
+                self.reject_nested_unit(inner);
+                None
+            }
+            TypeKind::Map(key, value) => {
+                self.reject_nested_unit(key);
+                self.reject_nested_unit(value);
+                None
+            }
+            TypeKind::Simple(_) | TypeKind::Inferred => None,
+        }
+    }
+
+    /// Units are only supported on the value type of streams and constants, not on their components.
+    fn reject_nested_unit(&mut self, ty: &Type) {
+        if let TypeKind::Unit(..) = ty.kind {
+            self.handler.error_with_span(
+                "units are only supported on the type of streams and constants",
+                LabeledSpan::new(ty.span, "unit on a component type", true),
+            );
+        } else {
+            self.declared_unit(ty);
+        }
+    }
+
+    /// Determines the unit of an output stream, checking its expression against the annotated unit if present.
+    fn check_output_unit(&mut self, output: &Output) -> UnitTy {
+        if !self.unit_checked.insert(output.id) {
+            // already checked, or referenced by its own expression without an annotated unit
+            return self.units.get(&output.id).cloned().unwrap_or(UnitTy::Any);
+        }
+        let unit = self.check_expression_unit(&output.expression).unwrap_or(UnitTy::Any);
+        match self.units.get(&output.id).cloned() {
+            Some(UnitTy::Known(declared)) => {
+                let _ = self.convert_unit(&output.expression, unit, &declared);
+                UnitTy::Known(declared)
+            }
+            _ => {
+                self.units.insert(output.id, unit.clone());
+                unit
+            }
+        }
+    }
+
+    /// Records the conversion of the value of `expr` with the given unit into the `target` unit.
+    fn convert_unit(&mut self, expr: &Expression, unit: UnitTy, target: &Unit) -> Result<(), ()> {
+        let unit = match unit {
+            UnitTy::Known(unit) => unit,
+            UnitTy::Any => return Ok(()),
+        };
+        match unit.conversion_to(target) {
+            Some(factor) => {
+                if factor != 1.0 {
+                    self.unit_conversions.insert(expr.id, factor);
+                }
+                Ok(())
+            }
+            None => {
+                self.handler.error_with_span(
+                    &format!("mismatched units `{}` and `{}`", target, unit),
+                    LabeledSpan::new(expr.span, &format!("expected `{}`, found `{}`", target, unit), true),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Unifies the units of the given expressions by converting them into the first known unit.
+    fn unify_units(&mut self, exprs: &[&Expression]) -> Result<UnitTy, ()> {
+        let mut units = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            units.push(self.check_expression_unit(expr)?);
+        }
+        let target = match units.iter().find_map(|unit| match unit {
+            UnitTy::Known(unit) => Some(unit.clone()),
+            UnitTy::Any => None,
+        }) {
+            Some(target) => target,
+            None => return Ok(UnitTy::Any),
+        };
+        for (expr, unit) in exprs.iter().zip(units) {
+            self.convert_unit(expr, unit, &target)?;
+        }
+        Ok(UnitTy::Known(target))
+    }
+
+    /// Checks that the given expressions are dimensionless, converting, e.g., `deg` into `rad`.
+    fn expect_dimensionless(&mut self, exprs: &[&Expression]) -> Result<UnitTy, ()> {
+        for expr in exprs {
+            let unit = self.check_expression_unit(expr)?;
+            self.convert_unit(expr, unit, &Unit::dimensionless())?;
+        }
+        Ok(UnitTy::dimensionless())
+    }
+
+    /// Checks the units within an expression and returns its unit.
+    fn check_expression_unit(&mut self, expr: &Expression) -> Result<UnitTy, ()> {
+        use crate::ast::ExpressionKind::*;
+        let unit = match &expr.kind {
+            Lit(_) | MissingExpression => UnitTy::Any,
+            Ident(_) => match self.declarations[&expr.id].clone() {
+                Declaration::Const(constant) => self.units[&constant.id].clone(),
+                Declaration::In(input) => self.units[&input.id].clone(),
+                Declaration::Out(output) => self.check_output_unit(&output),
+                Declaration::Let(binding) => self.units.get(&binding.id).cloned().unwrap_or(UnitTy::Any),
+                _ => UnitTy::dimensionless(),
+            },
+            Offset(inner, _) | StreamAccess(inner, _) | ParenthesizedExpression(_, inner, _) => {
+                self.check_expression_unit(inner)?
+            }
+            SlidingWindowAggregation { expr: inner, aggregation, .. } => {
+                let unit = self.check_expression_unit(inner)?;
+                match aggregation {
+                    WindowOperation::Count | WindowOperation::Conjunction | WindowOperation::Disjunction => {
+                        UnitTy::dimensionless()
+                    }
+                    // integrals over plain numbers remain plain numbers
+                    WindowOperation::Integral => match unit {
+                        UnitTy::Known(unit) if !unit.is_dimensionless() => {
+                            let seconds = Unit::lookup("s").expect("seconds are a unit");
+                            UnitTy::Known(self.unit_in_range(unit.mul(&seconds), expr.span)?)
+                        }
+                        unit => unit,
+                    },
+                    WindowOperation::Product => self.expect_dimensionless(&[inner])?,
+                    WindowOperation::Min | WindowOperation::Max | WindowOperation::Sum | WindowOperation::Average => {
+                        unit
+                    }
+                }
+            }
+            Ite(cond, cons, alt) => {
+                self.check_expression_unit(cond)?;
+                self.unify_units(&[cons, alt])?
+            }
+            Default(inner, default) => self.unify_units(&[inner, default])?,
+            Unary(UnOp::Neg, operand) => self.check_expression_unit(operand)?,
+            Unary(_, operand) => {
+                self.check_expression_unit(operand)?;
+                UnitTy::dimensionless()
+            }
+            Binary(op, left, right) => match op {
+                BinOp::Add | BinOp::Sub | BinOp::Rem => self.unify_units(&[left, right])?,
+                BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+                    self.unify_units(&[left, right])?;
+                    UnitTy::dimensionless()
+                }
+                BinOp::Mul | BinOp::Div => {
+                    let lhs = self.check_expression_unit(left)?;
+                    let rhs = self.check_expression_unit(right)?;
+                    let (lhs, rhs) = match (lhs, rhs) {
+                        (UnitTy::Known(lhs), rhs) if !lhs.is_dimensionless() => {
+                            (UnitTy::Known(lhs), Self::seconds_unit(right, rhs))
+                        }
+                        (lhs, UnitTy::Known(rhs)) if !rhs.is_dimensionless() => {
+                            (Self::seconds_unit(left, lhs), UnitTy::Known(rhs))
+                        }
+                        units => units,
+                    };
+                    match (lhs, rhs) {
+                        (UnitTy::Any, UnitTy::Any) => UnitTy::Any,
+                        (lhs, rhs) => {
+                            let (lhs, rhs) = (lhs.or_dimensionless(), rhs.or_dimensionless());
+                            let unit = if *op == BinOp::Mul { lhs.mul(&rhs) } else { lhs.div(&rhs) };
+                            UnitTy::Known(self.unit_in_range(unit, expr.span)?)
+                        }
+                    }
+                }
+                BinOp::Pow => self.power_unit(left, right)?,
+                BinOp::And | BinOp::Or | BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor | BinOp::Shl | BinOp::Shr => {
+                    self.check_expression_unit(left)?;
+                    self.check_expression_unit(right)?;
+                    UnitTy::dimensionless()
+                }
+            },
+            Function(name, _, args) | Method(_, name, _, args) => {
+                let args: Vec<&Expression> = match &expr.kind {
+                    Method(base, ..) => std::iter::once(base).chain(args).map(Box::as_ref).collect(),
+                    _ => args.iter().map(Box::as_ref).collect(),
+                };
+                match name.name.name.as_str() {
+                    // functions returning a value of the same unit as their arguments
+                    "abs" | "min" | "max" | "cast" | "or" | "unwrap_or" => self.unify_units(&args)?,
+                    "sqrt" => match self.check_expression_unit(args[0])? {
+                        UnitTy::Known(unit) => match unit.sqrt() {
+                            Some(root) => UnitTy::Known(root),
+                            None => {
+                                self.handler.error_with_span(
+                                    &format!("cannot take the square root of a value in `{}`", unit),
+                                    LabeledSpan::new(args[0].span, "the unit has odd exponents", true),
+                                );
+                                return Err(());
+                            }
+                        },
+                        UnitTy::Any => UnitTy::Any,
+                    },
+                    "floor" | "ceil" | "round" | "clamp" | "hypot" => self.unify_units(&args)?,
+                    "pow" => self.power_unit(args[0], args[1])?,
+                    // the arguments of `atan2` are coordinates, only their ratio matters
+                    "atan2" => {
+                        self.unify_units(&args)?;
+                        UnitTy::dimensionless()
+                    }
+                    // mathematical functions are only defined on plain numbers
+                    "sin" | "cos" | "tan" | "arctan" | "asin" | "acos" | "exp" | "ln" | "log10" => {
+                        self.expect_dimensionless(&args)?
+                    }
+                    // time values in seconds adapt to their context, see `seconds_unit`
+                    "seconds" => {
+                        self.check_expression_unit(args[0])?;
+                        UnitTy::Any
+                    }
+                    _ => {
+                        for arg in args {
+                            self.check_expression_unit(arg)?;
+                        }
+                        UnitTy::dimensionless()
+                    }
+                }
+            }
+            Tuple(elements) => {
+                for element in elements {
+                    self.check_expression_unit(element)?;
+                }
+                UnitTy::dimensionless()
+            }
+            Field(base, _) => {
+                self.check_expression_unit(base)?;
+                UnitTy::dimensionless()
+            }
+            Let(pattern, value, body) => {
+                let unit = self.check_expression_unit(value)?;
+                match pattern {
+                    LetPattern::Ident(binding) => {
+                        self.units.insert(binding.id, unit);
+                    }
+                    LetPattern::Tuple(bindings) => {
+                        for binding in bindings {
+                            self.units.insert(binding.id, UnitTy::dimensionless());
+                        }
+                    }
+                }
+                self.check_expression_unit(body)?
+            }
+        };
+        Ok(unit)
+    }
+
+    /// Returns the unit `s` for time values converted into seconds, e.g., `elapsed.seconds()`, so that dividing a value
+    /// with a unit by them yields a rate. Like integrals, rates over plain numbers remain plain numbers.
+    fn seconds_unit(expr: &Expression, unit: UnitTy) -> UnitTy {
+        match &expr.kind {
+            ExpressionKind::ParenthesizedExpression(_, inner, _) => Self::seconds_unit(inner, unit),
+            ExpressionKind::Method(_, name, _, _) if name.name.name == "seconds" && unit == UnitTy::Any => {
+                UnitTy::Known(Unit::lookup("s").expect("seconds are a unit"))
+            }
+            _ => unit,
+        }
+    }
+
+    /// Checks the units of `base ** exponent`, where the exponent of a value with a unit has to be an integer literal.
+    fn power_unit(&mut self, base: &Expression, exponent: &Expression) -> Result<UnitTy, ()> {
+        let base_unit = self.check_expression_unit(base)?;
+        self.expect_dimensionless(&[exponent])?;
+        match base_unit {
+            UnitTy::Known(unit) if !unit.is_dimensionless() => match Self::integer_literal(exponent) {
+                Some(power) => Ok(UnitTy::Known(self.unit_in_range(unit.powi(power), exponent.span)?)),
+                None => {
+                    self.handler.error_with_span(
+                        &format!("cannot raise a value in `{}` to a variable power", unit),
+                        LabeledSpan::new(exponent.span, "expected an integer literal", true),
+                    );
+                    Err(())
+                }
+            },
+            base_unit => Ok(base_unit),
+        }
+    }
+
+    /// Reports units with exponents exceeding the supported range, e.g., `m^100 * m^100`.
+    fn unit_in_range(&self, unit: Option<Unit>, span: Span) -> Result<Unit, ()> {
+        unit.ok_or_else(|| {
+            self.handler.error_with_span(
+                "unit exponent out of range",
+                LabeledSpan::new(span, "the exponents of units have to be between -128 and 127", true),
+            )
+        })
+    }
+
+    /// Returns the value of an integral numeric literal, e.g., the exponent in `x ** 2`.
+    fn integer_literal(expr: &Expression) -> Option<i32> {
+        use crate::ast::LitKind::*;
+        match &expr.kind {
+            ExpressionKind::Lit(lit) => match &lit.kind {
+                Numeric(val, None) => val.parse::<f64>().ok().filter(|v| v.fract() == 0.0).map(|v| v as i32),
+                _ => None,
+            },
+            ExpressionKind::ParenthesizedExpression(_, inner, _) => Self::integer_literal(inner),
+            _ => None,
+        }
+    }
+
     fn handle_error(&mut self, mut err: InferError, span: Span) {
         err.normalize_types(&mut self.unifier);
         match err {
@@ -1720,6 +2424,24 @@ mod tests {
         assert_eq!(0, num_type_errors(spec));
     }
 
+    #[test]
+    fn paced_trigger() {
+        let spec = "input a: Int32\ninput b: Bool\ntrigger @1Hz a.hold().defaults(to: 0) > 50\ntrigger @(a ∧ b) b";
+        assert_eq!(0, num_type_errors(spec));
+        // a periodic trigger cannot synchronously access an event-based stream and vice versa
+        assert_eq!(1, num_type_errors("input a: Int32\ntrigger @1Hz a > 50"));
+        assert_eq!(1, num_type_errors("input a: Int32\ninput b: Bool\ntrigger @a b"));
+    }
+
+    #[test]
+    fn trigger_message_placeholders() {
+        let spec = "input gps: UInt8\ninput alt: Float64\ntrigger gps < 2 \"only {gps} satellites, alt={alt.hold()}\"";
+        assert_eq!(0, num_type_errors(spec));
+        // placeholders are type checked and have to respect the pacing of the trigger
+        assert_eq!(1, num_type_errors("input gps: UInt8\ntrigger gps < 2 \"{gps + true}\""));
+        assert_eq!(1, num_type_errors("input gps: UInt8\ninput alt: Float64\ntrigger @gps gps < 2 \"{alt}\""));
+    }
+
     #[test]
     fn faulty_trigger() {
         //let spec = "trigger failed := 1";
@@ -1850,6 +2572,127 @@ mod tests {
         assert_eq!(get_type(spec), ValueTy::Bool);
     }
 
+    #[test]
+    fn test_math_functions() {
+        let spec = "import math\ninput x: Float32\ninput y: Float32\noutput a := atan2(y, x) + x.hypot(y) + ln(exp(x)) + log10(x) + pow(x, y) + tan(asin(x) + acos(y))\noutput b := floor(x) + ceil(x) + x.round()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F32));
+        let spec = "import math\ninput i: Int16\noutput a := clamp(i, -5, 5)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Int(IntTy::I16));
+        assert_eq!(1, num_type_errors("import math\ninput i: Int64\noutput a := exp(i)"));
+        assert_eq!(1, num_type_errors("import math\ninput x: Float64\ninput i: Int64\noutput a := atan2(x, i)"));
+    }
+
+    #[test]
+    fn test_string_functions() {
+        let spec = "import string\ninput s: String\noutput o := s.to_lower().starts_with(\"get\") && ends_with(s, \"/\") || s.contains(\"..\")";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+        let spec = "import string\ninput s: String\noutput o := concat(s.substring(from: 0, to: 3).defaults(to: \"\"), s.to_upper())";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::String);
+        let spec = "import string\ninput s: String\noutput o := s.split(\",\").at(2).defaults(to: \"0\").parse_int()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::Int(IntTy::I64).into()));
+        let spec = "import string\ninput s: String\noutput o: List<String> := split(s, \",\")\noutput n := o.len()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(1, num_type_errors("import string\ninput s: String\noutput o: List<UInt8> := s.split(\",\")"));
+        assert_eq!(1, num_type_errors("input s: List<String>"));
+        assert_eq!(1, num_type_errors("import string\ninput s: String\noutput o: UInt8 := s.len()"));
+        assert_eq!(1, num_type_errors("input s: String\noutput o := s.len()"));
+    }
+
+    #[test]
+    fn test_regex_captures() {
+        let spec = "import regex\ninput s: String\noutput o := s.capture(regex: r\"USER (\\w+)\", group: 1)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::String.into()));
+        let spec = "import regex\ninput s: String\noutput o := captures(s, regex: r\"(\\d+)\\.(\\d+)\")";
+        assert_eq!(0, num_type_errors(spec));
+        let group = ValueTy::Option(ValueTy::String.into());
+        assert_eq!(get_type(spec), ValueTy::Tuple(vec![group.clone(), group]));
+        let spec = "import regex\ninput b: Bytes\noutput o := b.captures(regex: \"(a+)b\").0";
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::Bytes.into()));
+    }
+
+    #[test]
+    fn test_regex_validation() {
+        assert_eq!(1, num_type_errors("import regex\ninput s: String\noutput o := s.matches(regex: \"(a\")"));
+        assert_eq!(1, num_type_errors("import regex\ninput s: String\noutput o := s.matches(regex: s)"));
+        assert_eq!(
+            1,
+            num_type_errors("import regex\ninput s: String\noutput o := s.capture(regex: \"(a)\", group: 2)")
+        );
+        assert_eq!(1, num_type_errors("import regex\ninput s: String\noutput o := s.captures(regex: \"a\")"));
+    }
+
+    #[test]
+    fn test_bytes_methods() {
+        let spec = "input b: Bytes\noutput o := b.len() > 4 && b.starts_with(\"GET\") && b.find(\"HTTP\").is_some()";
+        assert_eq!(0, num_type_errors(spec));
+        let spec = "input b: Bytes\noutput o := b.slice(from: 2, to: 4).defaults(to: b).to_hex()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::String);
+        let spec = "input b: Bytes\noutput o := b.read_u16_be(at: 2)";
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::UInt(UIntTy::U16).into()));
+        let spec = "input b: Bytes\noutput o := b.read_i32_le(at: 0)";
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::Int(IntTy::I32).into()));
+        assert_eq!(1, num_type_errors("input b: Bytes\noutput o := b.read_u24_be(at: 0)"));
+    }
+
+    #[test]
+    fn test_geo_functions() {
+        let spec = "import geo\ninput lat: Float64\ninput lon: Float64\noutput d := distance(lat, lon, 49.25, 7.04) + bearing(lat, lon, 49.25, 7.04)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+        let spec = "import geo\ninput lat: Float64\ninput lon: Float64\noutput e := enu(lat, lon, 0.0, 49.25, 7.04, 0.0).north";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+        let spec = "import geo\nconstant fence: String := \"49 7, 49 8, 50 8\"\ninput lat: Float64\ninput lon: Float64\noutput i := within(lat, lon, polygon: fence) && within(lat, lon, polygon: \"0 0, 0 1, 1 1\")";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(
+            1,
+            num_type_errors("import geo\ninput lat: Float64\noutput i := within(lat, lat, polygon: \"0 0, 0 1\")")
+        );
+        assert_eq!(
+            1,
+            num_type_errors(
+                "import geo\ninput lat: Float64\noutput i := within(lat, lat, polygon: \"0 0, 0 1, 91 0\")"
+            )
+        );
+        assert_eq!(
+            1,
+            num_type_errors(
+                "import geo\ninput lat: Float64\ninput s: String\noutput i := within(lat, lat, polygon: s)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_ip_addresses() {
+        let spec = "constant lan: IPv4Net := 192.168.0.0/16\ninput src: IPv4Addr\noutput o := src.in_subnet(10.0.0.0/8) || src.in_subnet(lan) || src.is_private() || src.is_loopback() || src.is_multicast()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+        let spec = "input src: IPv6Addr\noutput o := src.in_subnet(fe80::/10) && !src.is_private()";
+        assert_eq!(0, num_type_errors(spec));
+        let spec = "input src: IPv6Addr\ninput net: IPv6Net\noutput o := src.in_subnet(net) || src.in_subnet(::ffff:0.0.0.0/96)";
+        assert_eq!(0, num_type_errors(spec));
+        let spec = "input src: IPv6Addr\noutput o := src.to_string()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::String);
+        // invalid subnets
+        assert_eq!(1, num_type_errors("input src: IPv4Addr\noutput o := src.in_subnet(10.0.0.1/8)"));
+        assert_eq!(1, num_type_errors("input src: IPv4Addr\noutput o := src.in_subnet(10.0.0.0/33)"));
+        assert_eq!(1, num_type_errors("input src: IPv4Addr\noutput o := src.in_subnet(10.0.0.256/32)"));
+        assert_eq!(1, num_type_errors("input src: IPv6Addr\noutput o := src.in_subnet(fe80::1/10)"));
+        assert_eq!(1, num_type_errors("input src: IPv4Addr\noutput o := src.in_subnet(fe80::/10)"));
+        assert_eq!(1, num_type_errors("input src: IPv6Addr\noutput o := src.in_subnet(10.0.0.0/8)"));
+        assert_eq!(1, num_type_errors("input src: IPv4Addr\noutput o := src.in_subnet(\"10.0.0.0/8\")"));
+        // addresses are no numbers
+        assert_eq!(1, num_type_errors("input src: IPv4Addr\noutput o: UInt32 := src"));
+    }
+
     #[test]
     fn test_input_lookup() {
         let spec = "input a: UInt8\n output b: UInt8 := a";
@@ -2022,6 +2865,156 @@ mod tests {
         assert_eq!(get_type(spec), ValueTy::Bool);
     }
 
+    #[test]
+    fn test_input_assumption() {
+        assert_eq!(0, num_type_errors("input alt: Float64 assume alt >= 0.0 && alt < 10000.0"));
+        assert_eq!(1, num_type_errors("input alt: Float64 assume alt + 1.0"));
+        assert_eq!(1, num_type_errors("input a: UInt8 assume a < 300"));
+    }
+
+    #[test]
+    fn test_units() {
+        let spec = "input alt: Float64<ft>\ninput gnd: Float64<m>\ninput spd: Float32<kn>\ninput t: Float64<s>\noutput agl: Float64<m> := alt - gnd\noutput v: Float64<m/s> := (alt - gnd) / t\noutput low := agl < 100.0 || cast(spd) > v\noutput a: Float64<m/s^2> := v / t";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type("input alt: Float64<ft>\noutput a: Float64<m> := alt"), ValueTy::Float(FloatTy::F64));
+    }
+
+    #[test]
+    fn test_unit_mismatch() {
+        assert_eq!(1, num_type_errors("input a: Float64<m>\ninput t: Float64<s>\noutput b := a + t"));
+        assert_eq!(1, num_type_errors("input a: Float64<m>\ninput t: Float64<s>\noutput b: Float64<m/s> := a * t"));
+        assert_eq!(1, num_type_errors("input a: Float64<m>\ninput b: Float64\noutput c := a < b"));
+        assert_eq!(0, num_type_errors("input a: Float64<m>\ninput b: Float64\noutput c := a * b > 2.0"));
+    }
+
+    #[test]
+    fn test_unit_annotations() {
+        assert_eq!(1, num_type_errors("input a: Int64<m>"));
+        assert_eq!(1, num_type_errors("input a: Float64<furlong>"));
+        assert_eq!(1, num_type_errors("input a: (Float64<m>, Float64)"));
+        assert_eq!(0, num_type_errors("constant c: Float64<ft> := 1000.0\ninput a: Float64<m>\noutput b := a > c"));
+        assert_eq!(1, num_type_errors("input a: Float64<m^127>\noutput b := a * a"));
+        assert_eq!(1, num_type_errors("input a: Float64<m^100>\noutput b := a ** 2.0"));
+    }
+
+    #[test]
+    fn test_unit_functions() {
+        let spec = "import math\ninput a: Float64<m>\ninput d: Float64<deg>\noutput s := sin(d)\noutput r: Float64<m> := sqrt(a * a)\noutput m := max(a, 3.0)\noutput p: Float64<m^3> := a ** 3.0";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(1, num_type_errors("import math\ninput a: Float64<m>\noutput s := sin(a)"));
+        assert_eq!(1, num_type_errors("import math\ninput a: Float64<m>\noutput s := sqrt(a)"));
+        assert_eq!(1, num_type_errors("input a: Float64<m>\ninput b: Float64\noutput s := a ** b"));
+        let spec = "import math\ninput x: Float64<ft>\ninput y: Float64<m>\ninput d: Float64<deg>\noutput h: Float64<m> := hypot(x, y)\noutput a := atan2(x, y) + tan(d)\noutput v: Float64<m^2> := pow(y, 2.0)\noutput c: Float64<m> := clamp(x, 0.0, y)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(1, num_type_errors("import math\ninput x: Float64<m>\noutput a := exp(x)"));
+    }
+
+    #[test]
+    fn test_named_tuple() {
+        let spec = "output out: (lat: Float64, lon: Float64) := (1.0, 2.0)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(
+            get_type(spec),
+            ValueTy::NamedTuple(vec![
+                ("lat".to_string(), ValueTy::Float(FloatTy::F64)),
+                ("lon".to_string(), ValueTy::Float(FloatTy::F64))
+            ])
+        );
+    }
+
+    #[test]
+    fn test_named_tuple_access() {
+        let spec = "input pos: (lat: Float64, lon: Float64)\noutput out: Float64 := pos.lon";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+    }
+
+    #[test]
+    fn test_named_tuple_positional_access() {
+        let spec = "input pos: (lat: Float64, valid: Bool)\noutput out: Bool := pos.1";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+    }
+
+    #[test]
+    fn test_named_tuple_unknown_field() {
+        let spec = "input pos: (lat: Float64, lon: Float64)\noutput out: Float64 := pos.alt";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_named_tuple_field_names_mismatch() {
+        let spec = "input pos: (lat: Float64, lon: Float64)\noutput out: (x: Float64, y: Float64) := pos";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_let_binding() {
+        let spec = "input a: Int8\noutput out: Int16 := let x = a + 1 in x * x";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Int(IntTy::I16));
+    }
+
+    #[test]
+    fn test_let_destructuring() {
+        let spec = "input pos: (lat: Float64, lon: Float64)\noutput out: Float64 := let (lat, lon) = pos in lat - lon";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+    }
+
+    #[test]
+    fn test_let_destructuring_faulty_len() {
+        let spec = "input pos: (Float64, Float64)\noutput out: Float64 := let (x, y, z) = pos in x";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_let_destructuring_faulty_type() {
+        let spec = "input pos: (Float64, Bool)\noutput out: Float64 := let (x, y) = pos in y";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_access_to_later_recursive_stream() {
+        let spec = "input a: Bool\noutput o := h\noutput h := a ∨ h.offset(by: -1).defaults(to: false)";
+        assert_eq!(0, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_past_time_operators() {
+        let spec =
+            "input a: Bool\ninput b: Int64\noutput o := once(a) ∧ historically(b > 0)\noutput s := a since b = 0";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+        assert_eq!(1, num_type_errors("input a: Int64\noutput o := once(a)"));
+    }
+
+    #[test]
+    fn test_signal_operators() {
+        let spec = "input a: Float64\ninput b: Float64\noutput d := a.derivative()\noutput e := ema(a + b, alpha: 0.1)\noutput l @1Hz := a.hold().defaults(to: 0.0).lowpass(cutoff: 2Hz)\n";
+        assert_eq!(0, num_type_errors(spec));
+        let spec = "input a: Int32\noutput d := a.derivative() + a.lowpass(cutoff: 500mHz)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+        let spec = "input a: Float64<m>\noutput v: Float64<m/s> := a.derivative()\noutput l: Float64<m> := a.lowpass(cutoff: 1Hz)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(1, num_type_errors("input a: Float64<m>\noutput t := now()\noutput v: Float64<m> := a / (t - t.offset(by: -1).defaults(to: t)).seconds()"));
+        assert_eq!(1, num_type_errors("input a: Float64<m>\noutput v: Float64<m> := a.derivative()"));
+    }
+
+    #[test]
+    fn test_template_instantiation() {
+        let spec = "input a: Int64\ninput b: Bool\ntemplate t(x) {\n  output o := x + 1\n}\ninstantiate t(a) as i\n";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Int(IntTy::I64));
+    }
+
+    #[test]
+    fn test_faulty_template_instantiation() {
+        let spec = "input a: Int64\ninput b: Bool\ntemplate t(x) {\n  output o := x + 1\n}\ninstantiate t(a) as i\ninstantiate t(b) as j\n";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
     #[test]
     fn test_window_widening() {
         let spec = "input in: Int8\n output out: Int64 @5Hz:= in.aggregate(over: 3s, using: Σ)";
@@ -2200,8 +3193,8 @@ mod tests {
     fn test_function_arguments_regression() {
         let spec = "input a: Int32\ntrigger a > 50";
         let type_table = type_check(spec);
-        // expression `a > 50` has NodeId = 3
-        let exp_a_gt_50_id = NodeId::new(5);
+        // expression `a > 50` has NodeId = 6
+        let exp_a_gt_50_id = NodeId::new(6);
         assert_eq!(type_table.get_value_type(exp_a_gt_50_id), &ValueTy::Bool);
         assert_eq!(type_table.get_func_arg_types(exp_a_gt_50_id), &vec![ValueTy::Int(IntTy::I32)]);
     }
@@ -2390,4 +3383,113 @@ mod tests {
         let spec = "output a: Int32 := 1111111111111111111111111110";
         assert_eq!(1, num_type_errors(spec));
     }
+
+    #[test]
+    fn test_set_accumulation() {
+        let spec = "input port: UInt16\noutput ports: Set<UInt16> := ports.offset(by: -1).defaults(to: Set()).insert(port)\noutput seen := ports.contains(port)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+    }
+
+    #[test]
+    fn test_set_inferred() {
+        let spec = "input port: UInt16\noutput ports := ports.offset(by: -1).defaults(to: Set()).insert(port)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Set(ValueTy::UInt(UIntTy::U16).into()));
+    }
+
+    #[test]
+    fn test_set_element_mismatch() {
+        let spec =
+            "input port: UInt16\noutput ports: Set<String> := ports.offset(by: -1).defaults(to: Set()).insert(port)";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_set_unknown_element() {
+        let spec = "output s := Set()\noutput n := s.len()";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_map_get() {
+        let spec = "input host: UInt32\ninput port: UInt16\noutput last: Map<UInt32, UInt16> := last.offset(by: -1).defaults(to: Map()).insert(key: host, value: port)\noutput p := last.get(key: host)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::UInt(UIntTy::U16).into()));
+    }
+
+    #[test]
+    fn test_collection_input() {
+        assert_eq!(1, num_type_errors("input ports: Set<UInt16>\noutput n := ports.len()"));
+        assert_eq!(1, num_type_errors("input last: (UInt8, Map<UInt32, UInt16>)"));
+    }
+
+    #[test]
+    fn test_now() {
+        let spec = "input a: Int32\noutput t := now()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Timestamp);
+    }
+
+    #[test]
+    fn test_time_arithmetic() {
+        let spec = "input a: Int32\noutput t := now()\noutput d := t - t.offset(by: -1).defaults(to: t)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Duration);
+        let spec = "input a: Int32\noutput t: Timestamp := now() - 5s + 100ms";
+        assert_eq!(0, num_type_errors(spec));
+        let spec = "input a: Int32\noutput d := (now() - now().offset(by: -1).defaults(to: now())).seconds()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+    }
+
+    #[test]
+    fn test_duration_comparison() {
+        let spec = "input a: Int32\noutput t := now()\noutput late := t - t.offset(by: -1).defaults(to: t) > 5s";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+    }
+
+    #[test]
+    fn test_time_arithmetic_mismatch() {
+        assert_eq!(1, num_type_errors("input a: Int32\noutput t := now() + now()"));
+        assert_eq!(1, num_type_errors("input a: Int32\noutput t := now() - 5"));
+        assert_eq!(1, num_type_errors("input a: Int32\noutput t := now() > 5s"));
+        assert_eq!(1, num_type_errors("input a: Int32\noutput t := a + 5Hz"));
+    }
+
+    #[test]
+    fn test_optional_methods() {
+        let spec =
+            "input a: Int32\ninput b: Int32\noutput c := a.get().or(b.get()).is_some() && b.offset(by: -1).is_none()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+        let spec = "input a: Int32\noutput c := a.offset(by: -1).unwrap_or(0)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Int(IntTy::I32));
+    }
+
+    #[test]
+    fn test_optional_methods_mismatch() {
+        assert_eq!(1, num_type_errors("input a: Int32\ninput b: Float64\noutput c := a.get().or(b.get())"));
+        assert_eq!(1, num_type_errors("input a: Int32\noutput c := a.is_some()"));
+    }
+
+    #[test]
+    fn test_optional_arithmetic() {
+        let spec = "input a: Float64\ninput b: Float64\noutput c := a.get() + b.get()";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::Float(FloatTy::F64).into()));
+        let spec = "input a: Int8\noutput c: Int8? := a.offset(by: -1) * 2";
+        assert_eq!(0, num_type_errors(spec));
+        let spec = "input a: Float64\ninput b: Float64\noutput c := (a.get() - b.get()).defaults(to: 0.0)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F64));
+    }
+
+    #[test]
+    fn test_optional_arithmetic_mismatch() {
+        assert_eq!(1, num_type_errors("input a: Float64\ninput b: Int64\noutput c := a.get() + b.get()"));
+        assert_eq!(1, num_type_errors("input a: Float64\noutput c: Float64 := a.get() + a"));
+    }
 }
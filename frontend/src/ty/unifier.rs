@@ -170,7 +170,15 @@ impl UnifiableTy for ValueTy {
                 Some(other_ty) => other_ty.normalize_ty(unifier),
             },
             ValueTy::Tuple(t) => ValueTy::Tuple(t.iter().map(|el| el.normalize_ty(unifier)).collect()),
+            ValueTy::NamedTuple(fields) => {
+                ValueTy::NamedTuple(fields.iter().map(|(name, el)| (name.clone(), el.normalize_ty(unifier))).collect())
+            }
             ValueTy::Option(ty) => ValueTy::Option(Box::new(ty.normalize_ty(unifier))),
+            ValueTy::Set(ty) => ValueTy::Set(Box::new(ty.normalize_ty(unifier))),
+            ValueTy::List(ty) => ValueTy::List(Box::new(ty.normalize_ty(unifier))),
+            ValueTy::Map(key, value) => {
+                ValueTy::Map(Box::new(key.normalize_ty(unifier)), Box::new(value.normalize_ty(unifier)))
+            }
             _ if self.is_primitive() => self.clone(),
             ValueTy::Constr(_) => self.clone(),
             ValueTy::Param(_, _) => self.clone(),
@@ -255,6 +263,13 @@ impl UnifiableTy for ValueTy {
                 }
             }
             (ValueTy::Option(l), ValueTy::Option(r)) => l.equal_to(unifier, r).map(|ty| ValueTy::Option(ty.into())),
+            (ValueTy::Set(l), ValueTy::Set(r)) => l.equal_to(unifier, r).map(|ty| ValueTy::Set(ty.into())),
+            (ValueTy::List(l), ValueTy::List(r)) => l.equal_to(unifier, r).map(|ty| ValueTy::List(ty.into())),
+            (ValueTy::Map(l_key, l_value), ValueTy::Map(r_key, r_value)) => {
+                let key = l_key.equal_to(unifier, r_key)?;
+                let value = l_value.equal_to(unifier, r_value)?;
+                Some(ValueTy::Map(key.into(), value.into()))
+            }
             (ValueTy::Tuple(l), ValueTy::Tuple(r)) => {
                 if l.len() != r.len() {
                     return None;
@@ -265,6 +280,36 @@ impl UnifiableTy for ValueTy {
                 }
                 Some(ValueTy::Tuple(params))
             }
+            (ValueTy::NamedTuple(l), ValueTy::NamedTuple(r)) => {
+                if l.len() != r.len() || l.iter().zip(r).any(|((l_name, _), (r_name, _))| l_name != r_name) {
+                    return None;
+                }
+                let fields: Vec<(String, ValueTy)> = l
+                    .iter()
+                    .zip(r)
+                    .flat_map(|((name, l), (_, r))| l.equal_to(unifier, r).map(|ty| (name.clone(), ty)))
+                    .collect();
+                if fields.len() != l.len() {
+                    return None;
+                }
+                Some(ValueTy::NamedTuple(fields))
+            }
+            // a named tuple is compatible with an unnamed tuple of the same shape, the names are kept
+            (ValueTy::NamedTuple(named), ValueTy::Tuple(unnamed))
+            | (ValueTy::Tuple(unnamed), ValueTy::NamedTuple(named)) => {
+                if named.len() != unnamed.len() {
+                    return None;
+                }
+                let fields: Vec<(String, ValueTy)> = named
+                    .iter()
+                    .zip(unnamed)
+                    .flat_map(|((name, l), r)| l.equal_to(unifier, r).map(|ty| (name.clone(), ty)))
+                    .collect();
+                if fields.len() != named.len() {
+                    return None;
+                }
+                Some(ValueTy::NamedTuple(fields))
+            }
             (l, r) => {
                 if l == r {
                     Some(l.clone())
@@ -281,7 +326,9 @@ impl UnifiableTy for ValueTy {
         match self {
             ValueTy::Infer(t) => unifier.vars_equal(var, *t),
             ValueTy::Tuple(t) => t.iter().any(|e| e.contains_var(unifier, var)),
-            ValueTy::Option(t) => t.contains_var(unifier, var),
+            ValueTy::NamedTuple(fields) => fields.iter().any(|(_, e)| e.contains_var(unifier, var)),
+            ValueTy::Option(t) | ValueTy::Set(t) | ValueTy::List(t) => t.contains_var(unifier, var),
+            ValueTy::Map(k, v) => k.contains_var(unifier, var) || v.contains_var(unifier, var),
             _ => false,
         }
     }
@@ -0,0 +1,266 @@
+//! Physical units of floating-point value types, e.g., `Float64<m/s>`
+//!
+//! Units are checked separately from the value types: a value type `Float64<ft>` is unified as `Float64`, and the
+//! type checker records the scaling factors needed to convert between compatible units, e.g., from `ft` to `m`.
+
+use lazy_static::lazy_static;
+use std::convert::TryFrom;
+use uom::num::rational::Rational64;
+use uom::num::ToPrimitive;
+use uom::si::{
+    angle, electric_current, electric_potential, energy, force, frequency, length, mass, power, pressure,
+    thermodynamic_temperature, time, velocity,
+};
+use uom::typenum::Integer;
+
+/// The exponents of the base dimensions length, mass, time, electric current, and temperature.
+type Dimension = [i8; 5];
+
+const DIMENSIONLESS: Dimension = [0, 0, 0, 0, 0];
+
+/// The dimension of a quantity of `uom`, e.g., `[1, 0, -1, 0, 0]` for velocities.
+fn dimension<D: uom::si::Dimension + ?Sized>() -> Dimension {
+    [D::L::to_i8(), D::M::to_i8(), D::T::to_i8(), D::I::to_i8(), D::Th::to_i8()]
+}
+
+/// The factor converting a value in a unit of `uom` into the base unit of its quantity.
+fn coefficient<U: uom::Conversion<Rational64, T = Rational64>>() -> f64 {
+    U::coefficient().to_f64().expect("conversion factors are finite")
+}
+
+/// Declares a unit by its symbol in specifications and its definition in `uom`, e.g., `unit!("ft", length::foot)`.
+/// `uom` checks units at compile time, so the units of a specification are looked up in this table at runtime.
+macro_rules! unit {
+    ($symbol:expr, $quantity:ident::$unit:ident) => {
+        ($symbol, dimension::<$quantity::Dimension>(), coefficient::<$quantity::$unit>())
+    };
+}
+
+lazy_static! {
+    /// The known units with their dimension and their factor relative to the coherent SI unit of the dimension.
+    static ref UNITS: Vec<(&'static str, Dimension, f64)> = vec![
+        unit!("m", length::meter),
+        unit!("km", length::kilometer),
+        unit!("cm", length::centimeter),
+        unit!("mm", length::millimeter),
+        unit!("ft", length::foot),
+        unit!("in", length::inch),
+        unit!("yd", length::yard),
+        unit!("mi", length::mile),
+        unit!("nmi", length::nautical_mile),
+        unit!("NM", length::nautical_mile),
+        unit!("kg", mass::kilogram),
+        unit!("g", mass::gram),
+        unit!("t", mass::ton),
+        unit!("lb", mass::pound),
+        unit!("s", time::second),
+        unit!("ms", time::millisecond),
+        unit!("us", time::microsecond),
+        unit!("ns", time::nanosecond),
+        unit!("min", time::minute),
+        unit!("h", time::hour),
+        unit!("A", electric_current::ampere),
+        unit!("mA", electric_current::milliampere),
+        unit!("K", thermodynamic_temperature::kelvin),
+        unit!("Hz", frequency::hertz),
+        unit!("kHz", frequency::kilohertz),
+        unit!("kn", velocity::knot),
+        unit!("mph", velocity::mile_per_hour),
+        unit!("N", force::newton),
+        unit!("Pa", pressure::pascal),
+        unit!("hPa", pressure::hectopascal),
+        unit!("kPa", pressure::kilopascal),
+        unit!("bar", pressure::bar),
+        unit!("psi", pressure::pound_force_per_square_inch),
+        unit!("J", energy::joule),
+        unit!("W", power::watt),
+        unit!("kW", power::kilowatt),
+        unit!("V", electric_potential::volt),
+        unit!("rad", angle::radian),
+        unit!("deg", angle::degree),
+    ];
+}
+
+/// A physical unit, e.g., `m/s`, given by its dimension and its scale relative to the coherent SI unit.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Unit {
+    dimension: Dimension,
+    /// The factor converting a value in this unit into the coherent SI unit of its dimension, e.g., `0.3048` for `ft`
+    scale: f64,
+    /// The unit as written in the specification, used in error messages
+    name: String,
+}
+
+/// The unit of an expression during unit checking.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum UnitTy {
+    /// The expression has the given unit.
+    Known(Unit),
+    /// The expression adapts to the unit required by its context, e.g., a numeric literal.
+    Any,
+}
+
+impl Unit {
+    /// The unit of plain numbers.
+    pub(crate) fn dimensionless() -> Unit {
+        Unit { dimension: DIMENSIONLESS, scale: 1.0, name: String::new() }
+    }
+
+    /// Looks up a unit by its symbol, e.g., `m` or `kn`.
+    pub(crate) fn lookup(symbol: &str) -> Option<Unit> {
+        if symbol == "1" {
+            return Some(Unit::dimensionless());
+        }
+        UNITS.iter().find(|(name, _, _)| *name == symbol).map(|&(name, dimension, scale)| Unit {
+            dimension,
+            scale,
+            name: name.to_string(),
+        })
+    }
+
+    pub(crate) fn is_dimensionless(&self) -> bool {
+        self.dimension == DIMENSIONLESS
+    }
+
+    /// Multiplies two units, or returns `None` if an exponent of the product exceeds the range of exponents.
+    pub(crate) fn mul(&self, other: &Unit) -> Option<Unit> {
+        let dimension = Unit::combine(&self.dimension, &other.dimension, i8::checked_add)?;
+        let name = match (self.name.is_empty(), other.name.is_empty()) {
+            (true, _) => other.name.clone(),
+            (_, true) => self.name.clone(),
+            _ => format!("{}*{}", self.name, other.name),
+        };
+        Some(Unit { dimension, scale: self.scale * other.scale, name })
+    }
+
+    /// Divides two units, or returns `None` if an exponent of the quotient exceeds the range of exponents.
+    pub(crate) fn div(&self, other: &Unit) -> Option<Unit> {
+        let dimension = Unit::combine(&self.dimension, &other.dimension, i8::checked_sub)?;
+        let name = match (self.name.is_empty(), other.name.is_empty()) {
+            (_, true) => self.name.clone(),
+            (true, _) => format!("1/{}", Unit::parenthesize(&other.name)),
+            _ => format!("{}/{}", self.name, Unit::parenthesize(&other.name)),
+        };
+        Some(Unit { dimension, scale: self.scale / other.scale, name })
+    }
+
+    /// Raises the unit to an integer power, or returns `None` if an exponent of the result exceeds the range of exponents.
+    pub(crate) fn powi(&self, exponent: i32) -> Option<Unit> {
+        let factor = i8::try_from(exponent).ok()?;
+        let dimension = Unit::combine(&self.dimension, &[factor; 5], i8::checked_mul)?;
+        let name = match exponent {
+            _ if self.name.is_empty() => String::new(),
+            0 => String::new(),
+            1 => self.name.clone(),
+            _ => format!("{}^{}", Unit::parenthesize(&self.name), exponent),
+        };
+        Some(Unit { dimension, scale: self.scale.powi(exponent), name })
+    }
+
+    fn combine(lhs: &Dimension, rhs: &Dimension, op: fn(i8, i8) -> Option<i8>) -> Option<Dimension> {
+        let mut dimension = DIMENSIONLESS;
+        for (ix, exponent) in dimension.iter_mut().enumerate() {
+            *exponent = op(lhs[ix], rhs[ix])?;
+        }
+        Some(dimension)
+    }
+
+    /// Returns the square root of the unit, e.g., `m` for `m^2`, or `None` if a dimension has an odd exponent.
+    pub(crate) fn sqrt(&self) -> Option<Unit> {
+        if self.dimension.iter().any(|d| d % 2 != 0) {
+            return None;
+        }
+        let name = if self.name.is_empty() { String::new() } else { format!("sqrt({})", self.name) };
+        Some(Unit { dimension: self.dimension.map(|d| d / 2), scale: self.scale.sqrt(), name })
+    }
+
+    /// Returns the factor converting a value in this unit into the `target` unit, or `None` if the dimensions differ.
+    /// The factor is `1.0` if both units have the same scale.
+    pub(crate) fn conversion_to(&self, target: &Unit) -> Option<f64> {
+        if self.dimension != target.dimension {
+            return None;
+        }
+        let factor = self.scale / target.scale;
+        if (factor - 1.0).abs() < 1e-12 {
+            Some(1.0)
+        } else {
+            Some(factor)
+        }
+    }
+
+    fn parenthesize(name: &str) -> String {
+        if name.contains('*') || name.contains('/') {
+            format!("({})", name)
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "1")
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+impl UnitTy {
+    pub(crate) fn dimensionless() -> UnitTy {
+        UnitTy::Known(Unit::dimensionless())
+    }
+
+    /// Returns the unit, treating units adapting to their context as plain numbers.
+    pub(crate) fn or_dimensionless(self) -> Unit {
+        match self {
+            UnitTy::Known(unit) => unit,
+            UnitTy::Any => Unit::dimensionless(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion() {
+        let m = Unit::lookup("m").unwrap();
+        let ft = Unit::lookup("ft").unwrap();
+        let s = Unit::lookup("s").unwrap();
+        assert_eq!(ft.conversion_to(&m), Some(0.3048));
+        assert_eq!(m.conversion_to(&m), Some(1.0));
+        assert_eq!(m.conversion_to(&s), None);
+        let knots = Unit::lookup("kn").unwrap();
+        let mps = m.div(&s).unwrap();
+        assert_eq!(mps.to_string(), "m/s");
+        assert!((knots.conversion_to(&mps).unwrap() - 0.514_444).abs() < 1e-6);
+        let kmh = Unit::lookup("km").unwrap().div(&Unit::lookup("h").unwrap()).unwrap();
+        assert!((kmh.conversion_to(&mps).unwrap() - 1.0 / 3.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn derived_units() {
+        let m = Unit::lookup("m").unwrap();
+        let s = Unit::lookup("s").unwrap();
+        let acceleration = m.div(&s.powi(2).unwrap()).unwrap();
+        assert_eq!(acceleration.to_string(), "m/s^2");
+        assert_eq!(acceleration.mul(&s).unwrap().conversion_to(&m.div(&s).unwrap()), Some(1.0));
+        assert_eq!(m.powi(2).unwrap().sqrt().unwrap().conversion_to(&m), Some(1.0));
+        assert_eq!(m.sqrt(), None);
+        assert!(Unit::lookup("deg").unwrap().is_dimensionless());
+        assert_eq!(Unit::lookup("furlong"), None);
+    }
+
+    #[test]
+    fn exponents_out_of_range() {
+        let m = Unit::lookup("m").unwrap();
+        assert_eq!(m.powi(200), None);
+        let m127 = m.powi(127).unwrap();
+        assert_eq!(m127.mul(&m), None);
+        assert_eq!(m.powi(-128).unwrap().div(&m), None);
+        assert_eq!(m127.div(&m).unwrap().conversion_to(&m.powi(126).unwrap()), Some(1.0));
+    }
+}
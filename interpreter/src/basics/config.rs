@@ -1,14 +1,22 @@
 use super::{CSVInputSource, EventSourceConfig, OutputChannel};
+use rtlola_frontend::ir::{Trigger, TriggerSeverity};
 
 #[derive(Clone, Debug)]
 pub struct EvalConfig {
     pub source: EventSourceConfig,
     pub statistics: Statistics,
     pub verbosity: Verbosity,
+    /// Triggers with a lower severity are counted but not printed.
+    pub min_severity: TriggerSeverity,
     pub output_channel: OutputChannel,
     pub evaluator: EvaluatorChoice,
     pub mode: ExecutionMode,
     pub time_presentation: TimeRepresentation,
+    pub trigger_policy: TriggerPolicy,
+    /// Determines the result of integer arithmetic that exceeds the declared width of its type.
+    pub overflow: OverflowMode,
+    /// Determines whether the monitor continues after a runtime error, e.g., a division by zero.
+    pub runtime_errors: RuntimeErrorPolicy,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -35,6 +43,59 @@ pub enum Verbosity {
     Debug,
 }
 
+/// Determines the consequences of a raised trigger beyond reporting it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TriggerPolicy {
+    /// The monitor fails after the evaluation if one of these triggers was raised.
+    pub fail_on: TriggerSelection,
+    /// Commands that run whenever one of the selected triggers is raised.  The details of the trigger are passed in
+    /// the environment variables `RTLOLA_TRIGGER_NAME`, `RTLOLA_TRIGGER_INDEX`, `RTLOLA_TRIGGER_SEVERITY`,
+    /// `RTLOLA_TRIGGER_MESSAGE`, and `RTLOLA_TRIGGER_TIME`.
+    pub actions: Vec<(TriggerSelection, String)>,
+}
+
+/// Selects a set of triggers by their names.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TriggerSelection {
+    #[default]
+    None,
+    All,
+    Named(Vec<String>),
+}
+
+impl TriggerSelection {
+    pub fn contains(&self, trigger: &Trigger) -> bool {
+        match self {
+            TriggerSelection::None => false,
+            TriggerSelection::All => true,
+            TriggerSelection::Named(names) => trigger.name.as_ref().is_some_and(|name| names.contains(name)),
+        }
+    }
+}
+
+/// Determines how integer arithmetic handles results that do not fit into the declared type.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum OverflowMode {
+    /// Discards the bits exceeding the width of the type, i.e., computes modulo `2^width`.
+    #[default]
+    Wrapping,
+    /// Clamps the result to the minimal or maximal value of the type.
+    Saturating,
+    /// Reports a runtime error, see `RuntimeErrorPolicy`.
+    Trapping,
+}
+
+/// Determines the consequences of a runtime error during the evaluation of a stream, e.g., a division by zero.
+/// In either case, the stream produces no value and a warning is reported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RuntimeErrorPolicy {
+    /// The monitor continues with the evaluation.
+    #[default]
+    Continue,
+    /// The monitor stops the evaluation and fails with a `RuntimeFailure`.
+    Abort,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ExecutionMode {
     Offline,
@@ -72,7 +133,19 @@ impl EvalConfig {
         mode: ExecutionMode,
         time_presentation: TimeRepresentation,
     ) -> Self {
-        EvalConfig { source, statistics, verbosity, output_channel: output, evaluator, mode, time_presentation }
+        EvalConfig {
+            source,
+            statistics,
+            verbosity,
+            min_severity: TriggerSeverity::Info,
+            output_channel: output,
+            evaluator,
+            mode,
+            time_presentation,
+            trigger_policy: TriggerPolicy::default(),
+            overflow: OverflowMode::default(),
+            runtime_errors: RuntimeErrorPolicy::default(),
+        }
     }
 
     pub fn debug() -> Self {
@@ -119,10 +192,14 @@ impl Default for EvalConfig {
             source: EventSourceConfig::CSV { src: CSVInputSource::StdIn },
             statistics: Statistics::None,
             verbosity: Verbosity::Triggers,
+            min_severity: TriggerSeverity::Info,
             output_channel: OutputChannel::StdOut,
             evaluator: EvaluatorChoice::ClosureBased,
             mode: ExecutionMode::Offline,
             time_presentation: TimeRepresentation::Hide,
+            trigger_policy: TriggerPolicy::default(),
+            overflow: OverflowMode::default(),
+            runtime_errors: RuntimeErrorPolicy::default(),
         }
     }
 }
@@ -1,13 +1,15 @@
 #![allow(clippy::mutex_atomic)]
 
-use super::{EvalConfig, TimeFormat, TimeRepresentation, Verbosity};
+use super::{EvalConfig, RuntimeErrorPolicy, TimeFormat, TimeRepresentation, TriggerPolicy, Verbosity};
 use crate::basics::{CSVEventSource, CSVInputSource, PCAPEventSource, PCAPInputSource, Time};
-use crate::storage::Value;
+use crate::storage::{RuntimeError, Value};
 use crossterm::{cursor, terminal, ClearType};
-use rtlola_frontend::ir::RTLolaIR;
+use rtlola_frontend::ir::{InputStream, MessagePart, RTLolaIR, Trigger, TriggerSeverity};
 use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io::{stderr, stdout, Write};
+use std::process::{Child, Command};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -62,31 +64,90 @@ pub enum OutputChannel {
 #[derive(Debug)]
 pub struct OutputHandler {
     pub(crate) verbosity: Verbosity,
+    min_severity: TriggerSeverity,
     channel: OutputChannel,
     file: Option<File>,
     pub(crate) statistics: Option<Statistics>,
     pub(crate) start_time: Mutex<SystemTime>,
     time_representation: TimeRepresentation,
+    trigger_policy: TriggerPolicy,
+    // The first raised trigger the policy fails on.
+    failure: Mutex<Option<TriggerFailure>>,
+    runtime_error_policy: RuntimeErrorPolicy,
+    // The first runtime error if the monitor aborts on runtime errors.
+    runtime_failure: Mutex<Option<RuntimeFailure>>,
+    // The running commands of trigger actions.
+    actions: Mutex<Vec<Child>>,
 }
 
+/// The error of a monitor whose `TriggerPolicy` fails on a raised trigger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerFailure {
+    pub trigger_idx: usize,
+    pub name: Option<String>,
+    pub time: Time,
+    pub message: String,
+}
+
+impl fmt::Display for TriggerFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "trigger `{}`", name)?,
+            None => write!(f, "trigger #{}", self.trigger_idx)?,
+        }
+        write!(f, " was raised at {}.{:09}s: {}", self.time.as_secs(), self.time.subsec_nanos(), self.message)
+    }
+}
+
+impl Error for TriggerFailure {}
+
+/// The error of a monitor that aborts on runtime errors, see `RuntimeErrorPolicy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeFailure {
+    pub stream: String,
+    pub time: Time,
+    pub message: String,
+}
+
+impl fmt::Display for RuntimeFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "runtime error in stream `{}` at {}.{:09}s: {}",
+            self.stream,
+            self.time.as_secs(),
+            self.time.subsec_nanos(),
+            self.message
+        )
+    }
+}
+
+impl Error for RuntimeFailure {}
+
 impl OutputHandler {
-    pub(crate) fn new(config: &EvalConfig, num_trigger: usize) -> OutputHandler {
+    pub(crate) fn new(config: &EvalConfig, triggers: &[Trigger]) -> OutputHandler {
         let statistics = if config.verbosity == Verbosity::Progress {
-            let stats = Statistics::new(num_trigger);
+            let stats = Statistics::new(triggers);
             stats.start_print_progress();
             Some(stats)
         } else if config.statistics == crate::basics::Statistics::Debug {
-            Some(Statistics::new(num_trigger))
+            Some(Statistics::new(triggers))
         } else {
             None
         };
         OutputHandler {
             verbosity: config.verbosity,
+            min_severity: config.min_severity,
             channel: config.output_channel.clone(),
             file: None,
             statistics,
             start_time: Mutex::new(SystemTime::now()),
             time_representation: config.time_presentation,
+            trigger_policy: config.trigger_policy.clone(),
+            failure: Mutex::new(None),
+            runtime_error_policy: config.runtime_errors,
+            runtime_failure: Mutex::new(None),
+            actions: Mutex::new(Vec::new()),
         }
     }
 
@@ -130,24 +191,151 @@ impl OutputHandler {
         }
     }
 
+    /// Renders the message of a trigger by interpolating the values of its placeholders in order.
+    pub(crate) fn trigger_message(trigger: &Trigger, values: &[Value]) -> String {
+        if trigger.message_parts.is_empty() {
+            // the message defaults to the trigger expression
+            return trigger.message.clone();
+        }
+        let mut values = values.iter();
+        trigger
+            .message_parts
+            .iter()
+            .map(|part| match part {
+                MessagePart::Text(text) => text.clone(),
+                MessagePart::Placeholder(_) => values.next().expect("one value per placeholder").to_string(),
+            })
+            .collect()
+    }
+
+    /// Reports a raised trigger, labeled with its name and severity, e.g., `Trigger gps_lost (critical): ...`.
+    /// Triggers below the minimum severity are counted but not printed.  Afterwards, the trigger policy is applied.
     #[allow(dead_code)]
-    pub(crate) fn trigger<F, T: Into<String>>(&self, msg: F, trigger_idx: usize, time: Time)
+    pub(crate) fn trigger<F, T: Into<String>>(&self, msg: F, trigger: &Trigger, time: Time)
     where
         F: FnOnce() -> T,
     {
-        let msg = || {
-            if let Some(ti) = self.time_info(time) {
-                format!("{}: {}", ti, msg().into())
-            } else {
-                msg().into()
+        let print = trigger.severity >= self.min_severity && Verbosity::Triggers <= self.verbosity;
+        let actions: Vec<&String> = self
+            .trigger_policy
+            .actions
+            .iter()
+            .filter(|(selection, _)| selection.contains(trigger))
+            .map(|(_, command)| command)
+            .collect();
+        let mut failure = self.failure.lock().unwrap();
+        let fails = failure.is_none() && self.trigger_policy.fail_on.contains(trigger);
+        if print || fails || !actions.is_empty() {
+            let msg = msg().into();
+            if print {
+                let label = match &trigger.name {
+                    Some(name) => format!("Trigger {} ({})", name, trigger.severity),
+                    None => format!("Trigger ({})", trigger.severity),
+                };
+                if let Some(ti) = self.time_info(time) {
+                    self.print(format!("{}: {}: {}", ti, label, msg));
+                } else {
+                    self.print(format!("{}: {}", label, msg));
+                }
+            }
+            for command in actions {
+                self.run_action(command, trigger, &msg, time);
             }
+            if fails {
+                *failure = Some(TriggerFailure {
+                    trigger_idx: trigger.trigger_idx,
+                    name: trigger.name.clone(),
+                    time,
+                    message: msg,
+                });
+            }
+        }
+        if let Some(statistics) = &self.statistics {
+            statistics.trigger(trigger.trigger_idx);
+        }
+    }
+
+    /// Runs the command of a trigger action without waiting for it to finish.
+    fn run_action(&self, command: &str, trigger: &Trigger, msg: &str, time: Time) {
+        #[cfg(windows)]
+        let mut cmd = {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(command);
+            cmd
+        };
+        #[cfg(not(windows))]
+        let mut cmd = {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(command);
+            cmd
         };
-        self.emit(Verbosity::Triggers, msg);
+        let child = cmd
+            .env("RTLOLA_TRIGGER_NAME", trigger.name.as_deref().unwrap_or_default())
+            .env("RTLOLA_TRIGGER_INDEX", trigger.trigger_idx.to_string())
+            .env("RTLOLA_TRIGGER_SEVERITY", trigger.severity.to_string())
+            .env("RTLOLA_TRIGGER_MESSAGE", msg)
+            .env("RTLOLA_TRIGGER_TIME", format!("{}.{:09}", time.as_secs(), time.subsec_nanos()))
+            .spawn();
+        match child {
+            Ok(child) => {
+                let mut actions = self.actions.lock().unwrap();
+                // reap the actions that finished in the meantime
+                actions.retain_mut(|action| matches!(action.try_wait(), Ok(None)));
+                actions.push(child);
+            }
+            Err(e) => self.runtime_warning(|| format!("Could not run trigger action `{}`: {}", command, e)),
+        }
+    }
+
+    /// Returns the first raised trigger the trigger policy fails on, if any.
+    pub(crate) fn trigger_failure(&self) -> Option<TriggerFailure> {
+        self.failure.lock().unwrap().clone()
+    }
+
+    /// Counts a raised trigger whose report is suppressed by its options.
+    pub(crate) fn suppressed_trigger(&self, trigger: &Trigger) {
+        self.debug(|| format!("Suppressed report of trigger {}.", trigger.trigger_idx));
+        if let Some(statistics) = &self.statistics {
+            statistics.suppressed_trigger(trigger.trigger_idx);
+        }
+    }
+
+    /// Reports an input value that is rejected because it violates the assumption of its stream.
+    pub(crate) fn assumption_violated(&self, input: &InputStream, value: &Value, time: Time) {
+        self.emit(Verbosity::WarningsOnly, || {
+            let msg = format!("Assumption violated: input `{}` received {}, value rejected", input.name, value);
+            match self.time_info(time) {
+                Some(ti) => format!("{}: {}", ti, msg),
+                None => msg,
+            }
+        });
         if let Some(statistics) = &self.statistics {
-            statistics.trigger(trigger_idx);
+            statistics.assumption_violated();
         }
     }
 
+    /// Reports a runtime error during the evaluation of a stream, which consequently produces no value.
+    pub(crate) fn runtime_error(&self, stream: &str, error: &RuntimeError, time: Time) {
+        self.runtime_warning(|| {
+            let ti = self.time_info(time).unwrap_or_else(|| format!("{}.{:09}s", time.as_secs(), time.subsec_nanos()));
+            format!("{}: Runtime error in stream `{}`: {}, no value produced", ti, stream, error)
+        });
+        if let Some(statistics) = &self.statistics {
+            statistics.runtime_error();
+        }
+        if self.runtime_error_policy == RuntimeErrorPolicy::Abort {
+            let mut failure = self.runtime_failure.lock().unwrap();
+            if failure.is_none() {
+                *failure = Some(RuntimeFailure { stream: stream.to_string(), time, message: error.to_string() });
+            }
+        }
+    }
+
+    /// Returns the first runtime error if the monitor aborts on runtime errors, and `None` otherwise.
+    pub(crate) fn runtime_failure(&self) -> Option<RuntimeFailure> {
+        self.runtime_failure.lock().unwrap().clone()
+    }
+
     #[allow(dead_code)]
     pub(crate) fn debug<F, T: Into<String>>(&self, msg: F)
     where
@@ -196,6 +384,9 @@ impl OutputHandler {
                 statistics.terminate();
             }
         }
+        for mut action in self.actions.lock().unwrap().drain(..) {
+            let _ = action.wait();
+        }
     }
 }
 
@@ -204,15 +395,23 @@ struct StatisticsData {
     start: SystemTime,
     num_events: AtomicU64,
     num_triggers: Vec<AtomicU64>,
+    num_suppressed: Vec<AtomicU64>,
+    num_violations: AtomicU64,
+    num_runtime_errors: AtomicU64,
+    trigger_names: Vec<Option<String>>,
     done: Mutex<bool>,
 }
 
 impl StatisticsData {
-    fn new(num_trigger: usize) -> Self {
+    fn new(triggers: &[Trigger]) -> Self {
         Self {
             start: SystemTime::now(),
             num_events: AtomicU64::new(0),
-            num_triggers: (0..num_trigger).map(|_| AtomicU64::new(0)).collect(),
+            num_triggers: triggers.iter().map(|_| AtomicU64::new(0)).collect(),
+            num_suppressed: triggers.iter().map(|_| AtomicU64::new(0)).collect(),
+            num_violations: AtomicU64::new(0),
+            num_runtime_errors: AtomicU64::new(0),
+            trigger_names: triggers.iter().map(|trigger| trigger.name.clone()).collect(),
             done: Mutex::new(false),
         }
     }
@@ -224,8 +423,8 @@ pub(crate) struct Statistics {
 }
 
 impl Statistics {
-    fn new(num_trigger: usize) -> Self {
-        let data = Arc::new(StatisticsData::new(num_trigger));
+    fn new(triggers: &[Trigger]) -> Self {
+        let data = Arc::new(StatisticsData::new(triggers));
         Statistics { data }
     }
 
@@ -243,7 +442,7 @@ impl Statistics {
                 if *done {
                     return;
                 }
-                Self::clear_progress_info();
+                Self::clear_progress_info(&copy);
                 Self::print_progress_info(&copy, spinner.next().unwrap());
             }
         });
@@ -257,10 +456,22 @@ impl Statistics {
         self.data.num_triggers[trigger_idx].fetch_add(1, Ordering::Relaxed);
     }
 
+    fn suppressed_trigger(&self, trigger_idx: usize) {
+        self.data.num_suppressed[trigger_idx].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn assumption_violated(&self) {
+        self.data.num_violations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn runtime_error(&self) {
+        self.data.num_runtime_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
     #[allow(clippy::mutex_atomic)]
     pub(crate) fn terminate(&self) {
         let mut done = self.data.done.lock().unwrap();
-        Self::clear_progress_info();
+        Self::clear_progress_info(&self.data);
         Self::print_progress_info(&self.data, ' ');
         *done = true;
     }
@@ -288,16 +499,65 @@ impl Statistics {
         // write trigger statistics
         let num_triggers =
             data.num_triggers.iter().fold(0, |val, num_trigger| val + num_trigger.load(Ordering::Relaxed));
-        writeln!(out, "  {} triggers", num_triggers).unwrap_or_else(|_| {});
+        let num_suppressed =
+            data.num_suppressed.iter().fold(0, |val, num_suppressed| val + num_suppressed.load(Ordering::Relaxed));
+        let mut line = format!("  {} triggers", num_triggers);
+        if num_suppressed > 0 {
+            line += &format!(" ({} suppressed)", num_suppressed);
+        }
+        let num_violations = data.num_violations.load(Ordering::Relaxed);
+        if num_violations > 0 {
+            line += &format!(", {} assumption violations", num_violations);
+        }
+        let num_runtime_errors = data.num_runtime_errors.load(Ordering::Relaxed);
+        if num_runtime_errors > 0 {
+            line += &format!(", {} runtime errors", num_runtime_errors);
+        }
+        writeln!(out, "{}", line).unwrap_or_else(|_| {});
+        for (name, num_trigger) in Self::named_trigger_counts(data) {
+            writeln!(out, "    {}: {}", name, num_trigger).unwrap_or_else(|_| {});
+        }
     }
 
-    fn clear_progress_info() {
+    fn clear_progress_info(data: &Arc<StatisticsData>) {
         let terminal = terminal();
         // clear screen as much as written in `print_progress_info`
-        cursor().move_up(1);
-        terminal.clear(ClearType::CurrentLine).unwrap_or_else(|_| {});
-        cursor().move_up(1);
-        terminal.clear(ClearType::CurrentLine).unwrap_or_else(|_| {});
+        let lines = 2 + data.trigger_names.iter().flatten().count();
+        for _ in 0..lines {
+            cursor().move_up(1);
+            terminal.clear(ClearType::CurrentLine).unwrap_or_else(|_| {});
+        }
+    }
+
+    /// Returns how often each named trigger was raised, in the order of their declaration.
+    fn named_trigger_counts(data: &StatisticsData) -> Vec<(&str, u64)> {
+        data.trigger_names
+            .iter()
+            .zip(&data.num_triggers)
+            .filter_map(|(name, num_trigger)| {
+                name.as_ref().map(|name| (name.as_str(), num_trigger.load(Ordering::Relaxed)))
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_num_violations(&self) -> u64 {
+        self.data.num_violations.load(Ordering::Relaxed)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_num_runtime_errors(&self) -> u64 {
+        self.data.num_runtime_errors.load(Ordering::Relaxed)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_num_suppressed(&self, trigger_idx: usize) -> u64 {
+        self.data.num_suppressed[trigger_idx].load(Ordering::Relaxed)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_num_trigger_by_name(&self, name: &str) -> Option<u64> {
+        Self::named_trigger_counts(&self.data).into_iter().find(|(n, _)| *n == name).map(|(_, num)| num)
     }
 
     #[cfg(test)]
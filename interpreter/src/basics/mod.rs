@@ -6,10 +6,11 @@ mod pcap_input;
 pub(crate) type Time = Duration;
 
 pub use self::config::{
-    EvalConfig, EvaluatorChoice, ExecutionMode, Statistics, TimeFormat, TimeRepresentation, Verbosity,
+    EvalConfig, EvaluatorChoice, ExecutionMode, OverflowMode, RuntimeErrorPolicy, Statistics, TimeFormat,
+    TimeRepresentation, TriggerPolicy, TriggerSelection, Verbosity,
 };
-pub use self::io_handler::OutputChannel;
 pub(crate) use self::io_handler::{create_event_source, EventSource, EventSourceConfig, OutputHandler};
+pub use self::io_handler::{OutputChannel, RuntimeFailure, TriggerFailure};
 
 pub use self::csv_input::{CSVEventSource, CSVInputSource};
 
@@ -9,7 +9,7 @@ use etherparse::{
 };
 use ip_network::IpNetwork;
 use pcap_on_demand::{Activated, Capture, Device, Error as PCAPError};
-use rtlola_frontend::ir::RTLolaIR;
+use rtlola_frontend::ir::{RTLolaIR, Type};
 use std::error::Error;
 use std::net::IpAddr;
 use std::str::FromStr;
@@ -81,6 +81,12 @@ fn ipv4_destination(packet: &SlicedPacket) -> Value {
         Value::None
     }
 }
+fn ipv4_source_address(packet: &SlicedPacket) -> Value {
+    get_ipv4_header(packet).map(|header| Value::IPv4Addr(header.source.into())).unwrap_or(Value::None)
+}
+fn ipv4_destination_address(packet: &SlicedPacket) -> Value {
+    get_ipv4_header(packet).map(|header| Value::IPv4Addr(header.destination.into())).unwrap_or(Value::None)
+}
 fn ipv4_ihl(packet: &SlicedPacket) -> Value {
     if let Some(header) = get_ipv4_header(packet) {
         Value::Unsigned(header.ihl().into())
@@ -186,6 +192,12 @@ fn ipv6_destination(packet: &SlicedPacket) -> Value {
         Value::None
     }
 }
+fn ipv6_source_address(packet: &SlicedPacket) -> Value {
+    get_ipv6_header(packet).map(|header| Value::IPv6Addr(header.source.into())).unwrap_or(Value::None)
+}
+fn ipv6_destination_address(packet: &SlicedPacket) -> Value {
+    get_ipv6_header(packet).map(|header| Value::IPv6Addr(header.destination.into())).unwrap_or(Value::None)
+}
 fn ipv6_traffic_class(packet: &SlicedPacket) -> Value {
     if let Some(header) = get_ipv6_header(packet) {
         Value::Unsigned(header.traffic_class.into())
@@ -493,11 +505,10 @@ impl PCAPEventSource {
                 None => FromFile { start: None },
             },
         };
-        let input_names: Vec<String> = ir.inputs.iter().map(|i| i.name.clone()).collect();
-
         // Generate Mapping that given a parsed packet returns the value for the corresponding input stream
-        let mut mapping: Vec<Box<dyn Fn(&SlicedPacket) -> Value>> = Vec::with_capacity(input_names.len());
-        for name in input_names.iter() {
+        let mut mapping: Vec<Box<dyn Fn(&SlicedPacket) -> Value>> = Vec::with_capacity(ir.inputs.len());
+        for input in ir.inputs.iter() {
+            let name = &input.name;
             let layers: Vec<&str> = name.split("::").collect();
             if layers.len() > 3 || layers.is_empty() {
                 eprintln!("Malformed input name: {}", name);
@@ -526,6 +537,9 @@ impl PCAPEventSource {
                         std::process::exit(1);
                     };
                     match layers[1] {
+                        // addresses are given as tuples of octets unless the input is declared as an address
+                        "source" if input.ty == Type::IPv4Addr => Box::new(ipv4_source_address),
+                        "destination" if input.ty == Type::IPv4Addr => Box::new(ipv4_destination_address),
                         "source" => Box::new(ipv4_source),
                         "destination" => Box::new(ipv4_destination),
                         "ihl" => Box::new(ipv4_ihl),
@@ -563,6 +577,8 @@ impl PCAPEventSource {
                         std::process::exit(1);
                     };
                     match layers[1] {
+                        "source" if input.ty == Type::IPv6Addr => Box::new(ipv6_source_address),
+                        "destination" if input.ty == Type::IPv6Addr => Box::new(ipv6_destination_address),
                         "source" => Box::new(ipv6_source),
                         "destination" => Box::new(ipv6_destination),
                         "traffic_class" => Box::new(ipv6_traffic_class),
@@ -18,6 +18,10 @@ fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
     let config = rtlola_interpreter::Config::new(&args);
-    config.run()?;
+    if let Err(e) = config.run() {
+        // e.g., a trigger the `--fail-on-trigger` option refers to was raised
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
     Ok(())
 }
@@ -2,28 +2,36 @@
 //!
 //! See [Building fast interpreters in Rust](https://blog.cloudflare.com/building-fast-interpreters-in-rust/)
 
-use crate::evaluator::EvaluationContext;
-use crate::storage::Value;
+use crate::evaluator::{bind_local, boolean_robustness, comparison_robustness, EvaluationContext};
+use crate::geo::{coordinate, geo_function, Polygon};
+use crate::storage::{round_float, RuntimeError, Value};
+use ip_network::{Ipv4Network, Ipv6Network};
 use regex::bytes::Regex as BytesRegex;
 use regex::Regex;
-use rtlola_frontend::ir::{Constant, Expression, ExpressionKind, Offset, StreamAccessKind, StreamReference, Type};
-use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use rtlola_frontend::ir::{
+    ArithLogOp, Constant, Expression, ExpressionKind, Offset, StreamAccessKind, StreamReference, Type,
+};
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shr};
 
 pub(crate) trait Expr {
     fn compile(self) -> CompiledExpr;
+    /// Compiles a boolean expression into a closure computing its robustness as a float value
+    fn compile_robustness(self) -> CompiledExpr;
 }
-pub(crate) struct CompiledExpr(Box<dyn Fn(&EvaluationContext<'_>) -> Value>);
+/// A closure computing the value of an expression, or the runtime error preventing it.
+type Closure = dyn Fn(&EvaluationContext<'_>) -> Result<Value, RuntimeError>;
+pub(crate) struct CompiledExpr(Box<Closure>);
 // alternative: using Higher-Rank Trait Bounds (HRTBs)
 // pub(crate) struct CompiledExpr<'s>(Box<dyn 's + for<'a> Fn(&EvaluationContext<'a>) -> Value>);
 
 impl CompiledExpr {
     /// Creates a compiled expression IR from a generic closure.
-    pub(crate) fn new(closure: impl 'static + Fn(&EvaluationContext<'_>) -> Value) -> Self {
+    pub(crate) fn new(closure: impl 'static + Fn(&EvaluationContext<'_>) -> Result<Value, RuntimeError>) -> Self {
         CompiledExpr(Box::new(closure))
     }
 
     /// Executes a filter against a provided context with values.
-    pub(crate) fn execute(&self, ctx: &EvaluationContext) -> Value {
+    pub(crate) fn execute(&self, ctx: &EvaluationContext) -> Result<Value, RuntimeError> {
         self.0(ctx)
     }
 }
@@ -37,51 +45,79 @@ impl Expr for Expression {
                     Constant::Bool(b) => Value::Bool(b),
                     Constant::UInt(u) => Value::Unsigned(u),
                     Constant::Int(i) => Value::Signed(i),
-                    Constant::Float(f) => Value::Float(f.into()),
+                    Constant::Float(f) => match &self.ty {
+                        Type::Float(fty) => Value::new_float(round_float(f, fty)),
+                        _ => Value::Float(f.into()),
+                    },
                     Constant::Str(s) => Value::Str(s.into_boxed_str()),
+                    Constant::Duration(ns) => Value::Duration(ns),
+                    Constant::IPv4Net(addr, prefix) => {
+                        Value::IPv4Net(Ipv4Network::new(addr, prefix).expect("checked by the frontend"))
+                    }
+                    Constant::IPv6Net(addr, prefix) => {
+                        Value::IPv6Net(Ipv6Network::new(addr, prefix).expect("checked by the frontend"))
+                    }
                 };
-                CompiledExpr::new(move |_| v.clone())
+                CompiledExpr::new(move |_| Ok(v.clone()))
             }
 
             ArithLog(op, operands, _ty) => {
                 let f_operands: Vec<CompiledExpr> = operands.into_iter().map(|e| e.compile()).collect();
+                let ty = self.ty;
 
                 macro_rules! create_unop {
                     ($fn:ident) => {
                         CompiledExpr::new(move |ctx| {
-                            let lhs = f_operands[0].execute(ctx);
-                            lhs.$fn()
+                            let lhs = f_operands[0].execute(ctx)?;
+                            Ok(lhs.$fn())
+                        })
+                    };
+                }
+                macro_rules! create_arith_unop {
+                    ($op:ident) => {
+                        CompiledExpr::new(move |ctx| {
+                            let lhs = f_operands[0].execute(ctx)?;
+                            Value::arith($op, lhs, None, &ty, ctx.overflow)
+                        })
+                    };
+                }
+                macro_rules! create_arith_binop {
+                    ($op:ident) => {
+                        CompiledExpr::new(move |ctx| {
+                            let lhs = f_operands[0].execute(ctx)?;
+                            let rhs = f_operands[1].execute(ctx)?;
+                            Value::arith($op, lhs, Some(rhs), &ty, ctx.overflow)
                         })
                     };
                 }
                 macro_rules! create_binop {
                     ($fn:ident) => {
                         CompiledExpr::new(move |ctx| {
-                            let lhs = f_operands[0].execute(ctx);
-                            let rhs = f_operands[1].execute(ctx);
-                            lhs.$fn(rhs)
+                            let lhs = f_operands[0].execute(ctx)?;
+                            let rhs = f_operands[1].execute(ctx)?;
+                            Ok(lhs.$fn(rhs))
                         })
                     };
                 }
                 macro_rules! create_cmp {
                     ($fn:ident) => {
                         CompiledExpr::new(move |ctx| {
-                            let lhs = f_operands[0].execute(ctx);
-                            let rhs = f_operands[1].execute(ctx);
-                            Value::Bool(lhs.$fn(&rhs))
+                            let lhs = f_operands[0].execute(ctx)?;
+                            let rhs = f_operands[1].execute(ctx)?;
+                            Ok(Value::Bool(lhs.$fn(&rhs)))
                         })
                     };
                 }
                 macro_rules! create_lazyop {
                     ($b:expr) => {
                         CompiledExpr::new(move |ctx| {
-                            let lhs = f_operands[0].execute(ctx).get_bool();
+                            let lhs = f_operands[0].execute(ctx)?.get_bool();
                             if lhs == $b {
-                                Value::Bool($b)
+                                Ok(Value::Bool($b))
                             } else {
-                                let res = f_operands[1].execute(ctx);
+                                let res = f_operands[1].execute(ctx)?;
                                 assert!(res.is_bool());
-                                res
+                                Ok(res)
                             }
                         })
                     };
@@ -90,14 +126,14 @@ impl Expr for Expression {
                 use rtlola_frontend::ir::ArithLogOp::*;
                 match op {
                     Not => create_unop!(not),
-                    BitNot => create_unop!(not),
-                    Neg => create_unop!(neg),
-                    Add => create_binop!(add),
-                    Sub => create_binop!(sub),
-                    Mul => create_binop!(mul),
-                    Div => create_binop!(div),
-                    Rem => create_binop!(rem),
-                    Pow => create_binop!(pow),
+                    BitNot => create_arith_unop!(BitNot),
+                    Neg => create_arith_unop!(Neg),
+                    Add => create_arith_binop!(Add),
+                    Sub => create_arith_binop!(Sub),
+                    Mul => create_arith_binop!(Mul),
+                    Div => create_arith_binop!(Div),
+                    Rem => create_arith_binop!(Rem),
+                    Pow => create_arith_binop!(Pow),
                     Eq => create_cmp!(eq),
                     Lt => create_cmp!(lt),
                     Le => create_cmp!(le),
@@ -109,40 +145,56 @@ impl Expr for Expression {
                     BitAnd => create_binop!(bitand),
                     BitOr => create_binop!(bitor),
                     BitXor => create_binop!(bitxor),
-                    Shl => create_binop!(shl),
+                    Shl => create_arith_binop!(Shl),
                     Shr => create_binop!(shr),
                 }
             }
 
+            OptionalArithLog(op, operands, _ty) => {
+                let f_operands: Vec<CompiledExpr> = operands.into_iter().map(|e| e.compile()).collect();
+                let ty = self.ty;
+
+                use rtlola_frontend::ir::ArithLogOp::*;
+                match op {
+                    Add | Sub | Mul | Div | Rem | Pow => {
+                        CompiledExpr::new(move |ctx| match (f_operands[0].execute(ctx)?, f_operands[1].execute(ctx)?) {
+                            (Value::None, _) | (_, Value::None) => Ok(Value::None),
+                            (lhs, rhs) => Value::arith(op, lhs, Some(rhs), &ty, ctx.overflow),
+                        })
+                    }
+                    op => unreachable!("{:?} is not applicable to optional values", op),
+                }
+            }
+
             OffsetLookup { target, offset } => {
                 let offset = match offset {
                     Offset::FutureDiscreteOffset(_) | Offset::FutureRealTimeOffset(_) => unimplemented!(),
                     Offset::PastDiscreteOffset(u) => -(u as i16),
                     Offset::PastRealTimeOffset(_dur) => unimplemented!(),
                 };
-                CompiledExpr::new(move |ctx| ctx.lookup_with_offset(target, offset))
+                CompiledExpr::new(move |ctx| Ok(ctx.lookup_with_offset(target, offset)))
             }
 
             StreamAccess(str_ref, kind) => {
                 use StreamAccessKind::*;
                 match kind {
                     Sync => CompiledExpr::new(move |ctx| ctx.lookup_latest_check(str_ref)),
-                    Hold => CompiledExpr::new(move |ctx| ctx.lookup_latest(str_ref)),
+                    Hold => CompiledExpr::new(move |ctx| Ok(ctx.lookup_latest(str_ref))),
                     Optional => {
                         use StreamReference::*;
                         match str_ref {
                             InRef(ix) => CompiledExpr::new(move |ctx| {
                                 if ctx.fresh_inputs.contains(ix) {
-                                    ctx.lookup_latest(str_ref)
+                                    Ok(ctx.lookup_latest(str_ref))
                                 } else {
-                                    Value::None
+                                    Ok(Value::None)
                                 }
                             }),
                             OutRef(ix) => CompiledExpr::new(move |ctx| {
                                 if ctx.fresh_outputs.contains(ix) {
-                                    ctx.lookup_latest(str_ref)
+                                    Ok(ctx.lookup_latest(str_ref))
                                 } else {
-                                    Value::None
+                                    Ok(Value::None)
                                 }
                             }),
                         }
@@ -150,7 +202,7 @@ impl Expr for Expression {
                 }
             }
 
-            WindowLookup(win_ref) => CompiledExpr::new(move |ctx| ctx.lookup_window(win_ref)),
+            WindowLookup(win_ref) => CompiledExpr::new(move |ctx| Ok(ctx.lookup_window(win_ref))),
 
             Ite { condition, consequence, alternative, .. } => {
                 let f_condition = condition.compile();
@@ -158,7 +210,7 @@ impl Expr for Expression {
                 let f_alternative = alternative.compile();
 
                 CompiledExpr::new(move |ctx| {
-                    let cond = f_condition.execute(ctx).get_bool();
+                    let cond = f_condition.execute(ctx)?.get_bool();
                     if cond {
                         f_consequence.execute(ctx)
                     } else {
@@ -169,20 +221,30 @@ impl Expr for Expression {
 
             Tuple(entries) => {
                 let f_entries: Vec<CompiledExpr> = entries.into_iter().map(|e| e.compile()).collect();
-                CompiledExpr::new(move |ctx| Value::Tuple(f_entries.iter().map(|f| f.execute(ctx)).collect()))
+                CompiledExpr::new(move |ctx| {
+                    Ok(Value::Tuple(f_entries.iter().map(|f| f.execute(ctx)).collect::<Result<_, _>>()?))
+                })
             }
 
             Function(name, args, ty) => {
                 //TODO(marvin): handle type
-                assert!(!args.is_empty());
+                let ret_ty = self.ty;
+                if args.is_empty() {
+                    return match name.as_ref() {
+                        "Set" => CompiledExpr::new(move |_| Ok(Value::Set(Box::default()))),
+                        "Map" => CompiledExpr::new(move |_| Ok(Value::Map(Box::default()))),
+                        "now" => CompiledExpr::new(move |ctx| Value::new_timestamp(ctx.ts)),
+                        f => unreachable!("Unknown function: {}, args: {:?}", f, args),
+                    };
+                }
                 let f_arg = args[0].clone().compile();
 
                 macro_rules! create_floatfn {
                     ($fn:ident) => {
                         CompiledExpr::new(move |ctx| {
-                            let arg = f_arg.execute(ctx);
+                            let arg = f_arg.execute(ctx)?;
                             match arg {
-                                Value::Float(f) => Value::new_float(f.$fn()),
+                                Value::Float(f) => Value::try_float(f.into_inner().$fn()),
                                 _ => unreachable!(),
                             }
                         })
@@ -195,28 +257,75 @@ impl Expr for Expression {
                             unreachable!("wrong number of arguments for function $fn")
                         }
                         CompiledExpr::new(move |ctx| {
-                            let fst = f_arg.execute(ctx);
-                            let snd = args[1].clone().compile().execute(ctx);
+                            let fst = f_arg.execute(ctx)?;
+                            let snd = args[1].clone().compile().execute(ctx)?;
                             match (fst, snd) {
-                                (Value::Float(f1), Value::Float(f2)) => Value::Float(f1.$fn(f2)),
-                                (Value::Signed(s1), Value::Signed(s2)) => Value::Signed(s1.$fn(s2)),
-                                (Value::Unsigned(u1), Value::Unsigned(u2)) => Value::Unsigned(u1.$fn(u2)),
+                                (Value::Float(f1), Value::Float(f2)) => Ok(Value::Float(f1.$fn(f2))),
+                                (Value::Signed(s1), Value::Signed(s2)) => Ok(Value::Signed(s1.$fn(s2))),
+                                (Value::Unsigned(u1), Value::Unsigned(u2)) => Ok(Value::Unsigned(u1.$fn(u2))),
                                 (v1, v2) => unreachable!("wrong Value types of {:?}, {:?} for function $fn", v1, v2),
                             }
                         })
                     }};
                 }
 
+                macro_rules! create_binary_floatfn {
+                    ($fn:ident) => {{
+                        let f_snd = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| match (f_arg.execute(ctx)?, f_snd.execute(ctx)?) {
+                            (Value::Float(f1), Value::Float(f2)) => {
+                                Value::try_float(f1.into_inner().$fn(f2.into_inner()))
+                            }
+                            (v1, v2) => unreachable!("wrong Value types of {:?}, {:?} for function $fn", v1, v2),
+                        })
+                    }};
+                }
+
                 match name.as_ref() {
                     "sqrt" => create_floatfn!(sqrt),
                     "sin" => create_floatfn!(sin),
                     "cos" => create_floatfn!(cos),
+                    "tan" => create_floatfn!(tan),
                     "arctan" => create_floatfn!(atan),
+                    "asin" => create_floatfn!(asin),
+                    "acos" => create_floatfn!(acos),
+                    "exp" => create_floatfn!(exp),
+                    "ln" => create_floatfn!(ln),
+                    "log10" => create_floatfn!(log10),
+                    "floor" => create_floatfn!(floor),
+                    "ceil" => create_floatfn!(ceil),
+                    "round" => create_floatfn!(round),
+                    "atan2" => create_binary_floatfn!(atan2),
+                    "hypot" => create_binary_floatfn!(hypot),
+                    "pow" => create_binary_floatfn!(powf),
+                    "clamp" => {
+                        let f_lo = args[1].clone().compile();
+                        let f_hi = args[2].clone().compile();
+                        CompiledExpr::new(move |ctx| {
+                            match (f_arg.execute(ctx)?, f_lo.execute(ctx)?, f_hi.execute(ctx)?) {
+                                (Value::Float(f), Value::Float(lo), Value::Float(hi)) => {
+                                    Ok(Value::Float(f.max(lo).min(hi)))
+                                }
+                                (Value::Signed(s), Value::Signed(lo), Value::Signed(hi)) => {
+                                    Ok(Value::Signed(s.max(lo).min(hi)))
+                                }
+                                (Value::Unsigned(u), Value::Unsigned(lo), Value::Unsigned(hi)) => {
+                                    Ok(Value::Unsigned(u.max(lo).min(hi)))
+                                }
+                                (v, lo, hi) => {
+                                    unreachable!("wrong Value types of {:?}, {:?}, {:?} for function clamp", v, lo, hi)
+                                }
+                            }
+                        })
+                    }
                     "abs" => CompiledExpr::new(move |ctx| {
-                        let arg = f_arg.execute(ctx);
+                        let arg = f_arg.execute(ctx)?;
                         match arg {
-                            Value::Float(f) => Value::new_float(f.abs()),
-                            Value::Signed(i) => Value::Signed(i.abs()),
+                            Value::Float(f) => Ok(Value::new_float(f.abs())),
+                            Value::Signed(i) if i < 0 => {
+                                Value::arith(ArithLogOp::Neg, arg, None, &ret_ty, ctx.overflow)
+                            }
+                            Value::Signed(_) => Ok(arg),
                             v => unreachable!("wrong Value type of {:?}, for function abs", v),
                         }
                     }),
@@ -236,9 +345,9 @@ impl Expr for Expression {
                         if !is_bytes {
                             let re = Regex::new(&re_str).expect("Given regular expression was invalid");
                             CompiledExpr::new(move |ctx| {
-                                let val = f_arg.execute(ctx);
+                                let val = f_arg.execute(ctx)?;
                                 if let Value::Str(s) = &val {
-                                    Value::Bool(re.is_match(s))
+                                    Ok(Value::Bool(re.is_match(s)))
                                 } else {
                                     unreachable!("expected `String`, found {:?}", val);
                                 }
@@ -246,94 +355,200 @@ impl Expr for Expression {
                         } else {
                             let re = BytesRegex::new(&re_str).expect("Given regular expression was invalid");
                             CompiledExpr::new(move |ctx| {
-                                let val = f_arg.execute(ctx);
+                                let val = f_arg.execute(ctx)?;
                                 if let Value::Bytes(b) = &val {
-                                    Value::Bool(re.is_match(b))
+                                    Ok(Value::Bool(re.is_match(b)))
                                 } else {
                                     unreachable!("expected `Bytes`, found {:?}", val);
                                 }
                             })
                         }
                     }
+                    "capture" | "captures" => {
+                        let is_bytes = matches!(&ty, Type::Function(args, _) if args[0] == Type::Bytes);
+                        let re_str = match &args[1].kind {
+                            LoadConstant(Constant::Str(s)) => s,
+                            _ => unreachable!("regex should be a string literal"),
+                        };
+                        // the group of `capture`, or none for all groups of `captures`
+                        let f_group = args.get(2).map(|group| group.clone().compile());
+                        macro_rules! create_capture {
+                            ($regex:ident, $variant:ident, $as_slice:ident) => {{
+                                let re = $regex::new(&re_str).expect("Given regular expression was invalid");
+                                CompiledExpr::new(move |ctx| {
+                                    let val = f_arg.execute(ctx)?;
+                                    let haystack = match &val {
+                                        Value::$variant(haystack) => haystack,
+                                        _ => unreachable!("expected `{}`, found {:?}", stringify!($variant), val),
+                                    };
+                                    let captures = re.captures(haystack);
+                                    let group = |ix: usize| {
+                                        captures
+                                            .as_ref()
+                                            .and_then(|captures| captures.get(ix))
+                                            .map(|m| Value::$variant(m.$as_slice().into()))
+                                            .unwrap_or(Value::None)
+                                    };
+                                    match &f_group {
+                                        Some(f_group) => match f_group.execute(ctx)? {
+                                            Value::Unsigned(ix) => Ok(group(ix as usize)),
+                                            v => unreachable!("expected `UInt64`, found {:?}", v),
+                                        },
+                                        None => Ok(Value::Tuple((1..re.captures_len()).map(group).collect())),
+                                    }
+                                })
+                            }};
+                        }
+                        if is_bytes {
+                            create_capture!(BytesRegex, Bytes, as_bytes)
+                        } else {
+                            create_capture!(Regex, Str, as_str)
+                        }
+                    }
+                    "distance" | "bearing" | "enu" => {
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|e| e.clone().compile()).collect();
+                        CompiledExpr::new(move |ctx| {
+                            let mut values = vec![f_arg.execute(ctx)?];
+                            for f in &f_rest {
+                                values.push(f.execute(ctx)?);
+                            }
+                            geo_function(&name, &values)
+                        })
+                    }
+                    "within" => {
+                        let polygon = match &args[2].kind {
+                            LoadConstant(Constant::Str(s)) => Polygon::parse(s).expect("checked by the frontend"),
+                            _ => unreachable!("polygon should be a string constant"),
+                        };
+                        let f_lon = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| {
+                            let (lat, lon) = (f_arg.execute(ctx)?, f_lon.execute(ctx)?);
+                            Ok(Value::Bool(polygon.contains(coordinate(&lat), coordinate(&lon))))
+                        })
+                    }
+                    "in_subnet" => {
+                        let f_network = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| {
+                            Ok(Value::Bool(f_arg.execute(ctx)?.in_subnet(&f_network.execute(ctx)?)))
+                        })
+                    }
+                    "is_private" | "is_loopback" | "is_multicast" | "to_string" => {
+                        CompiledExpr::new(move |ctx| Ok(Value::address_function(&name, &[f_arg.execute(ctx)?])))
+                    }
                     "at" => {
                         assert_eq!(args.len(), 2);
                         let index_arg = args[1].clone().compile();
                         CompiledExpr::new(move |ctx| {
-                            let val = f_arg.execute(ctx);
-                            let index = index_arg.execute(ctx);
+                            let val = f_arg.execute(ctx)?;
+                            let index = index_arg.execute(ctx)?;
                             match (val, index) {
                                 (Value::Bytes(b), Value::Unsigned(idx)) => {
                                     if let Some(&byte) = b.get(idx as usize) {
-                                        Value::Unsigned(byte.into())
+                                        Ok(Value::Unsigned(byte.into()))
                                     } else {
-                                        Value::None
+                                        Ok(Value::None)
                                     }
                                 }
-                                (val, _) => unreachable!("expected `Bytes`, found {:?}", val),
+                                (Value::List(l), Value::Unsigned(idx)) => {
+                                    Ok(l.get(idx as usize).cloned().unwrap_or(Value::None))
+                                }
+                                (val, _) => unreachable!("expected `Bytes` or `List`, found {:?}", val),
                             }
                         })
                     }
-                    f => unreachable!("Unknown function: {}, args: {:?}", f, args),
-                }
-            }
-
-            //Expression::Convert { from, to, expr } => CompiledExpr::new(move |ctx| {}),
-            Convert { from, to, expr } => {
-                let f_expr = expr.compile();
-
-                macro_rules! create_convert {
-                    (Float, $to:ident, $ty:ty) => {
+                    "insert" => {
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|e| e.clone().compile()).collect();
+                        CompiledExpr::new(move |ctx| match f_arg.execute(ctx)? {
+                            Value::Set(mut set) => {
+                                debug_assert_eq!(f_rest.len(), 1, "insert into a set takes an element");
+                                set.insert(f_rest[0].execute(ctx)?);
+                                Ok(Value::Set(set))
+                            }
+                            Value::Map(mut map) => {
+                                debug_assert_eq!(f_rest.len(), 2, "insert into a map takes a key and a value");
+                                map.insert(f_rest[0].execute(ctx)?, f_rest[1].execute(ctx)?);
+                                Ok(Value::Map(map))
+                            }
+                            v => unreachable!("wrong Value type of {:?} for function insert", v),
+                        })
+                    }
+                    "contains" => {
+                        assert_eq!(args.len(), 2);
+                        let f_elem = args[1].clone().compile();
                         CompiledExpr::new(move |ctx| {
-                            let v = f_expr.execute(ctx);
-                            match v {
-                                Value::Float(f) => Value::$to(f.into_inner() as $ty),
-                                v => unreachable!(
-                                    "Value type of {:?} does not match convert from type {:?}",
-                                    v,
-                                    Value::new_float(0.0)
-                                ),
+                            let elem = f_elem.execute(ctx)?;
+                            match f_arg.execute(ctx)? {
+                                Value::Set(set) => Ok(Value::Bool(set.contains(&elem))),
+                                Value::Map(map) => Ok(Value::Bool(map.contains_key(&elem))),
+                                v @ Value::Str(_) => Ok(Value::string_function("contains", &[v, elem])),
+                                v => unreachable!("wrong Value type of {:?} for function contains", v),
                             }
                         })
-                    };
-                    ($from:ident, Float, $ty:ty) => {
+                    }
+                    "get" => {
+                        assert_eq!(args.len(), 2);
+                        let f_key = args[1].clone().compile();
                         CompiledExpr::new(move |ctx| {
-                            let v = f_expr.execute(ctx);
-                            match v {
-                                Value::$from(v) => Value::new_float(v as $ty),
-                                v => unreachable!(
-                                    "Value type of {:?} does not match convert from type {:?}",
-                                    v,
-                                    Value::$from(0)
-                                ),
+                            let key = f_key.execute(ctx)?;
+                            match f_arg.execute(ctx)? {
+                                Value::Map(map) => Ok(map.get(&key).cloned().unwrap_or(Value::None)),
+                                v => unreachable!("wrong Value type of {:?} for function get", v),
                             }
                         })
-                    };
-                    ($from:ident, $to:ident, $ty:ty) => {
+                    }
+                    "len" => CompiledExpr::new(move |ctx| match f_arg.execute(ctx)? {
+                        Value::Set(set) => Ok(Value::Unsigned(set.len() as u64)),
+                        Value::List(list) => Ok(Value::Unsigned(list.len() as u64)),
+                        Value::Map(map) => Ok(Value::Unsigned(map.len() as u64)),
+                        v @ Value::Str(_) => Ok(Value::string_function("len", &[v])),
+                        v @ Value::Bytes(_) => Ok(Value::bytes_function("len", &[v])),
+                        v => unreachable!("wrong Value type of {:?} for function len", v),
+                    }),
+                    "concat" | "substring" | "starts_with" | "ends_with" | "to_lower" | "to_upper" | "split"
+                    | "parse_int" | "parse_uint" | "parse_float" | "slice" | "to_hex" | "find" => {
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|e| e.clone().compile()).collect();
                         CompiledExpr::new(move |ctx| {
-                            let v = f_expr.execute(ctx);
-                            match v {
-                                Value::$from(v) => Value::$to(v as $ty),
-                                v => unreachable!(
-                                    "Value type of {:?} does not match convert from type {:?}",
-                                    v,
-                                    Value::$from(0)
-                                ),
+                            let mut values = vec![f_arg.execute(ctx)?];
+                            for f in &f_rest {
+                                values.push(f.execute(ctx)?);
+                            }
+                            if let Value::Bytes(_) = values[0] {
+                                Ok(Value::bytes_function(&name, &values))
+                            } else {
+                                Ok(Value::string_function(&name, &values))
                             }
                         })
-                    };
+                    }
+                    read if read.starts_with("read_") => {
+                        let f_index = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| {
+                            Ok(Value::bytes_function(&name, &[f_arg.execute(ctx)?, f_index.execute(ctx)?]))
+                        })
+                    }
+                    "seconds" => CompiledExpr::new(move |ctx| Ok(Value::new_float(f_arg.execute(ctx)?.get_seconds()))),
+                    "is_some" => CompiledExpr::new(move |ctx| Ok(Value::Bool(f_arg.execute(ctx)? != Value::None))),
+                    "is_none" => CompiledExpr::new(move |ctx| Ok(Value::Bool(f_arg.execute(ctx)? == Value::None))),
+                    "or" | "unwrap_or" => {
+                        assert_eq!(args.len(), 2);
+                        let f_alternative = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| match f_arg.execute(ctx)? {
+                            Value::None => f_alternative.execute(ctx),
+                            v => Ok(v),
+                        })
+                    }
+                    f => unreachable!("Unknown function: {}, args: {:?}", f, args),
                 }
+            }
+
+            //Expression::Convert { from, to, expr } => CompiledExpr::new(move |ctx| {}),
+            Convert { from, to, expr } => {
+                let f_expr = expr.compile();
 
                 use Type::*;
-                match (from, to) {
-                    (UInt(_), UInt(_)) => CompiledExpr::new(move |ctx| f_expr.execute(ctx)),
-                    (UInt(_), Int(_)) => create_convert!(Unsigned, Signed, i64),
-                    (UInt(_), Float(_)) => create_convert!(Unsigned, Float, f64),
-                    (Int(_), UInt(_)) => create_convert!(Signed, Unsigned, u64),
-                    (Int(_), Int(_)) => CompiledExpr::new(move |ctx| f_expr.execute(ctx)),
-                    (Int(_), Float(_)) => create_convert!(Signed, Float, f64),
-                    (Float(_), UInt(_)) => create_convert!(Float, Unsigned, u64),
-                    (Float(_), Int(_)) => create_convert!(Float, Signed, i64),
-                    (Float(_), Float(_)) => CompiledExpr::new(move |ctx| f_expr.execute(ctx)),
+                match (&from, &to) {
+                    (UInt(_) | Int(_) | Float(_), UInt(_) | Int(_) | Float(_)) => {
+                        CompiledExpr::new(move |ctx| f_expr.execute(ctx)?.convert(&to, ctx.overflow))
+                    }
                     (from, to) => unreachable!("from: {:?}, to: {:?}", from, to),
                 }
             }
@@ -342,11 +557,11 @@ impl Expr for Expression {
                 let f_expr = expr.compile();
                 let f_default = default.compile();
                 CompiledExpr::new(move |ctx| {
-                    let v = f_expr.execute(ctx);
+                    let v = f_expr.execute(ctx)?;
                     if let Value::None = v {
                         f_default.execute(ctx)
                     } else {
-                        v
+                        Ok(v)
                     }
                 })
             }
@@ -354,13 +569,57 @@ impl Expr for Expression {
             TupleAccess(expr, num) => {
                 let f_expr = expr.compile();
                 CompiledExpr::new(move |ctx| {
-                    if let Value::Tuple(args) = f_expr.execute(ctx) {
-                        args[num].clone()
+                    if let Value::Tuple(args) = f_expr.execute(ctx)? {
+                        Ok(args[num].clone())
                     } else {
                         unreachable!("verified by type checker");
                     }
                 })
             }
+
+            Let { variable, value, body } => {
+                let f_value = value.compile();
+                let f_body = body.compile();
+                CompiledExpr::new(move |ctx| {
+                    bind_local(&ctx.locals, variable, f_value.execute(ctx)?);
+                    f_body.execute(ctx)
+                })
+            }
+
+            LocalVariable(variable) => CompiledExpr::new(move |ctx| Ok(ctx.locals.borrow()[variable].clone())),
+        }
+    }
+
+    fn compile_robustness(self) -> CompiledExpr {
+        use rtlola_frontend::ir::ArithLogOp::*;
+        let robustness = |f: &CompiledExpr, ctx: &EvaluationContext<'_>| {
+            Ok::<_, RuntimeError>(f.execute(ctx)?.get_number().expect("robustness is a number"))
+        };
+        match self.kind {
+            ExpressionKind::ArithLog(op @ (Not | And | Or), operands, _) => {
+                let f_operands: Vec<CompiledExpr> = operands.into_iter().map(|e| e.compile_robustness()).collect();
+                match op {
+                    Not => CompiledExpr::new(move |ctx| Ok(Value::new_float(-robustness(&f_operands[0], ctx)?))),
+                    And => CompiledExpr::new(move |ctx| {
+                        Ok(Value::new_float(robustness(&f_operands[0], ctx)?.min(robustness(&f_operands[1], ctx)?)))
+                    }),
+                    _ => CompiledExpr::new(move |ctx| {
+                        Ok(Value::new_float(robustness(&f_operands[0], ctx)?.max(robustness(&f_operands[1], ctx)?)))
+                    }),
+                }
+            }
+            ExpressionKind::ArithLog(op @ (Eq | Lt | Le | Ne | Ge | Gt), operands, _) => {
+                let f_operands: Vec<CompiledExpr> = operands.into_iter().map(|e| e.compile()).collect();
+                CompiledExpr::new(move |ctx| {
+                    let lhs = f_operands[0].execute(ctx)?;
+                    let rhs = f_operands[1].execute(ctx)?;
+                    Ok(Value::new_float(comparison_robustness(op, &lhs, &rhs)))
+                })
+            }
+            _ => {
+                let f_expr = self.compile();
+                CompiledExpr::new(move |ctx| Ok(Value::new_float(boolean_robustness(f_expr.execute(ctx)?.get_bool()))))
+            }
         }
     }
 }
@@ -25,7 +25,7 @@ pub(crate) struct Controller {
 
 impl Controller {
     pub(crate) fn new(ir: RTLolaIR, config: EvalConfig) -> Self {
-        let output_handler = Arc::new(OutputHandler::new(&config, ir.triggers.len()));
+        let output_handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
         Self { ir, config, output_handler }
     }
 
@@ -87,9 +87,19 @@ impl Controller {
                 WorkItem::Time(t, ts) => self.evaluate_timed_item(&mut evaluator, &t, ts),
                 WorkItem::End => {
                     self.output_handler.output(|| "Finished entire input. Terminating.");
+                    self.output_handler.terminate();
+                    if let Some(failure) = self.output_handler.trigger_failure() {
+                        eprintln!("{}", failure);
+                        std::process::exit(1);
+                    }
                     std::process::exit(0);
                 }
             }
+            if let Some(failure) = self.output_handler.runtime_failure() {
+                self.output_handler.terminate();
+                eprintln!("{}", failure);
+                std::process::exit(1);
+            }
         }
     }
 
@@ -158,14 +168,20 @@ impl Controller {
                                 due_streams,
                                 &mut next_deadline,
                             );
+                            if self.aborted() {
+                                break 'outer;
+                            }
                         }
                         self.output_handler.debug(|| format!("Schedule Event {:?}.", (&e, ts)));
                         self.evaluate_event_item(&mut evaluator, &e, ts);
                         current_time = ts;
+                        if self.aborted() {
+                            break 'outer;
+                        }
                     }
                     WorkItem::Time(_, _) => panic!("Received time command in offline mode."),
                     WorkItem::End => {
-                        while has_time_driven && current_time == next_deadline {
+                        while has_time_driven && current_time == next_deadline && !self.aborted() {
                             // schedule last timed event before terminating
                             due_streams = self.schedule_timed(
                                 &mut evaluator,
@@ -174,16 +190,31 @@ impl Controller {
                                 &mut next_deadline,
                             );
                         }
-                        self.output_handler.output(|| "Finished entire input. Terminating.");
-                        self.output_handler.terminate();
+                        if !self.aborted() {
+                            self.output_handler.output(|| "Finished entire input. Terminating.");
+                        }
                         break 'outer;
                     }
                 }
             }
         }
+        self.output_handler.terminate();
 
+        // Dropping the receiver stops the EventDrivenManager in case the evaluation was aborted.
+        drop(work_rx);
         edm_thread.join().expect("Could not join on EventDrivenManger thread");
-        Ok(())
+        if let Some(failure) = self.output_handler.runtime_failure() {
+            return Err(failure.into());
+        }
+        match self.output_handler.trigger_failure() {
+            Some(failure) => Err(failure.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns whether the evaluation has to stop because of a runtime error, see `RuntimeErrorPolicy::Abort`.
+    fn aborted(&self) -> bool {
+        self.output_handler.runtime_failure().is_some()
     }
 
     fn schedule_timed<'a>(
@@ -96,9 +96,9 @@ impl EventDrivenManager {
                 local_queue.push(WorkItem::Event(event, time));
                 self.current_cycle += 1;
             }
-            match work_queue.send(local_queue) {
-                Ok(_) => {}
-                Err(e) => self.out_handler.runtime_warning(|| format!("Error when sending local queue. {}", e)),
+            if work_queue.send(local_queue).is_err() {
+                // The evaluation stopped early, e.g., due to a runtime error, so there is nothing left to do.
+                return Ok(());
             }
         }
     }
@@ -1,4 +1,4 @@
-use crate::basics::{EvalConfig, OutputHandler, Time};
+use crate::basics::{EvalConfig, OutputHandler, RuntimeFailure, Time, TriggerFailure};
 use crate::coordination::Event;
 use crate::evaluator::{Evaluator, EvaluatorData};
 use crate::storage::Value;
@@ -115,4 +115,20 @@ impl Monitor {
     pub fn name_for_output(&self, id: OutputReference) -> &str {
         self.ir.outputs[id].name.as_str()
     }
+
+    /**
+    Get the first raised trigger the trigger policy of the configuration fails on, if any.
+    */
+    pub fn trigger_failure(&self) -> Option<TriggerFailure> {
+        self.output_handler.trigger_failure()
+    }
+
+    /**
+    Get the first runtime error, e.g., a division by zero, if the configuration aborts on runtime errors.
+
+    Streams that fail to evaluate produce no value; once this returns a failure, the caller should stop feeding events.
+    */
+    pub fn runtime_failure(&self) -> Option<RuntimeFailure> {
+        self.output_handler.runtime_failure()
+    }
 }
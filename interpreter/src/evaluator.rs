@@ -1,12 +1,17 @@
-use crate::basics::{EvalConfig, EvaluatorChoice::*, ExecutionMode, OutputHandler, Time};
+use crate::basics::{EvalConfig, EvaluatorChoice::*, ExecutionMode, OutputHandler, OverflowMode, Time};
 use crate::closuregen::{CompiledExpr, Expr};
-use crate::storage::{GlobalStore, Value};
+use crate::geo::{self, Polygon};
+use crate::storage::{round_float, GlobalStore, RuntimeError, Value};
 use bit_set::BitSet;
+use ip_network::{Ipv4Network, Ipv6Network};
+use regex::bytes::Regex as BytesRegex;
 use regex::Regex;
 use rtlola_frontend::ir::{
-    Activation, Constant, Expression, InputReference, Offset, OutputReference, RTLolaIR, StreamAccessKind,
-    StreamReference, Trigger, Type, WindowReference,
+    Activation, ArithLogOp, Constant, Expression, InputReference, Offset, OutputReference, RTLolaIR, StreamAccessKind,
+    StreamReference, Trigger, TriggerOptions, Type, WindowReference,
 };
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -19,6 +24,51 @@ pub(crate) enum ActivationCondition {
     General(Activation<StreamReference>),
 }
 
+/// The state of a trigger needed to suppress repeated reports according to its `TriggerOptions`.
+#[derive(Debug, Default)]
+pub(crate) struct TriggerState {
+    // Set after a report of an edge-triggered trigger until it is re-armed.
+    disarmed: bool,
+    // The time since which the condition has been false.
+    false_since: Option<Time>,
+    // The times of the reports within the rate limit interval.
+    reports: VecDeque<Time>,
+}
+
+impl TriggerState {
+    /// Accepts the current value of the trigger condition and decides whether the trigger is reported.
+    fn accept(&mut self, options: &TriggerOptions, condition: bool, ts: Time) -> bool {
+        if options.is_edge_triggered() {
+            let rearm = options.rearm.unwrap_or_default();
+            if self.disarmed && self.false_since.is_some_and(|since| ts - since >= rearm) {
+                self.disarmed = false;
+            }
+            if !condition {
+                self.false_since.get_or_insert(ts);
+                return false;
+            }
+            self.false_since = None;
+            if self.disarmed {
+                return false;
+            }
+        }
+        if !condition {
+            return false;
+        }
+        if let Some((count, interval)) = options.limit {
+            while self.reports.front().is_some_and(|&report| report + interval <= ts) {
+                self.reports.pop_front();
+            }
+            if self.reports.len() >= count as usize {
+                return false;
+            }
+            self.reports.push_back(ts);
+        }
+        self.disarmed = options.is_edge_triggered();
+        true
+    }
+}
+
 pub(crate) struct EvaluatorData {
     // Evaluation order of output streams
     layers: Vec<Vec<OutputReference>>,
@@ -27,11 +77,15 @@ pub(crate) struct EvaluatorData {
     // Indexed by stream reference.
     exprs: Vec<Expression>,
     global_store: GlobalStore,
+    // The polygons of `within`, parsed on their first evaluation by the interpreted evaluator.
+    polygons: RefCell<HashMap<String, Polygon>>,
     start_time: Instant,           // only valid in online mode
     time_last_event: Option<Time>, // only valid in offline mode
     fresh_inputs: BitSet,
     fresh_outputs: BitSet,
     triggers: Vec<Option<Trigger>>,
+    // Indexed by trigger index.
+    trigger_states: Vec<TriggerState>,
     ir: RTLolaIR,
     handler: Arc<OutputHandler>,
     config: EvalConfig,
@@ -47,12 +101,21 @@ pub(crate) struct Evaluator {
     exprs: &'static Vec<Expression>,
     // Indexed by stream reference.
     compiled_exprs: Vec<CompiledExpr>,
+    // Indexed by stream reference, only present for triggers reporting their robustness.
+    compiled_robustness: Vec<Option<CompiledExpr>>,
+    // Indexed by stream reference, the placeholders of trigger messages.
+    compiled_placeholders: Vec<Vec<CompiledExpr>>,
+    // Indexed by input reference, only present for inputs with an assumption.
+    compiled_assumptions: Vec<Option<CompiledExpr>>,
     global_store: &'static mut GlobalStore,
+    polygons: &'static RefCell<HashMap<String, Polygon>>,
     start_time: &'static Instant,               // only valid in online mode
     time_last_event: &'static mut Option<Time>, // only valid in offline mode
     fresh_inputs: &'static mut BitSet,
     fresh_outputs: &'static mut BitSet,
     triggers: &'static Vec<Option<Trigger>>,
+    // Indexed by trigger index.
+    trigger_states: &'static mut Vec<TriggerState>,
     ir: &'static RTLolaIR,
     handler: &'static OutputHandler,
     config: &'static EvalConfig,
@@ -63,13 +126,20 @@ struct ExpressionEvaluator<'e> {
     global_store: &'e GlobalStore,
     fresh_inputs: &'e BitSet,
     fresh_outputs: &'e BitSet,
+    overflow: OverflowMode,
+    // Indexed by local variable.
+    locals: RefCell<Vec<Value>>,
+    polygons: &'e RefCell<HashMap<String, Polygon>>,
 }
 
 pub(crate) struct EvaluationContext<'e> {
-    ts: Time,
+    pub(crate) ts: Time,
     pub(crate) global_store: &'e GlobalStore,
     pub(crate) fresh_inputs: &'e BitSet,
     pub(crate) fresh_outputs: &'e BitSet,
+    pub(crate) overflow: OverflowMode,
+    // Indexed by local variable.
+    pub(crate) locals: RefCell<Vec<Value>>,
 }
 
 impl EvaluatorData {
@@ -96,16 +166,19 @@ impl EvaluatorData {
         for t in &ir.triggers {
             triggers[t.reference.out_ix()] = Some(t.clone());
         }
+        let trigger_states = ir.triggers.iter().map(|_| TriggerState::default()).collect();
         EvaluatorData {
             layers,
             activation_conditions,
             exprs,
             global_store,
+            polygons: RefCell::default(),
             start_time,
             time_last_event: None,
             fresh_inputs,
             fresh_outputs,
             triggers,
+            trigger_states,
             ir,
             handler,
             config,
@@ -123,18 +196,54 @@ impl EvaluatorData {
         } else {
             vec![]
         };
+        let compiled_robustness: Vec<Option<CompiledExpr>> = if leaked_data.config.evaluator == ClosureBased {
+            leaked_data
+                .ir
+                .outputs
+                .iter()
+                .zip(&leaked_data.triggers)
+                .map(|(o, trigger)| {
+                    trigger.as_ref().filter(|t| t.robustness).map(|_| o.expr.clone().compile_robustness())
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let compiled_placeholders: Vec<Vec<CompiledExpr>> = if leaked_data.config.evaluator == ClosureBased {
+            leaked_data
+                .triggers
+                .iter()
+                .map(|trigger| {
+                    trigger.iter().flat_map(Trigger::placeholders).map(|expr| expr.clone().compile()).collect()
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let compiled_assumptions: Vec<Option<CompiledExpr>> = if leaked_data.config.evaluator == ClosureBased {
+            leaked_data.ir.inputs.iter().map(|i| i.assumption.clone().map(Expr::compile)).collect()
+        } else {
+            vec![]
+        };
 
         Evaluator {
             layers: &leaked_data.layers,
             activation_conditions: &leaked_data.activation_conditions,
             exprs: &leaked_data.exprs,
             compiled_exprs,
+            compiled_robustness,
+            compiled_placeholders,
+            compiled_assumptions,
             global_store: &mut leaked_data.global_store,
+            polygons: &leaked_data.polygons,
             start_time: &leaked_data.start_time,
             time_last_event: &mut leaked_data.time_last_event,
             fresh_inputs: &mut leaked_data.fresh_inputs,
             fresh_outputs: &mut leaked_data.fresh_outputs,
             triggers: &leaked_data.triggers,
+            trigger_states: &mut leaked_data.trigger_states,
             ir: &leaked_data.ir,
             handler: &leaked_data.handler,
             config: &leaked_data.config,
@@ -182,9 +291,51 @@ impl Evaluator {
         }
     }
 
+    /// Checks whether the new value of an input, which is already stored and fresh, satisfies the assumption of the
+    /// input.
+    fn satisfies_assumption(&self, input: InputReference, ts: Time) -> bool {
+        let assumption = match &self.ir.inputs[input].assumption {
+            Some(assumption) => assumption,
+            None => return true,
+        };
+        let res = match self.config.evaluator {
+            ClosureBased => {
+                let ctx = EvaluationContext {
+                    ts,
+                    global_store: self.global_store,
+                    fresh_inputs: self.fresh_inputs,
+                    fresh_outputs: self.fresh_outputs,
+                    overflow: self.config.overflow,
+                    locals: RefCell::default(),
+                };
+                self.compiled_assumptions[input].as_ref().expect("input has an assumption").execute(&ctx)
+            }
+            Interpreted => {
+                let expr_eval = ExpressionEvaluator {
+                    global_store: self.global_store,
+                    fresh_inputs: self.fresh_inputs,
+                    fresh_outputs: self.fresh_outputs,
+                    overflow: self.config.overflow,
+                    locals: RefCell::default(),
+                    polygons: self.polygons,
+                };
+                expr_eval.eval_expr(assumption, ts)
+            }
+        };
+        // An assumption that cannot be evaluated is not satisfied.
+        res == Ok(Value::Bool(true))
+    }
+
     fn accept_input(&mut self, input: InputReference, v: Value, ts: Time) {
-        self.global_store.get_in_instance_mut(input).push_value(v.clone());
+        let dropped = self.global_store.get_in_instance_mut(input).push_value(v.clone());
         self.fresh_inputs.insert(input);
+        // A value violating the assumption is rejected, as if it was never received.
+        if !self.satisfies_assumption(input, ts) {
+            self.global_store.get_in_instance_mut(input).revert_push(dropped);
+            self.fresh_inputs.remove(input);
+            self.handler.assumption_violated(&self.ir.inputs[input], &v, ts);
+            return;
+        }
         self.handler.debug(|| format!("InputStream[{}] := {:?}.", input, v.clone()));
         let extended = &self.ir.inputs[input];
         for &win in &extended.dependent_windows {
@@ -252,6 +403,10 @@ impl Evaluator {
                 expr_eval.eval_expr(&exprs[ix], ts)
             }
         };
+        let res = match res {
+            Ok(res) => res,
+            Err(e) => return self.runtime_error(output, &e, ts),
+        };
 
         match self.is_trigger(output) {
             None => {
@@ -259,13 +414,37 @@ impl Evaluator {
                 self.global_store.get_out_instance_mut(output).unwrap().push_value(res.clone()); // TODO: unsafe unwrap.
                 self.fresh_outputs.insert(ix);
 
-                self.handler.output(|| format!("OutputStream[{}] := {:?}.", ix, res.clone()));
+                if !self.ir.outputs[ix].hidden {
+                    self.handler.output(|| format!("OutputStream[{}] := {:?}.", ix, res.clone()));
+                }
             }
 
             Some(trig) => {
+                let robustness = if trig.robustness {
+                    let robustness = match self.eval_robustness(output, ts) {
+                        Ok(robustness) => robustness,
+                        Err(e) => return self.runtime_error(output, &e, ts),
+                    };
+                    self.handler.output(|| format!("Trigger[{}] robustness := {}.", trig.trigger_idx, robustness));
+                    Some(robustness)
+                } else {
+                    None
+                };
                 // Check if we have to emit a warning.
-                if let Value::Bool(true) = res {
-                    self.handler.trigger(|| format!("Trigger: {}", trig.message), trig.trigger_idx, ts)
+                let raised = res == Value::Bool(true);
+                if self.trigger_states[trig.trigger_idx].accept(&trig.options, raised, ts) {
+                    self.handler.trigger(
+                        || match robustness {
+                            Some(robustness) => {
+                                format!("{} (robustness: {})", self.trigger_message(output, trig, ts), robustness)
+                            }
+                            None => self.trigger_message(output, trig, ts),
+                        },
+                        trig,
+                        ts,
+                    )
+                } else if raised {
+                    self.handler.suppressed_trigger(trig);
                 }
             }
         }
@@ -278,12 +457,60 @@ impl Evaluator {
         // TODO: Dependent streams?
     }
 
+    /// Reports a runtime error during the evaluation of a stream, which consequently produces no value.
+    /// Streams that only lack a value because a synchronously accessed stream failed are not reported again.
+    fn runtime_error(&self, output: OutputReference, error: &RuntimeError, ts: Time) {
+        if *error != RuntimeError::MissingValue {
+            self.handler.runtime_error(&self.ir.outputs[output].name, error, ts);
+        }
+    }
+
+    /// Renders the message of a trigger with the current values of its placeholders.
+    /// Placeholders whose evaluation fails are reported as runtime errors and rendered as `none`.
+    fn trigger_message(&self, output: OutputReference, trigger: &Trigger, ts: Time) -> String {
+        let values: Vec<Result<Value, RuntimeError>> = match self.config.evaluator {
+            ClosureBased => {
+                let (ctx, _) = self.as_EvaluationContext(ts);
+                self.compiled_placeholders[output].iter().map(|compiled| compiled.execute(&ctx)).collect()
+            }
+            Interpreted => {
+                let (expr_eval, _) = self.as_ExpressionEvaluator();
+                trigger.placeholders().map(|expr| expr_eval.eval_expr(expr, ts)).collect()
+            }
+        };
+        let values: Vec<Value> = values
+            .into_iter()
+            .map(|value| {
+                value.unwrap_or_else(|e| {
+                    self.runtime_error(output, &e, ts);
+                    Value::None
+                })
+            })
+            .collect();
+        OutputHandler::trigger_message(trigger, &values)
+    }
+
+    /// Computes the robustness of a trigger condition, i.e., the signed distance to its violation.
+    fn eval_robustness(&self, output: OutputReference, ts: Time) -> Result<f64, RuntimeError> {
+        match self.config.evaluator {
+            ClosureBased => {
+                let (ctx, _) = self.as_EvaluationContext(ts);
+                let compiled = self.compiled_robustness[output].as_ref().expect("trigger reports its robustness");
+                Ok(compiled.execute(&ctx)?.get_number().expect("robustness is a number"))
+            }
+            Interpreted => {
+                let (expr_eval, exprs) = self.as_ExpressionEvaluator();
+                expr_eval.eval_robustness(&exprs[output], ts)
+            }
+        }
+    }
+
     fn clear_freshness(&mut self) {
         self.fresh_inputs.clear();
         self.fresh_outputs.clear();
     }
 
-    fn is_trigger(&self, ix: OutputReference) -> Option<&Trigger> {
+    fn is_trigger(&self, ix: OutputReference) -> Option<&'static Trigger> {
         self.triggers[ix].as_ref()
     }
 
@@ -308,6 +535,9 @@ impl Evaluator {
                 global_store: &self.global_store,
                 fresh_inputs: &self.fresh_inputs,
                 fresh_outputs: &self.fresh_outputs,
+                overflow: self.config.overflow,
+                locals: RefCell::default(),
+                polygons: self.polygons,
             },
             &self.exprs,
         )
@@ -321,6 +551,8 @@ impl Evaluator {
                 global_store: &self.global_store,
                 fresh_inputs: &self.fresh_inputs,
                 fresh_outputs: &self.fresh_outputs,
+                overflow: self.config.overflow,
+                locals: RefCell::default(),
             },
             &self.compiled_exprs,
         )
@@ -328,15 +560,25 @@ impl Evaluator {
 }
 
 impl<'a> ExpressionEvaluator<'a> {
-    fn eval_expr(&self, expr: &Expression, ts: Time) -> Value {
+    fn eval_expr(&self, expr: &Expression, ts: Time) -> Result<Value, RuntimeError> {
         use rtlola_frontend::ir::ExpressionKind::*;
-        match &expr.kind {
+        let res = match &expr.kind {
             LoadConstant(c) => match c {
                 Constant::Bool(b) => Value::Bool(*b),
                 Constant::UInt(u) => Value::Unsigned(*u),
                 Constant::Int(i) => Value::Signed(*i),
-                Constant::Float(f) => Value::Float((*f).into()),
+                Constant::Float(f) => match &expr.ty {
+                    Type::Float(fty) => Value::new_float(round_float(*f, fty)),
+                    _ => Value::Float((*f).into()),
+                },
+                Constant::Duration(ns) => Value::Duration(*ns),
                 Constant::Str(s) => Value::Str(s.clone().into_boxed_str()),
+                Constant::IPv4Net(addr, prefix) => {
+                    Value::IPv4Net(Ipv4Network::new(*addr, *prefix).expect("checked by the frontend"))
+                }
+                Constant::IPv6Net(addr, prefix) => {
+                    Value::IPv6Net(Ipv6Network::new(*addr, *prefix).expect("checked by the frontend"))
+                }
             },
 
             ArithLog(op, operands, _ty) => {
@@ -350,35 +592,39 @@ impl<'a> ExpressionEvaluator<'a> {
                 };
                 match arity {
                     1 => {
-                        let operand = self.eval_expr(&operands[0], ts);
+                        let operand = self.eval_expr(&operands[0], ts)?;
                         match *op {
                             Not => !operand,
-                            Neg => -operand,
-                            BitNot => !operand,
+                            Neg | BitNot => Value::arith(*op, operand, None, &expr.ty, self.overflow)?,
                             _ => unreachable!(),
                         }
                     }
                     2 => {
-                        let lhs = self.eval_expr(&operands[0], ts);
+                        let lhs = self.eval_expr(&operands[0], ts)?;
 
                         if *op == And {
                             // evaluate lazy
-                            return if lhs.get_bool() { self.eval_expr(&operands[1], ts) } else { Value::Bool(false) };
+                            return if lhs.get_bool() {
+                                self.eval_expr(&operands[1], ts)
+                            } else {
+                                Ok(Value::Bool(false))
+                            };
                         }
                         if *op == Or {
                             // evaluate lazy
-                            return if lhs.get_bool() { Value::Bool(true) } else { self.eval_expr(&operands[1], ts) };
+                            return if lhs.get_bool() {
+                                Ok(Value::Bool(true))
+                            } else {
+                                self.eval_expr(&operands[1], ts)
+                            };
                         }
 
-                        let rhs = self.eval_expr(&operands[1], ts);
+                        let rhs = self.eval_expr(&operands[1], ts)?;
 
                         match *op {
-                            Add => lhs + rhs,
-                            Sub => lhs - rhs,
-                            Mul => lhs * rhs,
-                            Div => lhs / rhs,
-                            Rem => lhs % rhs,
-                            Pow => lhs.pow(rhs),
+                            Add | Sub | Mul | Div | Rem | Pow | Shl => {
+                                Value::arith(*op, lhs, Some(rhs), &expr.ty, self.overflow)?
+                            }
                             Eq => Value::Bool(lhs == rhs),
                             Lt => Value::Bool(lhs < rhs),
                             Le => Value::Bool(lhs <= rhs),
@@ -388,7 +634,6 @@ impl<'a> ExpressionEvaluator<'a> {
                             BitAnd => lhs & rhs,
                             BitOr => lhs | rhs,
                             BitXor => lhs ^ rhs,
-                            Shl => lhs << rhs,
                             Shr => lhs >> rhs,
                             Not | BitNot | Neg | And | Or => unreachable!(),
                         }
@@ -397,11 +642,24 @@ impl<'a> ExpressionEvaluator<'a> {
                 }
             }
 
+            OptionalArithLog(op, operands, _ty) => {
+                use rtlola_frontend::ir::ArithLogOp::*;
+                let lhs = self.eval_expr(&operands[0], ts)?;
+                let rhs = self.eval_expr(&operands[1], ts)?;
+                if lhs == Value::None || rhs == Value::None {
+                    return Ok(Value::None);
+                }
+                match *op {
+                    Add | Sub | Mul | Div | Rem | Pow => Value::arith(*op, lhs, Some(rhs), &expr.ty, self.overflow)?,
+                    op => unreachable!("{:?} is not applicable to optional values", op),
+                }
+            }
+
             Ite { condition, consequence, alternative, .. } => {
-                if self.eval_expr(condition, ts).get_bool() {
-                    self.eval_expr(consequence, ts)
+                if self.eval_expr(condition, ts)?.get_bool() {
+                    self.eval_expr(consequence, ts)?
                 } else {
-                    self.eval_expr(alternative, ts)
+                    self.eval_expr(alternative, ts)?
                 }
             }
 
@@ -414,7 +672,7 @@ impl<'a> ExpressionEvaluator<'a> {
             StreamAccess(str_ref, kind) => {
                 use StreamAccessKind::*;
                 match kind {
-                    Sync => self.lookup_latest_check(*str_ref),
+                    Sync => self.lookup_latest_check(*str_ref)?,
                     Hold => self.lookup_latest(*str_ref),
                     Optional => {
                         use StreamReference::*;
@@ -441,13 +699,20 @@ impl<'a> ExpressionEvaluator<'a> {
             WindowLookup(win_ref) => self.lookup_window(*win_ref, ts),
 
             Function(name, args, _ty) => {
-                assert!(!args.is_empty());
-                let fst = self.eval_expr(&args[0], ts);
+                if args.is_empty() {
+                    return Ok(match name.as_ref() {
+                        "Set" => Value::Set(Box::default()),
+                        "Map" => Value::Map(Box::default()),
+                        "now" => Value::new_timestamp(ts)?,
+                        _ => unreachable!("Unknown function: {}, args: {:?}", name, args),
+                    });
+                }
+                let fst = self.eval_expr(&args[0], ts)?;
 
                 macro_rules! create_float_arith {
                     ($fn:ident) => {
                         match fst {
-                            Value::Float(f) => Value::new_float(f.$fn()),
+                            Value::Float(f) => Value::try_float(f.into_inner().$fn())?,
                             v => unreachable!("wrong Value type of {:?} for function $fn", v),
                         }
                     };
@@ -458,7 +723,7 @@ impl<'a> ExpressionEvaluator<'a> {
                         if args.len() != 2 {
                             unreachable!("wrong number of arguments for function $fn")
                         }
-                        let snd = self.eval_expr(&args[1], ts);
+                        let snd = self.eval_expr(&args[1], ts)?;
                         match (fst, snd) {
                             (Value::Float(f1), Value::Float(f2)) => Value::Float(f1.$fn(f2)),
                             (Value::Signed(s1), Value::Signed(s2)) => Value::Signed(s1.$fn(s2)),
@@ -468,14 +733,55 @@ impl<'a> ExpressionEvaluator<'a> {
                     }};
                 }
 
+                macro_rules! create_binary_float_arith {
+                    ($fn:ident) => {{
+                        let snd = self.eval_expr(&args[1], ts)?;
+                        match (fst, snd) {
+                            (Value::Float(f1), Value::Float(f2)) => {
+                                Value::try_float(f1.into_inner().$fn(f2.into_inner()))?
+                            }
+                            (v1, v2) => unreachable!("wrong Value types of {:?}, {:?} for function $fn", v1, v2),
+                        }
+                    }};
+                }
+
                 match name.as_ref() {
                     "sqrt" => create_float_arith!(sqrt),
                     "sin" => create_float_arith!(sin),
                     "cos" => create_float_arith!(cos),
+                    "tan" => create_float_arith!(tan),
                     "arctan" => create_float_arith!(atan),
+                    "asin" => create_float_arith!(asin),
+                    "acos" => create_float_arith!(acos),
+                    "exp" => create_float_arith!(exp),
+                    "ln" => create_float_arith!(ln),
+                    "log10" => create_float_arith!(log10),
+                    "floor" => create_float_arith!(floor),
+                    "ceil" => create_float_arith!(ceil),
+                    "round" => create_float_arith!(round),
+                    "atan2" => create_binary_float_arith!(atan2),
+                    "hypot" => create_binary_float_arith!(hypot),
+                    "pow" => create_binary_float_arith!(powf),
+                    "clamp" => {
+                        let lo = self.eval_expr(&args[1], ts)?;
+                        let hi = self.eval_expr(&args[2], ts)?;
+                        match (fst, lo, hi) {
+                            (Value::Float(f), Value::Float(lo), Value::Float(hi)) => Value::Float(f.max(lo).min(hi)),
+                            (Value::Signed(s), Value::Signed(lo), Value::Signed(hi)) => {
+                                Value::Signed(s.max(lo).min(hi))
+                            }
+                            (Value::Unsigned(u), Value::Unsigned(lo), Value::Unsigned(hi)) => {
+                                Value::Unsigned(u.max(lo).min(hi))
+                            }
+                            (v, lo, hi) => {
+                                unreachable!("wrong Value types of {:?}, {:?}, {:?} for function clamp", v, lo, hi)
+                            }
+                        }
+                    }
                     "abs" => match fst {
                         Value::Float(f) => Value::new_float(f.abs()),
-                        Value::Signed(i) => Value::Signed(i.abs()),
+                        Value::Signed(i) if i < 0 => Value::arith(ArithLogOp::Neg, fst, None, &expr.ty, self.overflow)?,
+                        Value::Signed(_) => fst,
                         _ => {
                             unreachable!();
                         }
@@ -499,55 +805,183 @@ impl<'a> ExpressionEvaluator<'a> {
                             unreachable!()
                         }
                     }
-                    _ => unreachable!("Unknown function: {}, args: {:?}", name, args),
-                }
-            }
-
-            Tuple(entries) => Value::Tuple(entries.iter().map(|e| self.eval_expr(e, ts)).collect()),
-
-            Convert { from, to, expr } => {
-                use Type::*;
-                let v = self.eval_expr(expr, ts);
-                match (from, v) {
-                    (UInt(_), Value::Unsigned(u)) => match to {
-                        UInt(_) => Value::Unsigned(u),
-                        Int(_) => Value::Signed(u as i64),
-                        Float(_) => Value::new_float(u as f64),
-                        _ => unreachable!(),
+                    "capture" | "captures" => {
+                        let re_str = match &args[1].kind {
+                            LoadConstant(Constant::Str(s)) => s,
+                            _ => unreachable!("regex should be a string literal"),
+                        };
+                        // the group of `capture`, or none for all groups of `captures`
+                        let group_ix = match args.get(2).map(|group| self.eval_expr(group, ts)).transpose()? {
+                            Some(Value::Unsigned(ix)) => Some(ix as usize),
+                            Some(v) => unreachable!("expected `UInt64`, found {:?}", v),
+                            None => None,
+                        };
+                        macro_rules! capture {
+                            ($regex:ident, $variant:ident, $haystack:expr, $as_slice:ident) => {{
+                                let re = $regex::new(&re_str).expect("Given regular expression was invalid");
+                                let captures = re.captures($haystack);
+                                let group = |ix: usize| {
+                                    captures
+                                        .as_ref()
+                                        .and_then(|captures| captures.get(ix))
+                                        .map(|m| Value::$variant(m.$as_slice().into()))
+                                        .unwrap_or(Value::None)
+                                };
+                                match group_ix {
+                                    Some(ix) => group(ix),
+                                    None => Value::Tuple((1..re.captures_len()).map(group).collect()),
+                                }
+                            }};
+                        }
+                        match &fst {
+                            Value::Str(s) => capture!(Regex, Str, s, as_str),
+                            Value::Bytes(b) => capture!(BytesRegex, Bytes, b, as_bytes),
+                            v => unreachable!("wrong Value type of {:?} for function {}", v, name),
+                        }
+                    }
+                    "distance" | "bearing" | "enu" => {
+                        let rest =
+                            args[1..].iter().map(|arg| self.eval_expr(arg, ts)).collect::<Result<Vec<_>, _>>()?;
+                        geo::geo_function(name, &[vec![fst], rest].concat())?
+                    }
+                    "within" => {
+                        let lon = self.eval_expr(&args[1], ts)?;
+                        let polygon = match &args[2].kind {
+                            LoadConstant(Constant::Str(s)) => s,
+                            _ => unreachable!("polygon should be a string constant"),
+                        };
+                        let mut polygons = self.polygons.borrow_mut();
+                        if !polygons.contains_key(polygon) {
+                            polygons.insert(polygon.clone(), Polygon::parse(polygon).expect("checked by the frontend"));
+                        }
+                        Value::Bool(polygons[polygon].contains(geo::coordinate(&fst), geo::coordinate(&lon)))
+                    }
+                    "in_subnet" => {
+                        let network = self.eval_expr(&args[1], ts)?;
+                        Value::Bool(fst.in_subnet(&network))
+                    }
+                    "is_private" | "is_loopback" | "is_multicast" | "to_string" => {
+                        Value::address_function(name, &[fst])
+                    }
+                    "insert" => match fst {
+                        Value::Set(mut set) => {
+                            debug_assert_eq!(args.len(), 2, "insert into a set takes an element");
+                            set.insert(self.eval_expr(&args[1], ts)?);
+                            Value::Set(set)
+                        }
+                        Value::Map(mut map) => {
+                            debug_assert_eq!(args.len(), 3, "insert into a map takes a key and a value");
+                            map.insert(self.eval_expr(&args[1], ts)?, self.eval_expr(&args[2], ts)?);
+                            Value::Map(map)
+                        }
+                        v => unreachable!("wrong Value type of {:?} for function insert", v),
+                    },
+                    "contains" => {
+                        let snd = self.eval_expr(&args[1], ts)?;
+                        match fst {
+                            Value::Set(set) => Value::Bool(set.contains(&snd)),
+                            Value::Map(map) => Value::Bool(map.contains_key(&snd)),
+                            Value::Str(_) => Value::string_function(name, &[fst, snd]),
+                            v => unreachable!("wrong Value type of {:?} for function contains", v),
+                        }
+                    }
+                    "at" => match (fst, self.eval_expr(&args[1], ts)?) {
+                        (Value::Bytes(b), Value::Unsigned(idx)) => {
+                            b.get(idx as usize).map_or(Value::None, |&byte| Value::Unsigned(byte.into()))
+                        }
+                        (Value::List(l), Value::Unsigned(idx)) => l.get(idx as usize).cloned().unwrap_or(Value::None),
+                        (v, _) => unreachable!("wrong Value type of {:?} for function at", v),
                     },
-                    (Int(_), Value::Signed(i)) => match to {
-                        UInt(_) => Value::Unsigned(i as u64),
-                        Int(_) => Value::Signed(i),
-                        Float(_) => Value::new_float(i as f64),
-                        _ => unreachable!(),
+                    "get" => {
+                        let snd = self.eval_expr(&args[1], ts)?;
+                        match fst {
+                            Value::Map(map) => map.get(&snd).cloned().unwrap_or(Value::None),
+                            v => unreachable!("wrong Value type of {:?} for function get", v),
+                        }
+                    }
+                    "len" => match fst {
+                        Value::Set(set) => Value::Unsigned(set.len() as u64),
+                        Value::List(list) => Value::Unsigned(list.len() as u64),
+                        Value::Map(map) => Value::Unsigned(map.len() as u64),
+                        Value::Str(_) => Value::string_function(name, &[fst]),
+                        Value::Bytes(_) => Value::bytes_function(name, &[fst]),
+                        v => unreachable!("wrong Value type of {:?} for function len", v),
                     },
-                    (Float(_), Value::Float(f)) => match to {
-                        UInt(_) => Value::Unsigned(f.into_inner() as u64),
-                        Int(_) => Value::Signed(f.into_inner() as i64),
-                        Float(_) => Value::new_float(f.into_inner()),
-                        _ => unreachable!(),
+                    "concat" | "substring" | "starts_with" | "ends_with" | "to_lower" | "to_upper" | "split"
+                    | "parse_int" | "parse_uint" | "parse_float" | "slice" | "to_hex" | "find" => {
+                        let rest =
+                            args[1..].iter().map(|arg| self.eval_expr(arg, ts)).collect::<Result<Vec<_>, _>>()?;
+                        if let Value::Bytes(_) = fst {
+                            Value::bytes_function(name, &[vec![fst], rest].concat())
+                        } else {
+                            Value::string_function(name, &[vec![fst], rest].concat())
+                        }
+                    }
+                    read if read.starts_with("read_") => {
+                        let index = self.eval_expr(&args[1], ts)?;
+                        Value::bytes_function(name, &[fst, index])
+                    }
+                    "seconds" => Value::new_float(fst.get_seconds()),
+                    "is_some" => Value::Bool(fst != Value::None),
+                    "is_none" => Value::Bool(fst == Value::None),
+                    "or" | "unwrap_or" => match fst {
+                        Value::None => self.eval_expr(&args[1], ts)?,
+                        v => v,
                     },
-                    (from, v) => panic!("Value type of {:?} does not match convert from type {:?}", v, from),
+                    _ => unreachable!("Unknown function: {}, args: {:?}", name, args),
                 }
             }
 
+            Tuple(entries) => Value::Tuple(entries.iter().map(|e| self.eval_expr(e, ts)).collect::<Result<_, _>>()?),
+
+            Convert { to, expr, .. } => self.eval_expr(expr, ts)?.convert(to, self.overflow)?,
+
             Default { expr, default, .. } => {
-                let v = self.eval_expr(expr, ts);
+                let v = self.eval_expr(expr, ts)?;
                 if let Value::None = v {
-                    self.eval_expr(default, ts)
+                    self.eval_expr(default, ts)?
                 } else {
                     v
                 }
             }
 
             TupleAccess(expr, num) => {
-                if let Value::Tuple(entries) = self.eval_expr(expr, ts) {
+                if let Value::Tuple(entries) = self.eval_expr(expr, ts)? {
                     entries[*num].clone()
                 } else {
                     unreachable!("verified by type checker")
                 }
             }
-        }
+
+            Let { variable, value, body } => {
+                bind_local(&self.locals, *variable, self.eval_expr(value, ts)?);
+                self.eval_expr(body, ts)?
+            }
+            LocalVariable(variable) => self.locals.borrow()[*variable].clone(),
+        };
+        Ok(res)
+    }
+
+    /// Evaluates a boolean expression quantitatively: comparisons yield the signed distance of their operands, and
+    /// conjunctions and disjunctions yield the minimum and maximum of the robustness of their operands.
+    fn eval_robustness(&self, expr: &Expression, ts: Time) -> Result<f64, RuntimeError> {
+        use rtlola_frontend::ir::ExpressionKind::ArithLog;
+        use ArithLogOp::*;
+        Ok(match &expr.kind {
+            ArithLog(Not, operands, _) => -self.eval_robustness(&operands[0], ts)?,
+            ArithLog(And, operands, _) => {
+                self.eval_robustness(&operands[0], ts)?.min(self.eval_robustness(&operands[1], ts)?)
+            }
+            ArithLog(Or, operands, _) => {
+                self.eval_robustness(&operands[0], ts)?.max(self.eval_robustness(&operands[1], ts)?)
+            }
+            ArithLog(op @ (Eq | Lt | Le | Ne | Ge | Gt), operands, _) => {
+                let lhs = self.eval_expr(&operands[0], ts)?;
+                let rhs = self.eval_expr(&operands[1], ts)?;
+                comparison_robustness(*op, &lhs, &rhs)
+            }
+            _ => boolean_robustness(self.eval_expr(expr, ts)?.get_bool()),
+        })
     }
 
     fn lookup_latest(&self, stream_ref: StreamReference) -> Value {
@@ -558,18 +992,21 @@ impl<'a> ExpressionEvaluator<'a> {
         inst.get_value(0).unwrap_or(Value::None)
     }
 
-    fn lookup_latest_check(&self, stream_ref: StreamReference) -> Value {
+    fn lookup_latest_check(&self, stream_ref: StreamReference) -> Result<Value, RuntimeError> {
         let inst = match stream_ref {
             StreamReference::InRef(ix) => {
                 debug_assert!(self.fresh_inputs.contains(ix), "ix={}", ix);
                 self.global_store.get_in_instance(ix)
             }
             StreamReference::OutRef(ix) => {
-                debug_assert!(self.fresh_outputs.contains(ix), "ix={}", ix);
+                // The stream failed to produce a value due to a runtime error.
+                if !self.fresh_outputs.contains(ix) {
+                    return Err(RuntimeError::MissingValue);
+                }
                 self.global_store.get_out_instance(ix).expect("no out instance")
             }
         };
-        inst.get_value(0).unwrap_or(Value::None)
+        Ok(inst.get_value(0).unwrap_or(Value::None))
     }
 
     fn lookup_with_offset(&self, stream_ref: StreamReference, offset: i16) -> Value {
@@ -600,18 +1037,21 @@ impl<'e> EvaluationContext<'e> {
         inst.get_value(0).unwrap_or(Value::None)
     }
 
-    pub(crate) fn lookup_latest_check(&self, stream_ref: StreamReference) -> Value {
+    pub(crate) fn lookup_latest_check(&self, stream_ref: StreamReference) -> Result<Value, RuntimeError> {
         let inst = match stream_ref {
             StreamReference::InRef(ix) => {
                 debug_assert!(self.fresh_inputs.contains(ix), "ix={}", ix);
                 self.global_store.get_in_instance(ix)
             }
             StreamReference::OutRef(ix) => {
-                debug_assert!(self.fresh_outputs.contains(ix), "ix={}", ix);
+                // The stream failed to produce a value due to a runtime error.
+                if !self.fresh_outputs.contains(ix) {
+                    return Err(RuntimeError::MissingValue);
+                }
                 self.global_store.get_out_instance(ix).expect("no out instance")
             }
         };
-        inst.get_value(0).unwrap_or(Value::None)
+        Ok(inst.get_value(0).unwrap_or(Value::None))
     }
 
     pub(crate) fn lookup_with_offset(&self, stream_ref: StreamReference, offset: i16) -> Value {
@@ -633,6 +1073,56 @@ impl<'e> EvaluationContext<'e> {
     }
 }
 
+/// Binds a value to a local variable, which is read by the body of the binding `Let` expression.
+pub(crate) fn bind_local(locals: &RefCell<Vec<Value>>, variable: usize, value: Value) {
+    let mut locals = locals.borrow_mut();
+    if locals.len() <= variable {
+        locals.resize(variable + 1, Value::None);
+    }
+    locals[variable] = value;
+}
+
+/// The robustness of a comparison is the signed distance of its numeric operands.
+/// Comparisons of other values are either satisfied or violated without any margin.
+pub(crate) fn comparison_robustness(op: ArithLogOp, lhs: &Value, rhs: &Value) -> f64 {
+    use ArithLogOp::*;
+    match (lhs.get_number(), rhs.get_number()) {
+        (Some(lhs), Some(rhs)) => {
+            let robustness = match op {
+                Gt | Ge => lhs - rhs,
+                Lt | Le => rhs - lhs,
+                Eq => -(lhs - rhs).abs(),
+                Ne => (lhs - rhs).abs(),
+                _ => unreachable!("{:?} is no comparison", op),
+            };
+            // Equal infinite operands have no defined distance, they are treated like equal finite operands.
+            if robustness.is_nan() {
+                0.0
+            } else {
+                robustness
+            }
+        }
+        _ => boolean_robustness(match op {
+            Gt => lhs > rhs,
+            Ge => lhs >= rhs,
+            Lt => lhs < rhs,
+            Le => lhs <= rhs,
+            Eq => lhs == rhs,
+            Ne => lhs != rhs,
+            _ => unreachable!("{:?} is no comparison", op),
+        }),
+    }
+}
+
+/// Boolean values without a quantitative interpretation are infinitely robust.
+pub(crate) fn boolean_robustness(value: bool) -> f64 {
+    if value {
+        f64::INFINITY
+    } else {
+        f64::NEG_INFINITY
+    }
+}
+
 impl ActivationCondition {
     fn new(ac: &Activation<StreamReference>, n_inputs: usize) -> Self {
         use ActivationCondition::*;
@@ -696,7 +1186,7 @@ mod tests {
         let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
         let mut config = EvalConfig::default();
         config.verbosity = crate::basics::Verbosity::WarningsOnly;
-        let handler = Arc::new(OutputHandler::new(&config, ir.triggers.len()));
+        let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
         let now = Instant::now();
         let eval = EvaluatorData::new(ir.clone(), config, handler, now);
         (ir, eval, now)
@@ -758,7 +1248,7 @@ mod tests {
         peek_assert_eq!(eval, start, 0, Bool(true));
         peek_assert_eq!(eval, start, 1, Unsigned(3));
         peek_assert_eq!(eval, start, 2, Signed(-5));
-        peek_assert_eq!(eval, start, 3, Value::new_float(-123.456));
+        peek_assert_eq!(eval, start, 3, Value::new_float(-123.456f32 as f64));
         peek_assert_eq!(eval, start, 4, Str("foobar".into()));
     }
 
@@ -773,7 +1263,7 @@ mod tests {
         output o_4:  UInt8 := 8 * 3
         output o_5:  UInt8 := 8 / 3
         output o_6:  UInt8 := 8 % 3
-        output o_7:  UInt8 := 8 ** 3
+        output o_7: UInt16 := 8 ** 3
         output o_8:   Bool := false || false
         output o_9:   Bool := false || true
         output o_10:  Bool := true  || false
@@ -1133,4 +1623,388 @@ mod tests {
         eval_stream!(eval, start, 0);
         assert_eq!(eval.peek_value(out_ref, &Vec::new(), 0).unwrap(), expected);
     }
+
+    #[test]
+    fn test_set_accumulation() {
+        let (_, eval, start) = setup(
+            "input a: UInt8\noutput s: Set<UInt8> := s.offset(by: -1).defaults(to: Set()).insert(a)\noutput n := s.len()\noutput c := s.contains(3)",
+        );
+        let mut eval = eval.into_evaluator();
+        for v in &[Unsigned(1), Unsigned(2), Unsigned(1)] {
+            eval.eval_event(&[v.clone()], start.elapsed());
+        }
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Unsigned(2));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Bool(false));
+        eval.eval_event(&[Unsigned(3)], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Unsigned(3));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Bool(true));
+    }
+
+    #[test]
+    fn test_map_lookup() {
+        let (_, eval, start) = setup(
+            "input k: UInt8\ninput v: Int16\noutput m: Map<UInt8, Int16> := m.offset(by: -1).defaults(to: Map()).insert(key: k, value: v)\noutput x := m.get(key: 1).defaults(to: -1)\noutput n := m.len()",
+        );
+        let mut eval = eval.into_evaluator();
+        eval.eval_event(&[Unsigned(2), Signed(20)], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Signed(-1));
+        eval.eval_event(&[Unsigned(1), Signed(10)], start.elapsed());
+        eval.eval_event(&[Unsigned(1), Signed(30)], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Signed(30));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Unsigned(2));
+    }
+
+    #[test]
+    fn test_named_tuple_destructuring() {
+        let (_, eval, start) = setup(
+            "input lat: Float64\ninput lon: Float64\noutput pos: (lat: Float64, lon: Float64) := (lat, lon)\noutput d := let (x, y) = pos in x - y\noutput l := pos.lon",
+        );
+        let mut eval = eval.into_evaluator();
+        eval.eval_event(&[Value::new_float(3.0), Value::new_float(1.0)], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Value::new_float(2.0));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Value::new_float(1.0));
+    }
+
+    #[test]
+    fn test_template_instantiation() {
+        let (_, eval, start) = setup(
+            "input rpm_1: Int64\ninput rpm_2: Int64\ntemplate motor_check(rpm) {\n  output high := rpm > 5000\n}\ninstantiate motor_check(rpm_1) as motor_1\ninstantiate motor_check(rpm_2) as motor_2",
+        );
+        let mut eval = eval.into_evaluator();
+        eval.eval_event(&[Signed(6000), Signed(4000)], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Bool(true));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Bool(false));
+    }
+
+    #[test]
+    fn test_past_time_operators() {
+        let (_, eval, start) = setup(
+            "input a: Bool\ninput b: Bool\noutput o := once(a)\noutput h := historically(b)\noutput s := a since b",
+        );
+        let mut eval = eval.into_evaluator();
+        let mut check = |a: bool, b: bool, expected: [bool; 3]| {
+            eval.eval_event(&[Bool(a), Bool(b)], start.elapsed());
+            for (ix, &value) in expected.iter().enumerate() {
+                assert_eq!(eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap(), Bool(value));
+            }
+        };
+        check(false, true, [false, true, true]);
+        check(true, true, [true, true, true]);
+        check(true, false, [true, false, true]);
+        check(false, false, [true, false, false]);
+        check(true, false, [true, false, false]);
+    }
+
+    #[test]
+    fn test_timed_past_time_operators() {
+        let (_, eval, mut time) = setup_time("input a: Int64\noutput o @1Hz := once(a > 5, within: 2s)");
+        let mut eval = eval.into_evaluator();
+        let out_ref = StreamReference::OutRef(0);
+        time += Duration::from_secs(1);
+        eval.eval_event(&[Signed(7)], time);
+        eval.eval_time_driven_outputs(&[0], time);
+        assert_eq!(eval.peek_value(out_ref, &Vec::new(), 0).unwrap(), Bool(true));
+        time += Duration::from_secs(1);
+        eval.eval_event(&[Signed(1)], time);
+        eval.eval_time_driven_outputs(&[0], time);
+        assert_eq!(eval.peek_value(out_ref, &Vec::new(), 0).unwrap(), Bool(true));
+        time += Duration::from_secs(2);
+        eval.eval_time_driven_outputs(&[0], time);
+        assert_eq!(eval.peek_value(out_ref, &Vec::new(), 0).unwrap(), Bool(false));
+    }
+
+    #[test]
+    fn test_now() {
+        let (_, eval, _) = setup(
+            "input a: Int64\noutput t := now()\noutput dt := (t - t.offset(by: -1).defaults(to: t)).seconds()\noutput slow := t - t.offset(by: -1).defaults(to: t) > 1500ms",
+        );
+        let mut eval = eval.into_evaluator();
+        eval.eval_event(&[Signed(1)], Duration::from_millis(500));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Value::Timestamp(500_000_000));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Value::new_float(0.0));
+        eval.eval_event(&[Signed(2)], Duration::from_millis(1500));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Value::new_float(1.0));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Bool(false));
+        eval.eval_event(&[Signed(3)], Duration::from_millis(3500));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Value::new_float(2.0));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Bool(true));
+    }
+
+    #[test]
+    fn test_optional_arithmetic() {
+        let (_, eval, start) = setup(
+            "input a: Int64\ninput b: Int64\noutput c @ a := (a.get() + b.get()).defaults(to: -1)\noutput d @ a := a.get().or(b.get()).is_some()",
+        );
+        let mut eval = eval.into_evaluator();
+        eval.eval_event(&[Signed(1), Signed(2)], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Signed(3));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Bool(true));
+        eval.eval_event(&[Signed(4), Value::None], start.elapsed());
+        assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Signed(-1));
+        assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Bool(true));
+    }
+
+    #[test]
+    fn test_trigger_robustness() {
+        let spec = "#![robustness]\ninput velo: Float64\ninput mode: String\ntrigger velo > 7.0 ∧ !(velo ≥ 10.0) ∨ mode == \"off\"";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            assert!(ir.triggers[0].robustness);
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::WarningsOnly;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            let mut check = |velo: f64, mode: &str, expected: f64| {
+                eval.eval_event(&[Value::new_float(velo), Str(mode.into())], Time::default());
+                assert_eq!(eval.eval_robustness(0, Time::default()), Ok(expected));
+            };
+            check(8.5, "on", 1.5);
+            check(9.5, "on", 0.5);
+            check(6.0, "on", -1.0);
+            check(12.0, "on", -2.0);
+            check(12.0, "off", f64::INFINITY);
+        }
+    }
+
+    #[test]
+    fn test_input_assumption() {
+        let spec =
+            "input alt: Float64 assume alt >= 0.0 && alt < 10000.0\ninput b: Bool\noutput o := alt\noutput c := b";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            eval.eval_event(&[Value::new_float(120.5), Bool(true)], Time::default());
+            assert_eq!(eval.peek_value(StreamReference::InRef(0), &Vec::new(), 0).unwrap(), Value::new_float(120.5));
+            eval.eval_event(&[Value::new_float(-3.5), Bool(false)], Time::default());
+            assert_eq!(eval.peek_value(StreamReference::InRef(0), &Vec::new(), 0).unwrap(), Value::new_float(120.5));
+            assert!(!eval.fresh_inputs.contains(0));
+            assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Bool(false));
+            eval.eval_event(&[Value::new_float(20.0), Value::None], Time::default());
+            assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Value::new_float(20.0));
+        }
+    }
+
+    #[test]
+    fn test_geofence() {
+        let spec = "import geo\ninput lat: Float64\ninput lon: Float64\noutput inside := within(lat, lon, polygon: \"49.20 7.10, 49.20 7.12, 49.22 7.12, 49.22 7.10\")";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            for &(lat, lon, expected) in &[(49.21, 7.11, true), (49.23, 7.11, false), (49.21, 7.115, true)] {
+                eval.eval_event(&[Value::new_float(lat), Value::new_float(lon)], Time::default());
+                assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Bool(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tuple_destructuring() {
+        let spec =
+            "input a: Int64\ninput b: Int64\noutput d := let (x, y) = (a + b, a - b) in let (u, v) = (y, x) in u * v";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            eval.eval_event(&[Signed(5), Signed(3)], Time::default());
+            assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Signed(16));
+        }
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        use crate::basics::OverflowMode::*;
+        let spec = "input a: Int8\ninput b: UInt8\noutput s := a + 100\noutput n := -a\noutput d := b * 2";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            for &(overflow, expected) in &[(Wrapping, [-56, -128, 144]), (Saturating, [127, 127, 255])] {
+                let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+                let mut config = EvalConfig::default();
+                config.verbosity = crate::basics::Verbosity::Silent;
+                config.evaluator = evaluator;
+                config.overflow = overflow;
+                let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+                let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+                eval.eval_event(&[Signed(100), Unsigned(200)], Time::default());
+                let peek = |ix| eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap();
+                assert_eq!(peek(0), Signed(expected[0]));
+                assert_eq!(peek(2), Unsigned(expected[2] as u64));
+                eval.eval_event(&[Signed(-128), Value::None], Time::default());
+                let peek = |ix| eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap();
+                assert_eq!(peek(1), Signed(expected[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_math_functions() {
+        let spec = "import math\ninput x: Float64\ninput y: Float64\ninput i: Int64\noutput a := atan2(y, x)\noutput b := x.hypot(y)\noutput c := ln(exp(x))\noutput d := pow(x, y)\noutput e := (y / x).round()\noutput f := floor(y / x) + ceil(y / x)\noutput g := clamp(i, -5, 5)\noutput h := asin(x / y)";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.statistics = crate::basics::Statistics::Debug;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler.clone(), Instant::now()).into_evaluator();
+            eval.eval_event(&[Value::new_float(3.0), Value::new_float(4.0), Signed(-7)], Time::default());
+            let peek = |ix: usize| eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap();
+            assert_eq!(peek(0), Value::new_float(4f64.atan2(3.0)));
+            assert_eq!(peek(1), Value::new_float(5.0));
+            assert_eq!(peek(2), Value::new_float(3f64.exp().ln()));
+            assert_eq!(peek(3), Value::new_float(81.0));
+            assert_eq!(peek(4), Value::new_float(1.0));
+            assert_eq!(peek(5), Value::new_float(3.0));
+            assert_eq!(peek(6), Signed(-5));
+            assert_eq!(peek(7), Value::new_float(0.75f64.asin()));
+            // asin is undefined outside of [-1, 1], so `h` keeps its previous value
+            eval.eval_event(&[Value::new_float(4.0), Value::new_float(3.0), Signed(2)], Time::default());
+            assert_eq!(
+                eval.peek_value(StreamReference::OutRef(7), &Vec::new(), 0).unwrap(),
+                Value::new_float(0.75f64.asin())
+            );
+            assert_eq!(eval.peek_value(StreamReference::OutRef(6), &Vec::new(), 0).unwrap(), Signed(2));
+            assert_eq!(handler.statistics.as_ref().unwrap().get_num_runtime_errors(), 1);
+        }
+    }
+
+    #[test]
+    fn test_string_functions() {
+        let spec = "import string\ninput s: String\noutput a := s.len()\noutput b := s.split(\",\").at(1)\noutput c := s.split(\",\").at(1).defaults(to: \"\").parse_uint()\noutput d := s.substring(from: 1, to: 3)\noutput e := concat(s.to_upper(), \"!\")\noutput f := s.starts_with(\"gé\") && s.contains(\",4\") && !s.ends_with(\",\")\noutput g := s.substring(from: 3, to: 20)\noutput h := parse_float(s)\noutput i := s.split(\",\")\noutput j := s.split(\",\").len()\noutput k := s.split(\",\").at(2)";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            eval.eval_event(&[Str("gét,42".into())], Time::default());
+            let peek = |ix: usize| eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap();
+            assert_eq!(peek(0), Unsigned(6));
+            assert_eq!(peek(1), Str("42".into()));
+            assert_eq!(peek(2), Unsigned(42));
+            assert_eq!(peek(3), Str("ét".into()));
+            assert_eq!(peek(4), Str("GÉT,42!".into()));
+            assert_eq!(peek(5), Bool(true));
+            assert_eq!(peek(6), Value::None);
+            assert_eq!(peek(7), Value::None);
+            assert_eq!(peek(8), List(vec![Str("gét".into()), Str("42".into())].into()));
+            assert_eq!(peek(9), Unsigned(2));
+            assert_eq!(peek(10), Value::None);
+        }
+    }
+
+    #[test]
+    fn test_regex_captures() {
+        let spec = "import regex\ninput s: String\ninput b: Bytes\noutput u := s.capture(regex: \"USER (\\w+)\", group: 1)\noutput v := captures(s, regex: \"(\\d+)\\.(\\d+)?\")\noutput w := b.capture(regex: \"x(y+)\", group: 0)";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            eval.eval_event(&[Str("USER admin 10.".into()), Bytes(b"axyyz".to_vec().into())], Time::default());
+            let peek = |ix: usize| eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap();
+            assert_eq!(peek(0), Str("admin".into()));
+            assert_eq!(peek(1), Tuple(vec![Str("10".into()), Value::None].into()));
+            assert_eq!(peek(2), Bytes(b"xyy".to_vec().into()));
+            eval.eval_event(&[Str("PASS".into()), Bytes(b"".to_vec().into())], Time::default());
+            let peek = |ix: usize| eval.peek_value(StreamReference::OutRef(ix), &Vec::new(), 0).unwrap();
+            assert_eq!(peek(0), Value::None);
+            assert_eq!(peek(1), Tuple(vec![Value::None, Value::None].into()));
+            assert_eq!(peek(2), Value::None);
+        }
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let spec = "import math\ninput a: UInt8\ninput b: UInt8\ninput x: Float64\noutput q := a / b\noutput p := a * b\noutput r := sqrt(x)";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::Silent;
+            config.statistics = crate::basics::Statistics::Debug;
+            config.evaluator = evaluator;
+            config.overflow = crate::basics::OverflowMode::Trapping;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler.clone(), Instant::now()).into_evaluator();
+            eval.eval_event(&[Unsigned(200), Unsigned(0), Value::new_float(-1.0)], Time::default());
+            assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0), Option::None);
+            assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Unsigned(0));
+            assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0), Option::None);
+            eval.eval_event(&[Unsigned(200), Unsigned(2), Value::new_float(4.0)], Time::default());
+            assert_eq!(eval.peek_value(StreamReference::OutRef(0), &Vec::new(), 0).unwrap(), Unsigned(100));
+            assert_eq!(eval.peek_value(StreamReference::OutRef(1), &Vec::new(), 0).unwrap(), Unsigned(0));
+            assert_eq!(eval.peek_value(StreamReference::OutRef(2), &Vec::new(), 0).unwrap(), Value::new_float(2.0));
+            assert_eq!(handler.statistics.as_ref().unwrap().get_num_runtime_errors(), 3);
+            assert!(handler.runtime_failure().is_none());
+        }
+    }
+
+    #[test]
+    fn test_runtime_error_abort() {
+        let ir = parse("input a: Int64\ninput b: Int64\noutput o := a % b")
+            .unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+        let mut config = EvalConfig::default();
+        config.verbosity = crate::basics::Verbosity::Silent;
+        config.runtime_errors = crate::basics::RuntimeErrorPolicy::Abort;
+        let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+        let mut eval = EvaluatorData::new(ir, config, handler.clone(), Instant::now()).into_evaluator();
+        eval.eval_event(&[Signed(3), Signed(2)], Time::default());
+        assert!(handler.runtime_failure().is_none());
+        eval.eval_event(&[Signed(3), Signed(0)], Time::from_secs(1));
+        let failure = handler.runtime_failure().expect("division by zero should abort");
+        assert_eq!(failure.stream, "o");
+        assert_eq!(failure.time, Time::from_secs(1));
+        assert_eq!(failure.message, "division by zero");
+    }
+
+    #[test]
+    fn test_trigger_message_placeholders() {
+        let spec = "input gps: UInt8\ninput alt: Float64\ntrigger gps < 2 \"only {gps} satellites, alt={alt.hold().defaults(to: 0.0)}\"";
+        for &evaluator in &[ClosureBased, Interpreted] {
+            let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+            let trigger = ir.triggers[0].clone();
+            let mut config = EvalConfig::default();
+            config.verbosity = crate::basics::Verbosity::WarningsOnly;
+            config.evaluator = evaluator;
+            let handler = Arc::new(OutputHandler::new(&config, &ir.triggers));
+            let mut eval = EvaluatorData::new(ir, config, handler, Instant::now()).into_evaluator();
+            eval.eval_event(&[Value::None, Value::new_float(120.5)], Time::default());
+            eval.eval_event(&[Unsigned(1), Value::None], Time::default());
+            let ix = trigger.reference.out_ix();
+            assert_eq!(eval.trigger_message(ix, &trigger, Time::default()), "only 1 satellites, alt=120.5");
+        }
+    }
+
+    #[test]
+    fn test_trigger_options() {
+        let accept_all = |options: TriggerOptions, events: &[(bool, u64)]| {
+            let mut state = TriggerState::default();
+            events
+                .iter()
+                .map(|&(condition, ms)| state.accept(&options, condition, Duration::from_millis(ms)))
+                .collect::<Vec<bool>>()
+        };
+        let rising = TriggerOptions { rising_edge: true, ..TriggerOptions::default() };
+        let events = [(true, 0), (true, 1000), (false, 2000), (true, 3000)];
+        assert_eq!(accept_all(rising, &events), vec![true, false, false, true]);
+        let rearm = TriggerOptions { rearm: Some(Duration::from_secs(2)), ..TriggerOptions::default() };
+        let events = [(true, 0), (false, 1000), (true, 2000), (false, 3000), (false, 4000), (true, 5000)];
+        assert_eq!(accept_all(rearm, &events), vec![true, false, false, false, false, true]);
+        let limit = TriggerOptions { limit: Some((2, Duration::from_secs(10))), ..TriggerOptions::default() };
+        let events = [(true, 0), (true, 1000), (true, 2000), (true, 10000), (true, 10500), (true, 11000)];
+        assert_eq!(accept_all(limit, &events), vec![true, true, false, true, false, true]);
+    }
 }
@@ -0,0 +1,160 @@
+//! Geospatial computations of the `geo` module on WGS84 coordinates given in degrees
+
+use crate::storage::{RuntimeError, Value};
+
+/// The mean radius of the earth in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+/// The semi-major axis of the WGS84 ellipsoid in meters
+const WGS84_A: f64 = 6_378_137.0;
+/// The squared first eccentricity of the WGS84 ellipsoid
+const WGS84_E2: f64 = 6.694_379_990_14e-3;
+
+/// Returns the great-circle distance between two positions in meters using the haversine formula.
+pub(crate) fn distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    // rounding errors can push `a` slightly out of `[0, 1]` for antipodal positions
+    let a = ((d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2)).clamp(0.0, 1.0);
+    2.0 * EARTH_RADIUS * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Returns the initial bearing from the first to the second position in degrees clockwise from north, in `[0, 360)`.
+pub(crate) fn bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lambda = (lon2 - lon1).to_radians();
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// Converts a position with altitude in meters into earth-centered, earth-fixed coordinates.
+fn ecef(lat: f64, lon: f64, alt: f64) -> (f64, f64, f64) {
+    let (phi, lambda) = (lat.to_radians(), lon.to_radians());
+    let n = WGS84_A / (1.0 - WGS84_E2 * phi.sin().powi(2)).sqrt();
+    (
+        (n + alt) * phi.cos() * lambda.cos(),
+        (n + alt) * phi.cos() * lambda.sin(),
+        (n * (1.0 - WGS84_E2) + alt) * phi.sin(),
+    )
+}
+
+/// Returns the east, north, and up coordinates in meters of a position relative to the reference position.
+pub(crate) fn enu(lat: f64, lon: f64, alt: f64, ref_lat: f64, ref_lon: f64, ref_alt: f64) -> (f64, f64, f64) {
+    let (x, y, z) = ecef(lat, lon, alt);
+    let (ref_x, ref_y, ref_z) = ecef(ref_lat, ref_lon, ref_alt);
+    let (dx, dy, dz) = (x - ref_x, y - ref_y, z - ref_z);
+    let (phi, lambda) = (ref_lat.to_radians(), ref_lon.to_radians());
+    let east = -lambda.sin() * dx + lambda.cos() * dy;
+    let north = -phi.sin() * lambda.cos() * dx - phi.sin() * lambda.sin() * dy + phi.cos() * dz;
+    let up = phi.cos() * lambda.cos() * dx + phi.cos() * lambda.sin() * dy + phi.sin() * dz;
+    (east, north, up)
+}
+
+/// Applies one of `distance`, `bearing`, and `enu` to its arguments in degrees and meters.
+/// Fails if the result is not a number, e.g., for infinite coordinates.
+pub(crate) fn geo_function(name: &str, args: &[Value]) -> Result<Value, RuntimeError> {
+    let args: Vec<f64> = args.iter().map(coordinate).collect();
+    match name {
+        "distance" => Value::try_float(distance(args[0], args[1], args[2], args[3])),
+        "bearing" => Value::try_float(bearing(args[0], args[1], args[2], args[3])),
+        "enu" => {
+            let (east, north, up) = enu(args[0], args[1], args[2], args[3], args[4], args[5]);
+            Ok(Value::Tuple(vec![Value::try_float(east)?, Value::try_float(north)?, Value::try_float(up)?].into()))
+        }
+        _ => unreachable!("unknown function {} of the geo module", name),
+    }
+}
+
+/// Returns the number of a coordinate in degrees or meters, which is a `Float64` due to the type checker.
+pub(crate) fn coordinate(arg: &Value) -> f64 {
+    arg.get_number().unwrap_or_else(|| unreachable!("expected `Float64`, found {:?}", arg))
+}
+
+/// A geofence given by the latitudes and longitudes of its vertices
+#[derive(Debug, Clone)]
+pub(crate) struct Polygon {
+    vertices: Vec<(f64, f64)>,
+}
+
+impl Polygon {
+    /// Parses a polygon of the form `"lat lon, lat lon, lat lon, ..."`, which is validated by the frontend.
+    pub(crate) fn parse(source: &str) -> Option<Polygon> {
+        let vertices = source
+            .split(',')
+            .map(|vertex| {
+                let mut coordinates = vertex.split_whitespace().map(str::parse::<f64>);
+                match (coordinates.next(), coordinates.next(), coordinates.next()) {
+                    (Some(Ok(lat)), Some(Ok(lon)), None) => Some((lat, lon)),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if vertices.len() < 3 {
+            return None;
+        }
+        Some(Polygon { vertices })
+    }
+
+    /// Checks whether the position lies within the polygon by casting a ray along the latitude of the position.
+    pub(crate) fn contains(&self, lat: f64, lon: f64) -> bool {
+        let mut inside = false;
+        let mut previous = self.vertices[self.vertices.len() - 1];
+        for &current in &self.vertices {
+            let ((lat1, lon1), (lat2, lon2)) = (previous, current);
+            if (lat1 > lat) != (lat2 > lat) && lon < lon1 + (lat - lat1) / (lat2 - lat1) * (lon2 - lon1) {
+                inside = !inside;
+            }
+            previous = current;
+        }
+        inside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_and_bearing() {
+        // one degree along the equator
+        assert!((distance(0.0, 0.0, 0.0, 1.0) - 111_195.08).abs() < 0.01);
+        assert!((distance(49.2578, 7.0453, 49.2578, 7.0453)).abs() < 1e-9);
+        assert!((bearing(0.0, 0.0, 0.0, 1.0) - 90.0).abs() < 1e-9);
+        assert!((bearing(0.0, 0.0, -1.0, 0.0) - 180.0).abs() < 1e-9);
+        assert!((bearing(0.0, 0.0, 0.0, -1.0) - 270.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn not_a_number() {
+        let position = [Value::new_float(f64::INFINITY), Value::new_float(0.0)];
+        let args = [&position[..], &position[..]].concat();
+        assert_eq!(geo_function("distance", &args), Err(RuntimeError::NotANumber));
+        assert_eq!(geo_function("bearing", &args), Err(RuntimeError::NotANumber));
+        // antipodal positions
+        assert!((distance(0.0, 0.0, 0.0, 180.0) - std::f64::consts::PI * EARTH_RADIUS).abs() < 1e-6);
+    }
+
+    #[test]
+    fn local_coordinates() {
+        let (east, north, up) = enu(49.2578, 7.0453, 100.0, 49.2578, 7.0453, 0.0);
+        assert!(east.abs() < 1e-6 && north.abs() < 1e-6 && (up - 100.0).abs() < 1e-6);
+        let (east, north, _) = enu(49.2578, 7.0463, 0.0, 49.2578, 7.0453, 0.0);
+        assert!((east - 72.7).abs() < 0.1 && north.abs() < 0.01);
+        let (east, north, _) = enu(49.2588, 7.0453, 0.0, 49.2578, 7.0453, 0.0);
+        assert!(east.abs() < 1e-6 && (north - 111.2).abs() < 0.1);
+    }
+
+    #[test]
+    fn geofence() {
+        let fence = Polygon::parse("49.0 7.0, 49.0 8.0, 50.0 8.0, 50.0 7.0").unwrap();
+        assert!(fence.contains(49.5, 7.5));
+        assert!(!fence.contains(48.5, 7.5));
+        assert!(!fence.contains(49.5, 8.5));
+        let triangle = Polygon::parse("0 0,0 2,2 0").unwrap();
+        assert!(triangle.contains(0.5, 0.5));
+        assert!(!triangle.contains(1.5, 1.5));
+        assert!(Polygon::parse("0 0, 1 1").is_none());
+        assert!(Polygon::parse("0 0, 1, 1 1").is_none());
+    }
+}
@@ -15,6 +15,7 @@ mod basics;
 mod closuregen;
 mod coordination;
 mod evaluator;
+mod geo;
 mod storage;
 #[cfg(test)]
 mod tests;
@@ -27,11 +28,14 @@ use basics::{
 };
 use clap::{App, AppSettings, Arg, ArgGroup, SubCommand};
 use rtlola_frontend;
-use rtlola_frontend::ir::RTLolaIR;
+use rtlola_frontend::ir::{RTLolaIR, TriggerSeverity};
 use rtlola_frontend::{FrontendConfig, TypeConfig};
 use std::fs;
 use std::sync::Arc;
 
+pub use crate::basics::{
+    OverflowMode, RuntimeErrorPolicy, RuntimeFailure, TriggerFailure, TriggerPolicy, TriggerSelection,
+};
 pub use crate::coordination::Monitor;
 pub use crate::storage::Value;
 
@@ -50,7 +54,7 @@ pub struct Config {
 }
 
 const CONFIG: FrontendConfig =
-    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+    FrontendConfig { ty: TypeConfig { use_64bit_only: false, type_aliases: false }, allow_parameters: false };
 
 impl Config {
     // TODO find appropriate name
@@ -130,6 +134,46 @@ impl Config {
                     .possible_values(&["debug", "outputs", "triggers", "warnings", "progress", "silent", "quiet"])
                     .default_value("triggers")
             )
+            .arg(
+                Arg::with_name("MIN_SEVERITY")
+                    .help("Sets the minimum severity of printed triggers\n")
+                    .long("min-severity")
+                    .possible_values(&["info", "warning", "critical"])
+                    .default_value("info")
+            )
+            .arg(
+                Arg::with_name("FAIL_ON_TRIGGER")
+                    .help("Exits with a non-zero code if any trigger, or the trigger with the given name, was raised\n")
+                    .long("fail-on-trigger")
+                    .value_name("NAME")
+                    .takes_value(true)
+                    .min_values(0)
+                    .require_equals(true)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("TRIGGER_ACTION")
+                    .help("Runs a command whenever any trigger, or the trigger with the given name, is raised\nThe command receives the details of the trigger in the environment variables\nRTLOLA_TRIGGER_NAME, RTLOLA_TRIGGER_INDEX, RTLOLA_TRIGGER_SEVERITY, RTLOLA_TRIGGER_MESSAGE, and RTLOLA_TRIGGER_TIME.\n")
+                    .long("trigger-action")
+                    .value_name("[NAME=]COMMAND")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("OVERFLOW")
+                    .help("Sets how integer arithmetic handles results exceeding the declared type\n")
+                    .long("overflow")
+                    .possible_values(&["wrapping", "saturating", "trapping"])
+                    .default_value("wrapping")
+            )
+            .arg(
+                Arg::with_name("ON_RUNTIME_ERROR")
+                    .help("Sets whether the monitor continues or aborts when a stream fails to evaluate, e.g., due to a division by zero\n")
+                    .long("on-runtime-error")
+                    .possible_values(&["continue", "abort"])
+                    .default_value("continue")
+            )
             .arg(
                 Arg::with_name("TIMEREPRESENTATION")
                     .help("Sets the trigger time info representation\n")
@@ -239,6 +283,46 @@ impl Config {
                     .possible_values(&["debug", "outputs", "triggers", "warnings", "progress", "silent", "quiet"])
                     .default_value("triggers")
             )
+            .arg(
+                Arg::with_name("MIN_SEVERITY")
+                    .help("Sets the minimum severity of printed triggers\n")
+                    .long("min-severity")
+                    .possible_values(&["info", "warning", "critical"])
+                    .default_value("info")
+            )
+            .arg(
+                Arg::with_name("FAIL_ON_TRIGGER")
+                    .help("Exits with a non-zero code if any trigger, or the trigger with the given name, was raised\n")
+                    .long("fail-on-trigger")
+                    .value_name("NAME")
+                    .takes_value(true)
+                    .min_values(0)
+                    .require_equals(true)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("TRIGGER_ACTION")
+                    .help("Runs a command whenever any trigger, or the trigger with the given name, is raised\nThe command receives the details of the trigger in the environment variables\nRTLOLA_TRIGGER_NAME, RTLOLA_TRIGGER_INDEX, RTLOLA_TRIGGER_SEVERITY, RTLOLA_TRIGGER_MESSAGE, and RTLOLA_TRIGGER_TIME.\n")
+                    .long("trigger-action")
+                    .value_name("[NAME=]COMMAND")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("OVERFLOW")
+                    .help("Sets how integer arithmetic handles results exceeding the declared type\n")
+                    .long("overflow")
+                    .possible_values(&["wrapping", "saturating", "trapping"])
+                    .default_value("wrapping")
+            )
+            .arg(
+                Arg::with_name("ON_RUNTIME_ERROR")
+                    .help("Sets whether the monitor continues or aborts when a stream fails to evaluate, e.g., due to a division by zero\n")
+                    .long("on-runtime-error")
+                    .possible_values(&["continue", "abort"])
+                    .default_value("continue")
+            )
             .arg(
                 Arg::with_name("TIMEREPRESENTATION")
                     .help("Sets the trigger time info representation\n")
@@ -389,11 +473,99 @@ impl Config {
             _ => unreachable!(),
         };
 
-        let cfg = EvalConfig::new(src, Statistics::None, verbosity, out, evaluator, mode, time_representation);
+        let min_severity = match parse_matches.value_of("MIN_SEVERITY").unwrap() {
+            "info" => TriggerSeverity::Info,
+            "warning" => TriggerSeverity::Warning,
+            "critical" => TriggerSeverity::Critical,
+            _ => unreachable!(),
+        };
+
+        let overflow = match parse_matches.value_of("OVERFLOW").unwrap() {
+            "wrapping" => OverflowMode::Wrapping,
+            "saturating" => OverflowMode::Saturating,
+            "trapping" => OverflowMode::Trapping,
+            _ => unreachable!(),
+        };
+
+        let runtime_errors = match parse_matches.value_of("ON_RUNTIME_ERROR").unwrap() {
+            "continue" => RuntimeErrorPolicy::Continue,
+            "abort" => RuntimeErrorPolicy::Abort,
+            _ => unreachable!(),
+        };
+
+        let trigger_names: Vec<&str> = ir.triggers.iter().filter_map(|trigger| trigger.name.as_deref()).collect();
+        let fail_on = if !parse_matches.is_present("FAIL_ON_TRIGGER") {
+            TriggerSelection::None
+        } else {
+            let names: Vec<String> =
+                parse_matches.values_of("FAIL_ON_TRIGGER").into_iter().flatten().map(String::from).collect();
+            if let Some(name) = names.iter().find(|name| !trigger_names.contains(&name.as_str())) {
+                eprintln!("Unknown trigger `{}` in `--fail-on-trigger`.", name);
+                std::process::exit(1);
+            }
+            // an occurrence without a name selects all triggers
+            if parse_matches.occurrences_of("FAIL_ON_TRIGGER") as usize > names.len() {
+                TriggerSelection::All
+            } else {
+                TriggerSelection::Named(names)
+            }
+        };
+        let actions = parse_matches
+            .values_of("TRIGGER_ACTION")
+            .into_iter()
+            .flatten()
+            .map(|action| {
+                Self::parse_trigger_action(action, &trigger_names).unwrap_or_else(|name| {
+                    eprintln!("Unknown trigger `{}` in `--trigger-action`.", name);
+                    std::process::exit(1);
+                })
+            })
+            .collect();
+
+        let mut cfg = EvalConfig::new(src, Statistics::None, verbosity, out, evaluator, mode, time_representation);
+        cfg.min_severity = min_severity;
+        cfg.trigger_policy = TriggerPolicy { fail_on, actions };
+        cfg.overflow = overflow;
+        cfg.runtime_errors = runtime_errors;
 
         Config { cfg, ir }
     }
 
+    /// Splits a trigger action of the form `[NAME=]COMMAND` into the selected triggers and the command.
+    /// Leading words `NAME=value` that do not name a trigger are environment assignments of the command, e.g., in
+    /// `FOO=1 cmd`, and are skipped when looking for the trigger name. Such words are rejected as unknown trigger
+    /// names if no command follows them, whereas a command containing `=` elsewhere, e.g., in an argument, applies to
+    /// all triggers.
+    fn parse_trigger_action(action: &str, trigger_names: &[&str]) -> Result<(TriggerSelection, String), String> {
+        let mut unknown = None;
+        let mut start = 0;
+        let has_command = loop {
+            let word_start = action.len() - action[start..].trim_start().len();
+            let word_end = action[word_start..].find(char::is_whitespace).map_or(action.len(), |end| word_start + end);
+            match action[word_start..word_end].split_once('=') {
+                Some((name, _)) if trigger_names.contains(&name) => {
+                    let command = format!("{}{}", &action[..word_start], &action[word_start + name.len() + 1..]);
+                    return Ok((TriggerSelection::Named(vec![name.to_string()]), command));
+                }
+                Some((name, _)) if Self::is_trigger_name(name) => {
+                    unknown = Some(name);
+                    start = word_end;
+                }
+                _ => break word_start < word_end,
+            }
+        };
+        match unknown {
+            Some(name) if !has_command => Err(name.to_string()),
+            _ => Ok((TriggerSelection::All, action.to_string())),
+        }
+    }
+
+    /// Checks whether a string is an identifier, i.e., could name a trigger.
+    fn is_trigger_name(name: &str) -> bool {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+    }
+
     /**
     Turns a `Config` that was created through a call to `new_api` into a `Monitor`.
     */
@@ -5,4 +5,5 @@ mod window_aggregations;
 
 pub(crate) use self::stores::GlobalStore;
 pub use self::value::Value;
+pub(crate) use self::value::{round_float, RuntimeError};
 pub(crate) use self::window::SlidingWindow;
@@ -128,13 +128,23 @@ impl InstanceStore {
         }
     }
 
-    /// Updates the buffer of stream instance
-    pub(crate) fn push_value(&mut self, v: Value) {
+    /// Updates the buffer of stream instance and returns the value dropped from a full buffer
+    pub(crate) fn push_value(&mut self, v: Value) -> Option<Value> {
+        let mut dropped = None;
         if let MemorizationBound::Bounded(limit) = self.bound {
             if self.buffer.len() == limit as usize {
-                self.buffer.pop_back();
+                dropped = self.buffer.pop_back();
             }
         }
         self.buffer.push_front(v);
+        dropped
+    }
+
+    /// Reverts the last update of the buffer, where `dropped` is the value returned by `push_value`
+    pub(crate) fn revert_push(&mut self, dropped: Option<Value>) {
+        self.buffer.pop_front();
+        if let Some(v) = dropped {
+            self.buffer.push_back(v);
+        }
     }
 }
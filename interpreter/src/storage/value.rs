@@ -1,6 +1,12 @@
+use crate::basics::{OverflowMode, Time};
+use ip_network::{Ipv4Network, Ipv6Network};
 use ordered_float::NotNan;
-use rtlola_frontend::ir::Type;
+use rtlola_frontend::ir::{ArithLogOp, FloatTy, IntTy, Type, UIntTy};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::ops;
 
 use self::Value::*;
@@ -43,6 +49,48 @@ pub enum Value {
     A slice of bytes.
     */
     Bytes(Box<[u8]>),
+    /**
+    An IPv4 address.
+    */
+    IPv4Addr(Ipv4Addr),
+    /**
+    An IPv6 address.
+    */
+    IPv6Addr(Ipv6Addr),
+    /**
+    An IPv4 network.
+    */
+    IPv4Net(Ipv4Network),
+    /**
+    An IPv6 network.
+    */
+    IPv6Net(Ipv6Network),
+    /**
+    A span of time in nanoseconds, which may be negative.
+    */
+    Duration(i64),
+    /**
+    A point in time in nanoseconds since the start of the monitor.
+    */
+    Timestamp(i64),
+    /**
+    An immutable set of `Value`s.
+
+    All elements have the same type.
+    */
+    Set(Box<BTreeSet<Value>>),
+    /**
+    An immutable sequence of `Value`s.
+
+    All elements have the same type.
+    */
+    List(Box<[Value]>),
+    /**
+    An immutable map from `Value`s to `Value`s.
+
+    All keys have the same type, and so do all values.
+    */
+    Map(Box<BTreeMap<Value, Value>>),
 }
 
 impl Value {
@@ -58,18 +106,28 @@ impl Value {
         if let Ok(source) = std::str::from_utf8(source) {
             match ty {
                 Type::Bool => source.parse::<bool>().map(Bool).ok(),
-                Type::Int(_) => source.parse::<i64>().map(Signed).ok(),
+                Type::Int(_) => source.parse::<i64>().ok().filter(|&i| fits(i as i128, ty)).map(Signed),
                 Type::UInt(_) => {
                     // TODO: This is just a quickfix!! Think of something more general.
                     if source == "0.0" {
                         Some(Unsigned(0))
                     } else {
-                        source.parse::<u64>().map(Unsigned).ok()
+                        source.parse::<u64>().ok().filter(|&u| fits(u as i128, ty)).map(Unsigned)
                     }
                 }
-                Type::Float(_) => source.parse::<f64>().ok().map(|f| Float(NotNan::new(f).unwrap())),
+                Type::Float(fty) => source.parse::<f64>().ok().and_then(|f| Value::try_float(round_float(f, fty)).ok()),
                 Type::String => Some(Str(source.into())),
+                Type::IPv4Addr => source.parse().ok().map(IPv4Addr),
+                Type::IPv6Addr => source.parse().ok().map(IPv6Addr),
+                Type::IPv4Net => source.parse().ok().map(IPv4Net),
+                Type::IPv6Net => source.parse().ok().map(IPv6Net),
+                // time values are given in seconds
+                Type::Duration => source.parse::<f64>().ok().map(|f| Duration((f * 1e9) as i64)),
+                Type::Timestamp => source.parse::<f64>().ok().map(|f| Timestamp((f * 1e9) as i64)),
                 Type::Tuple(_) => unimplemented!(),
+                Type::Set(_) | Type::List(_) | Type::Map(_, _) => {
+                    unreachable!("rejected for inputs by the type checker")
+                }
                 Type::Option(_) | Type::Function(_, _) | Type::Bytes => unreachable!(),
             }
         } else {
@@ -84,6 +142,31 @@ impl Value {
         Float(NotNan::new(f).unwrap())
     }
 
+    /// Returns the evaluation time as 'Timestamp', or an overflow error if it exceeds the range of time values
+    pub(crate) fn new_timestamp(ts: Time) -> Result<Value, RuntimeError> {
+        i64::try_from(ts.as_nanos())
+            .map(Timestamp)
+            .map_err(|_| RuntimeError::Overflow(format!("{:?} exceeds the range of time values", ts)))
+    }
+
+    /// Returns the time value of a 'Timestamp' or 'Duration' value type in seconds
+    pub(crate) fn get_seconds(&self) -> f64 {
+        match *self {
+            Duration(ns) | Timestamp(ns) => ns as f64 / 1e9,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the numeric value of an integer or float value type, and `None` for all other value types
+    pub(crate) fn get_number(&self) -> Option<f64> {
+        match *self {
+            Unsigned(u) => Some(u as f64),
+            Signed(i) => Some(i as f64),
+            Float(f) => Some(f.into_inner()),
+            _ => Option::None,
+        }
+    }
+
     /// Decides if a value is of type bool
     pub(crate) fn is_bool(&self) -> bool {
         if let Bool(_) = self {
@@ -103,6 +186,430 @@ impl Value {
     }
 }
 
+/// An error that occurs while computing a value, e.g., a division by zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum RuntimeError {
+    /// An integer division or remainder by zero.
+    DivisionByZero,
+    /// A result exceeding its type under trapping overflow, or exceeding the range of time values.
+    Overflow(String),
+    /// A float operation without a numeric result, e.g., `sqrt(-1.0)`.
+    NotANumber,
+    /// An operation applied to values of unexpected types.
+    IncompatibleTypes(String),
+    /// A synchronous access to a stream that failed to produce a value.
+    MissingValue,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::DivisionByZero => write!(f, "division by zero"),
+            RuntimeError::Overflow(msg) => write!(f, "overflow: {}", msg),
+            RuntimeError::NotANumber => write!(f, "result is not a number"),
+            RuntimeError::IncompatibleTypes(msg) => write!(f, "incompatible operands: {}", msg),
+            RuntimeError::MissingValue => write!(f, "accessed stream produced no value"),
+        }
+    }
+}
+
+impl Value {
+    /// Returns a float value as 'Value' type, or an error if `f` is not a number.
+    pub(crate) fn try_float(f: f64) -> Result<Value, RuntimeError> {
+        NotNan::new(f).map(Float).map_err(|_| RuntimeError::NotANumber)
+    }
+
+    /// Applies an arithmetic operator to operands of the numeric type `ty`, where the result follows the declared
+    /// width of the type:
+    /// # Arguments:
+    /// * 'op' - The operator, one of the arithmetic, `BitNot`, or `Shl` operators
+    /// * 'rhs' - The second operand if the operator is binary
+    /// * 'ty' - The type of the result, where an optional type denotes the type of the non-optional result
+    /// * 'overflow' - Determines the result of integer operations that exceed the bounds of `ty`
+    pub(crate) fn arith(
+        op: ArithLogOp,
+        lhs: Value,
+        rhs: Option<Value>,
+        ty: &Type,
+        overflow: OverflowMode,
+    ) -> Result<Value, RuntimeError> {
+        match ty {
+            Type::Option(inner) => Value::arith(op, lhs, rhs, inner, overflow),
+            Type::Int(_) | Type::UInt(_) => match (lhs.get_integer(), rhs.as_ref().map(Value::get_integer)) {
+                (Some(l), Option::None) => {
+                    int_arith(op, l, Option::None, ty, overflow).map(|i| Value::from_integer(i, ty))
+                }
+                (Some(l), Some(Some(r))) => int_arith(op, l, Some(r), ty, overflow).map(|i| Value::from_integer(i, ty)),
+                _ => Err(RuntimeError::IncompatibleTypes(describe(op, &lhs, rhs.as_ref()))),
+            },
+            Type::Float(fty) => match Value::apply(op, &lhs, rhs.as_ref())? {
+                Float(f) => Value::try_float(round_float(f.into_inner(), fty)),
+                v => Ok(v),
+            },
+            _ => Value::apply(op, &lhs, rhs.as_ref()),
+        }
+    }
+
+    /// Converts a numeric value into the numeric type `to`, where integers exceeding the bounds of `to` are
+    /// handled according to `overflow` and floats are rounded to the width of `to`.
+    pub(crate) fn convert(self, to: &Type, overflow: OverflowMode) -> Result<Value, RuntimeError> {
+        let incompatible = || RuntimeError::IncompatibleTypes(format!("conversion of {:?} into `{}`", self, to));
+        match to {
+            Type::Int(_) | Type::UInt(_) => {
+                let i = match self {
+                    Float(f) => f.into_inner().trunc() as i128,
+                    ref v => v.get_integer().ok_or_else(incompatible)?,
+                };
+                let res = if fits(i, to) {
+                    i
+                } else {
+                    match overflow {
+                        OverflowMode::Wrapping => wrap(i, to),
+                        OverflowMode::Saturating => saturate(i > 0, to),
+                        OverflowMode::Trapping => {
+                            return Err(RuntimeError::Overflow(format!("{} does not fit into `{}`", self, to)))
+                        }
+                    }
+                };
+                Ok(Value::from_integer(res, to))
+            }
+            Type::Float(fty) => Value::try_float(round_float(self.get_number().ok_or_else(incompatible)?, fty)),
+            _ => Err(incompatible()),
+        }
+    }
+
+    /// Applies a function of the `string` module to its arguments, where the first argument is the string.
+    /// Indices count characters rather than bytes, and functions returning an optional value return `None` for
+    /// indices out of range or strings that do not represent a number.
+    pub(crate) fn string_function(name: &str, args: &[Value]) -> Value {
+        let string = |ix: usize| match &args[ix] {
+            Str(s) => s,
+            v => unreachable!("expected `String`, found {:?} in function {}", v, name),
+        };
+        let index = |ix: usize| match args[ix] {
+            Unsigned(u) => u as usize,
+            ref v => unreachable!("expected `UInt64`, found {:?} in function {}", v, name),
+        };
+        let s = string(0);
+        let optional = |res: Option<Value>| res.unwrap_or(None);
+        match name {
+            "len" => Unsigned(s.chars().count() as u64),
+            "concat" => Str(format!("{}{}", s, string(1)).into()),
+            "substring" => {
+                let (from, to) = (index(1), index(2));
+                optional(if from <= to && to <= s.chars().count() {
+                    Some(Str(s.chars().skip(from).take(to - from).collect::<String>().into()))
+                } else {
+                    Option::None
+                })
+            }
+            "starts_with" => Bool(s.starts_with(&**string(1))),
+            "ends_with" => Bool(s.ends_with(&**string(1))),
+            "contains" => Bool(s.contains(&**string(1))),
+            "to_lower" => Str(s.to_lowercase().into()),
+            "to_upper" => Str(s.to_uppercase().into()),
+            "split" => List(s.split(&**string(1)).map(|part| Str(part.into())).collect()),
+            "parse_int" => optional(s.trim().parse().ok().map(Signed)),
+            "parse_uint" => optional(s.trim().parse().ok().map(Unsigned)),
+            "parse_float" => optional(s.trim().parse().ok().and_then(|f| Value::try_float(f).ok())),
+            _ => unreachable!("unknown string function {}", name),
+        }
+    }
+
+    /// Applies a method of `Bytes` to its arguments, where the first argument is the byte string.
+    /// Methods returning an optional value return `None` for indices out of range or patterns that do not occur.
+    pub(crate) fn bytes_function(name: &str, args: &[Value]) -> Value {
+        let bytes = match &args[0] {
+            Bytes(b) => b,
+            v => unreachable!("expected `Bytes`, found {:?} in function {}", v, name),
+        };
+        let pattern = |ix: usize| match &args[ix] {
+            Str(s) => s.as_bytes(),
+            v => unreachable!("expected `String`, found {:?} in function {}", v, name),
+        };
+        let index = |ix: usize| match args[ix] {
+            Unsigned(u) => usize::try_from(u).unwrap_or(usize::MAX),
+            ref v => unreachable!("expected `UInt64`, found {:?} in function {}", v, name),
+        };
+        let optional = |res: Option<Value>| res.unwrap_or(None);
+        match name {
+            "len" => Unsigned(bytes.len() as u64),
+            "slice" => optional(bytes.get(index(1)..index(2)).map(|slice| Bytes(slice.into()))),
+            "to_hex" => Str(args[0].to_string().into()),
+            "find" => {
+                let pattern = pattern(1);
+                optional(if pattern.is_empty() {
+                    Some(Unsigned(0))
+                } else {
+                    bytes.windows(pattern.len()).position(|window| window == pattern).map(|ix| Unsigned(ix as u64))
+                })
+            }
+            "starts_with" => Bool(bytes.starts_with(pattern(1))),
+            _ if name.starts_with("read_") => {
+                // the name has the form `read_<u|i><bits>_<be|le>`
+                let bits: u32 = name[6..name.len() - 3].parse().expect("decoders are declared with their width");
+                let width = bits as usize / 8;
+                let start = index(1);
+                let read = start.checked_add(width).and_then(|end| bytes.get(start..end));
+                optional(read.map(|read| {
+                    let value = if name.ends_with("_be") {
+                        read.iter().fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte))
+                    } else {
+                        read.iter().rev().fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte))
+                    };
+                    if name.as_bytes()[5] == b'i' {
+                        // sign extension of the most significant bit
+                        Signed(((value << (64 - bits)) as i64) >> (64 - bits))
+                    } else {
+                        Unsigned(value)
+                    }
+                }))
+            }
+            _ => unreachable!("unknown function {} on bytes", name),
+        }
+    }
+
+    /// Applies a method of `IPv4Addr` or `IPv6Addr` other than `in_subnet`, where the first argument is the address.
+    pub(crate) fn address_function(name: &str, args: &[Value]) -> Value {
+        let address = args[0].get_address().unwrap_or_else(|| {
+            unreachable!("expected an address, found {:?} in function {}", args[0], name);
+        });
+        match name {
+            "is_private" => Bool(match address {
+                IpAddr::V4(addr) => addr.is_private(),
+                // unique local addresses, fc00::/7
+                IpAddr::V6(addr) => (addr.segments()[0] & 0xfe00) == 0xfc00,
+            }),
+            "is_loopback" => Bool(address.is_loopback()),
+            "is_multicast" => Bool(address.is_multicast()),
+            "to_string" => Str(address.to_string().into()),
+            _ => unreachable!("unknown function {} on addresses", name),
+        }
+    }
+
+    /// Checks whether an address lies in a network of the same address family.
+    pub(crate) fn in_subnet(&self, network: &Value) -> bool {
+        match (self, network) {
+            (IPv4Addr(addr), IPv4Net(network)) => network.contains(*addr),
+            (IPv6Addr(addr), IPv6Net(network)) => network.contains(*addr),
+            (addr, network) => unreachable!("wrong Value types of {:?} and {:?} for function in_subnet", addr, network),
+        }
+    }
+
+    fn get_address(&self) -> Option<IpAddr> {
+        match self {
+            IPv4Addr(addr) => Some((*addr).into()),
+            IPv6Addr(addr) => Some((*addr).into()),
+            _ => Option::None,
+        }
+    }
+
+    /// Applies an arithmetic operator to float or time values.
+    fn apply(op: ArithLogOp, lhs: &Value, rhs: Option<&Value>) -> Result<Value, RuntimeError> {
+        use ArithLogOp::*;
+        let res = match (op, lhs, rhs) {
+            (op, Float(f1), rhs) => {
+                let f1 = f1.into_inner();
+                let res = match (op, rhs) {
+                    (Neg, Option::None) => Some(-f1),
+                    (Add, Some(Float(f2))) => Some(f1 + f2.into_inner()),
+                    (Sub, Some(Float(f2))) => Some(f1 - f2.into_inner()),
+                    (Mul, Some(Float(f2))) => Some(f1 * f2.into_inner()),
+                    (Div, Some(Float(f2))) => Some(f1 / f2.into_inner()),
+                    (Rem, Some(Float(f2))) => Some(f1 % f2.into_inner()),
+                    (Pow, Some(Float(f2))) => Some(f1.powf(f2.into_inner())),
+                    (Pow, Some(&Signed(i))) => Some(f1.powi(i as i32)),
+                    _ => Option::None,
+                };
+                match res {
+                    Some(f) => return Value::try_float(f),
+                    Option::None => Option::None,
+                }
+            }
+            (Add, &Duration(d1), Some(&Duration(d2))) => Some(d1.checked_add(d2).map(Duration)),
+            (Add, &Timestamp(t), Some(&Duration(d))) | (Add, &Duration(d), Some(&Timestamp(t))) => {
+                Some(t.checked_add(d).map(Timestamp))
+            }
+            (Sub, &Duration(d1), Some(&Duration(d2))) => Some(d1.checked_sub(d2).map(Duration)),
+            (Sub, &Timestamp(t), Some(&Duration(d))) => Some(t.checked_sub(d).map(Timestamp)),
+            (Sub, &Timestamp(t1), Some(&Timestamp(t2))) => Some(t1.checked_sub(t2).map(Duration)),
+            _ => Option::None,
+        };
+        match res {
+            Some(Some(v)) => Ok(v),
+            Some(Option::None) => {
+                Err(RuntimeError::Overflow(format!("{} exceeds the range of time values", describe(op, lhs, rhs))))
+            }
+            Option::None => Err(RuntimeError::IncompatibleTypes(describe(op, lhs, rhs))),
+        }
+    }
+
+    fn get_integer(&self) -> Option<i128> {
+        match *self {
+            Unsigned(u) => Some(u as i128),
+            Signed(i) => Some(i as i128),
+            _ => Option::None,
+        }
+    }
+
+    fn from_integer(i: i128, ty: &Type) -> Value {
+        match ty {
+            Type::Int(_) => Signed(i as i64),
+            Type::UInt(_) => Unsigned(i as u64),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Renders an operation for error messages, e.g., `200 + 56`.
+fn describe(op: ArithLogOp, lhs: &impl fmt::Display, rhs: Option<&impl fmt::Display>) -> String {
+    match (op, rhs) {
+        (_, Some(rhs)) => format!("{} {} {}", lhs, op, rhs),
+        (ArithLogOp::Neg, Option::None) => format!("-{}", lhs),
+        (op, Option::None) => format!("{}{}", op, lhs),
+    }
+}
+
+/// Returns the width in bits and whether the integer type `ty` is signed.
+fn int_width(ty: &Type) -> (u32, bool) {
+    match ty {
+        Type::Int(IntTy::I8) => (8, true),
+        Type::Int(IntTy::I16) => (16, true),
+        Type::Int(IntTy::I32) => (32, true),
+        Type::Int(IntTy::I64) => (64, true),
+        Type::UInt(UIntTy::U8) => (8, false),
+        Type::UInt(UIntTy::U16) => (16, false),
+        Type::UInt(UIntTy::U32) => (32, false),
+        Type::UInt(UIntTy::U64) => (64, false),
+        _ => unreachable!("{} is not an integer type", ty),
+    }
+}
+
+/// Returns the smallest and the largest value of the integer type `ty`.
+fn int_bounds(ty: &Type) -> (i128, i128) {
+    match int_width(ty) {
+        (bits, true) => (-(1 << (bits - 1)), (1 << (bits - 1)) - 1),
+        (bits, false) => (0, (1 << bits) - 1),
+    }
+}
+
+fn fits(i: i128, ty: &Type) -> bool {
+    let (min, max) = int_bounds(ty);
+    min <= i && i <= max
+}
+
+/// Discards all bits of `i` exceeding the width of `ty`.
+fn wrap(i: i128, ty: &Type) -> i128 {
+    let (bits, signed) = int_width(ty);
+    let modulus = 1i128 << bits;
+    let res = i.rem_euclid(modulus);
+    if signed && res > int_bounds(ty).1 {
+        res - modulus
+    } else {
+        res
+    }
+}
+
+fn saturate(positive: bool, ty: &Type) -> i128 {
+    let (min, max) = int_bounds(ty);
+    if positive {
+        max
+    } else {
+        min
+    }
+}
+
+/// Computes the result of an integer operation on `lhs` and `rhs` exactly and fits it into `ty`.
+fn int_arith(
+    op: ArithLogOp,
+    lhs: i128,
+    rhs: Option<i128>,
+    ty: &Type,
+    overflow: OverflowMode,
+) -> Result<i128, RuntimeError> {
+    use ArithLogOp::*;
+    let operand = rhs;
+    let rhs = || operand.expect("binary operator");
+    let exponent = || u32::try_from(rhs()).unwrap_or(u32::MAX);
+    if matches!(op, Div | Rem) && rhs() == 0 {
+        return Err(RuntimeError::DivisionByZero);
+    }
+    let exact = match op {
+        Neg => Some(-lhs),
+        Add => lhs.checked_add(rhs()),
+        Sub => lhs.checked_sub(rhs()),
+        Mul => lhs.checked_mul(rhs()),
+        Div => Some(lhs / rhs()),
+        Rem => Some(lhs % rhs()),
+        Pow => lhs.checked_pow(exponent()),
+        // Bit operations act on the representation of the value and thus always wrap.
+        BitNot => return Ok(wrap(!lhs, ty)),
+        Shl if rhs() >= int_width(ty).0 as i128 => return Ok(0),
+        Shl => return Ok(wrap(lhs.wrapping_shl(rhs() as u32), ty)),
+        _ => return Err(RuntimeError::IncompatibleTypes(format!("{:?} is not an arithmetic operator", op))),
+    };
+    match exact {
+        Some(res) if fits(res, ty) => Ok(res),
+        _ => match overflow {
+            OverflowMode::Wrapping => {
+                let wrapped = match op {
+                    Mul => lhs.wrapping_mul(rhs()),
+                    Pow => lhs.wrapping_pow(exponent()),
+                    _ => exact.expect("only multiplications exceed 128 bits"),
+                };
+                Ok(wrap(wrapped, ty))
+            }
+            OverflowMode::Saturating => {
+                // Only multiplications can exceed 128 bits, so the sign of the result follows from the operands.
+                let positive = match (op, exact) {
+                    (_, Some(res)) => res > 0,
+                    (Pow, Option::None) => lhs > 0 || exponent() % 2 == 0,
+                    (_, Option::None) => (lhs > 0) == (rhs() > 0),
+                };
+                Ok(saturate(positive, ty))
+            }
+            OverflowMode::Trapping => {
+                let op = describe(op, &lhs, operand.as_ref());
+                Err(RuntimeError::Overflow(format!("{} does not fit into `{}`", op, ty)))
+            }
+        },
+    }
+}
+
+/// Rounds `f` to the nearest value representable in the float type `ty`.
+pub(crate) fn round_float(f: f64, ty: &FloatTy) -> f64 {
+    match ty {
+        FloatTy::F64 => f,
+        FloatTy::F32 => f as f32 as f64,
+        FloatTy::F16 => {
+            // Values at or above the midpoint between the largest half-precision value 65504 and 65536 round to
+            // infinity.
+            if f.abs() >= 65520.0 {
+                return f.signum() * f64::INFINITY;
+            }
+            // The spacing of half-precision values is 2^(e - 10) for the exponent e of `f`, and the exponent of
+            // subnormal values is fixed to -14.
+            let exponent = ((f.abs().to_bits() >> 52) as i32 - 1023).max(-14);
+            let spacing = 2f64.powi(exponent - 10);
+            round_ties_even(f / spacing) * spacing
+        }
+    }
+}
+
+/// Rounds to the nearest integer, where ties are rounded to the even integer as in IEEE 754 arithmetic.
+fn round_ties_even(x: f64) -> f64 {
+    let floor = x.floor();
+    if x - floor == 0.5 {
+        if floor % 2.0 == 0.0 {
+            floor
+        } else {
+            floor + 1.0
+        }
+    } else {
+        x.round()
+    }
+}
+
 impl ops::Add for Value {
     type Output = Value;
     fn add(self, other: Value) -> Value {
@@ -110,6 +617,8 @@ impl ops::Add for Value {
             (Unsigned(v1), Unsigned(v2)) => Unsigned(v1 + v2),
             (Signed(v1), Signed(v2)) => Signed(v1 + v2),
             (Float(v1), Float(v2)) => Float(v1 + v2),
+            (Duration(d1), Duration(d2)) => Duration(d1 + d2),
+            (Timestamp(t), Duration(d)) | (Duration(d), Timestamp(t)) => Timestamp(t + d),
             (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
         }
     }
@@ -122,6 +631,9 @@ impl ops::Sub for Value {
             (Unsigned(v1), Unsigned(v2)) => Unsigned(v1 - v2),
             (Signed(v1), Signed(v2)) => Signed(v1 - v2),
             (Float(v1), Float(v2)) => Float(v1 - v2),
+            (Duration(d1), Duration(d2)) => Duration(d1 - d2),
+            (Timestamp(t), Duration(d)) => Timestamp(t - d),
+            (Timestamp(t1), Timestamp(t2)) => Duration(t1 - t2),
             (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
         }
     }
@@ -163,21 +675,6 @@ impl ops::Rem for Value {
     }
 }
 
-impl Value {
-    /// Returns the powered value of a value type, with:
-    /// # Arguments:
-    /// * 'exp' - The exponent given as a 'Value'
-    pub(crate) fn pow(self, exp: Value) -> Value {
-        match (self, exp) {
-            (Unsigned(v1), Unsigned(v2)) => Unsigned(v1.pow(v2 as u32)),
-            (Signed(v1), Signed(v2)) => Signed(v1.pow(v2 as u32)),
-            (Float(v1), Float(v2)) => Value::new_float(v1.powf(v2.into())),
-            (Float(v1), Signed(v2)) => Value::new_float(v1.powi(v2 as i32)),
-            (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
-        }
-    }
-}
-
 impl ops::BitAnd for Value {
     type Output = Value;
     fn bitand(self, other: Value) -> Value {
@@ -264,6 +761,8 @@ impl PartialOrd for Value {
     }
 }
 
+/// Values are totally ordered so that any value can be an element of a set or a key of a map: values of the same variant
+/// are ordered by their content, values of different variants by their variant, where `None` comes first.
 impl Ord for Value {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
@@ -271,7 +770,93 @@ impl Ord for Value {
             (Signed(i1), Signed(i2)) => i1.cmp(i2),
             (Float(f1), Float(f2)) => f1.cmp(f2),
             (Str(s1), Str(s2)) => s1.cmp(s2),
-            (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
+            (Bool(b1), Bool(b2)) => b1.cmp(b2),
+            (Bytes(b1), Bytes(b2)) => b1.cmp(b2),
+            (IPv4Addr(a1), IPv4Addr(a2)) => a1.cmp(a2),
+            (IPv6Addr(a1), IPv6Addr(a2)) => a1.cmp(a2),
+            (IPv4Net(n1), IPv4Net(n2)) => n1.cmp(n2),
+            (IPv6Net(n1), IPv6Net(n2)) => n1.cmp(n2),
+            (Tuple(t1), Tuple(t2)) => t1.cmp(t2),
+            (Duration(d1), Duration(d2)) => d1.cmp(d2),
+            (Timestamp(t1), Timestamp(t2)) => t1.cmp(t2),
+            (Set(s1), Set(s2)) => s1.cmp(s2),
+            (List(l1), List(l2)) => l1.cmp(l2),
+            (Map(m1), Map(m2)) => m1.cmp(m2),
+            (None, None) => Ordering::Equal,
+            (a, b) => a.variant_index().cmp(&b.variant_index()),
+        }
+    }
+}
+
+impl Value {
+    /// The position of the variant in the order of values of different variants.
+    fn variant_index(&self) -> u8 {
+        match self {
+            None => 0,
+            Bool(_) => 1,
+            Unsigned(_) => 2,
+            Signed(_) => 3,
+            Float(_) => 4,
+            Tuple(_) => 5,
+            Str(_) => 6,
+            Bytes(_) => 7,
+            IPv4Addr(_) => 8,
+            IPv6Addr(_) => 9,
+            IPv4Net(_) => 10,
+            IPv6Net(_) => 11,
+            Duration(_) => 12,
+            Timestamp(_) => 13,
+            Set(_) => 14,
+            List(_) => 15,
+            Map(_) => 16,
+        }
+    }
+}
+
+/// Formats values for humans, e.g., when interpolated into trigger messages.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_list<'a>(
+            f: &mut fmt::Formatter<'_>,
+            items: impl Iterator<Item = &'a Value>,
+            open: &str,
+            close: &str,
+        ) -> fmt::Result {
+            write!(f, "{}", open)?;
+            for (ix, item) in items.enumerate() {
+                if ix > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            write!(f, "{}", close)
+        }
+        match self {
+            None => write!(f, "none"),
+            Bool(b) => write!(f, "{}", b),
+            Unsigned(u) => write!(f, "{}", u),
+            Signed(i) => write!(f, "{}", i),
+            Float(v) => write!(f, "{}", v),
+            Tuple(values) => write_list(f, values.iter(), "(", ")"),
+            Str(s) => write!(f, "{}", s),
+            Bytes(bytes) => bytes.iter().try_for_each(|byte| write!(f, "{:02x}", byte)),
+            IPv4Addr(addr) => write!(f, "{}", addr),
+            IPv6Addr(addr) => write!(f, "{}", addr),
+            IPv4Net(network) => write!(f, "{}", network),
+            IPv6Net(network) => write!(f, "{}", network),
+            Duration(_) | Timestamp(_) => write!(f, "{}s", self.get_seconds()),
+            Set(values) => write_list(f, values.iter(), "{", "}"),
+            List(values) => write_list(f, values.iter(), "[", "]"),
+            Map(entries) => {
+                write!(f, "{{")?;
+                for (ix, (key, value)) in entries.iter().enumerate() {
+                    if ix > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
@@ -280,6 +865,7 @@ impl Ord for Value {
 mod tests {
 
     use super::*;
+    use std::str::FromStr;
 
     #[test]
     fn size_of_value() {
@@ -287,4 +873,158 @@ mod tests {
         let expected = 24;
         assert!(result == expected, "Size of `Value` should be {} bytes, was `{}`", expected, result);
     }
+
+    #[test]
+    fn total_order() {
+        let set = |values: Vec<Value>| Set(Box::new(values.into_iter().collect()));
+        let mut values = set(vec![Unsigned(2), None, Unsigned(1)]);
+        if let Set(set) = &values {
+            assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![None, Unsigned(1), Unsigned(2)]);
+        }
+        values = set(vec![set(vec![Bool(true)]), set(vec![]), set(vec![Bool(false), Bool(true)])]);
+        assert_eq!(values.to_string(), "{{}, {false, true}, {true}}");
+        let map = |key: Value| Map(Box::new(vec![(key, Unsigned(0))].into_iter().collect()));
+        assert!(map(set(vec![])) < map(set(vec![None])));
+        assert!(Str("a".into()) < Set(Box::default()));
+        assert!(List(vec![Unsigned(1)].into()) < List(vec![Unsigned(1), None].into()));
+    }
+
+    #[test]
+    fn display_value() {
+        let tuple = Tuple(vec![Value::new_float(1.5), Str("a".into()), None].into_boxed_slice());
+        assert_eq!(tuple.to_string(), "(1.5, a, none)");
+        assert_eq!(Duration(2_500_000_000).to_string(), "2.5s");
+        assert_eq!(Bytes(vec![0, 255].into_boxed_slice()).to_string(), "00ff");
+    }
+
+    #[test]
+    fn integer_overflow() {
+        use ArithLogOp::*;
+        let i8_ty = Type::Int(IntTy::I8);
+        let u8_ty = Type::UInt(UIntTy::U8);
+        let arith = |op, lhs, rhs, ty, mode| Value::arith(op, lhs, Some(rhs), ty, mode).unwrap();
+        assert_eq!(arith(Add, Signed(100), Signed(27), &i8_ty, OverflowMode::Trapping), Signed(127));
+        assert_eq!(arith(Add, Signed(100), Signed(28), &i8_ty, OverflowMode::Wrapping), Signed(-128));
+        assert_eq!(arith(Sub, Signed(-100), Signed(29), &i8_ty, OverflowMode::Saturating), Signed(-128));
+        assert_eq!(arith(Sub, Unsigned(1), Unsigned(2), &u8_ty, OverflowMode::Wrapping), Unsigned(255));
+        assert_eq!(arith(Mul, Unsigned(16), Unsigned(17), &u8_ty, OverflowMode::Saturating), Unsigned(255));
+        assert_eq!(arith(Pow, Unsigned(2), Unsigned(9), &u8_ty, OverflowMode::Wrapping), Unsigned(0));
+        assert_eq!(arith(Div, Signed(-128), Signed(-1), &i8_ty, OverflowMode::Wrapping), Signed(-128));
+        assert_eq!(
+            arith(Shl, Unsigned(0b1100_0001), Unsigned(1), &u8_ty, OverflowMode::Trapping),
+            Unsigned(0b1000_0010)
+        );
+        assert_eq!(Value::arith(BitNot, Unsigned(0), Option::None, &u8_ty, OverflowMode::Trapping), Ok(Unsigned(255)));
+        assert_eq!(Value::arith(Neg, Signed(-128), Option::None, &i8_ty, OverflowMode::Saturating), Ok(Signed(127)));
+        let u64_ty = Type::UInt(UIntTy::U64);
+        assert_eq!(arith(Mul, Unsigned(u64::MAX), Unsigned(u64::MAX), &u64_ty, OverflowMode::Wrapping), Unsigned(1));
+        let i64_ty = Type::Int(IntTy::I64);
+        assert_eq!(arith(Pow, Signed(-2), Signed(127), &i64_ty, OverflowMode::Saturating), Signed(i64::MIN));
+    }
+
+    #[test]
+    fn integer_overflow_trapping() {
+        let u8_ty = Type::UInt(UIntTy::U8);
+        let sum = Value::arith(ArithLogOp::Add, Unsigned(200), Some(Unsigned(56)), &u8_ty, OverflowMode::Trapping);
+        assert!(matches!(sum, Err(RuntimeError::Overflow(_))));
+        let conversion = Signed(-1).convert(&u8_ty, OverflowMode::Trapping);
+        assert!(matches!(conversion, Err(RuntimeError::Overflow(_))));
+    }
+
+    #[test]
+    fn bytes_functions() {
+        let bytes = Bytes(vec![0x47, 0x45, 0x54, 0xff, 0xfe, 0x01].into_boxed_slice());
+        let call = |name: &str, rest: &[Value]| Value::bytes_function(name, &[&[bytes.clone()], rest].concat());
+        assert_eq!(call("len", &[]), Unsigned(6));
+        assert_eq!(call("slice", &[Unsigned(1), Unsigned(3)]), Bytes(vec![0x45, 0x54].into_boxed_slice()));
+        assert_eq!(call("slice", &[Unsigned(3), Unsigned(7)]), None);
+        assert_eq!(call("to_hex", &[]), Str("474554fffe01".into()));
+        assert_eq!(call("starts_with", &[Str("GET".into())]), Bool(true));
+        assert_eq!(call("find", &[Str("ET".into())]), Unsigned(1));
+        assert_eq!(call("find", &[Str("POST".into())]), None);
+        assert_eq!(call("read_u16_be", &[Unsigned(3)]), Unsigned(0xfffe));
+        assert_eq!(call("read_u16_le", &[Unsigned(3)]), Unsigned(0xfeff));
+        assert_eq!(call("read_i16_be", &[Unsigned(3)]), Signed(-2));
+        assert_eq!(call("read_u32_le", &[Unsigned(2)]), Unsigned(0x01fe_ff54));
+        assert_eq!(call("read_u32_be", &[Unsigned(3)]), None);
+        assert_eq!(call("read_u64_be", &[Unsigned(u64::MAX)]), None);
+    }
+    #[test]
+    fn address_functions() {
+        let v4 = |s: &str| IPv4Addr(s.parse().unwrap());
+        let v6 = |s: &str| IPv6Addr(s.parse().unwrap());
+        assert_eq!(Value::try_from(b"10.0.0.1", &Type::IPv4Addr), Some(v4("10.0.0.1")));
+        assert_eq!(Value::try_from(b"10.0.0.256", &Type::IPv4Addr), Option::None);
+        assert_eq!(Value::try_from(b"fe80::1", &Type::IPv6Addr), Some(v6("fe80::1")));
+        assert_eq!(Value::address_function("is_private", &[v4("172.16.3.4")]), Bool(true));
+        assert_eq!(Value::address_function("is_private", &[v4("8.8.8.8")]), Bool(false));
+        assert_eq!(Value::address_function("is_private", &[v6("fd00::1")]), Bool(true));
+        assert_eq!(Value::address_function("is_loopback", &[v6("::1")]), Bool(true));
+        assert_eq!(Value::address_function("is_multicast", &[v4("224.0.0.1")]), Bool(true));
+        assert_eq!(Value::address_function("to_string", &[v6("fe80:0::1")]), Str("fe80::1".into()));
+        assert_eq!(Value::try_from(b"10.0.0.0/8", &Type::IPv4Net), Some(IPv4Net("10.0.0.0/8".parse().unwrap())));
+        assert_eq!(Value::try_from(b"10.0.0.1/8", &Type::IPv4Net), Option::None);
+        let network = IPv4Net("10.0.0.0/8".parse().unwrap());
+        assert!(v4("10.1.2.3").in_subnet(&network));
+        assert!(!v4("11.0.0.1").in_subnet(&network));
+        assert!(v6("fe80::1").in_subnet(&IPv6Net("fe80::/10".parse().unwrap())));
+        assert_eq!(network.to_string(), "10.0.0.0/8");
+    }
+
+    #[test]
+    fn runtime_errors() {
+        use ArithLogOp::*;
+        let u8_ty = Type::UInt(UIntTy::U8);
+        let f64_ty = Type::Float(FloatTy::F64);
+        let arith = |op, lhs, rhs, ty| Value::arith(op, lhs, Some(rhs), ty, OverflowMode::Wrapping);
+        assert_eq!(arith(Div, Unsigned(1), Unsigned(0), &u8_ty), Err(RuntimeError::DivisionByZero));
+        assert_eq!(arith(Rem, Unsigned(1), Unsigned(0), &u8_ty), Err(RuntimeError::DivisionByZero));
+        assert_eq!(
+            arith(Div, Value::new_float(1.0), Value::new_float(0.0), &f64_ty),
+            Ok(Value::new_float(f64::INFINITY))
+        );
+        assert_eq!(arith(Div, Value::new_float(0.0), Value::new_float(0.0), &f64_ty), Err(RuntimeError::NotANumber));
+        assert!(matches!(arith(Add, Bool(true), Unsigned(1), &u8_ty), Err(RuntimeError::IncompatibleTypes(_))));
+        assert_eq!(Value::try_from(b"NaN", &f64_ty), Option::None);
+    }
+
+    #[test]
+    fn integer_conversion() {
+        let i8_ty = Type::Int(IntTy::I8);
+        assert_eq!(Unsigned(200).convert(&i8_ty, OverflowMode::Wrapping), Ok(Signed(-56)));
+        assert_eq!(Signed(-1).convert(&Type::UInt(UIntTy::U16), OverflowMode::Saturating), Ok(Unsigned(0)));
+        assert_eq!(Value::new_float(-300.7).convert(&i8_ty, OverflowMode::Saturating), Ok(Signed(-128)));
+        assert_eq!(Value::try_from(b"-129", &i8_ty), Option::None);
+        assert_eq!(Value::try_from(b"-128", &i8_ty), Some(Signed(-128)));
+    }
+
+    #[test]
+    fn float_rounding() {
+        assert_eq!(round_float(0.1, &FloatTy::F64), 0.1);
+        assert_eq!(round_float(0.1, &FloatTy::F32), 0.1f32 as f64);
+        assert_eq!(round_float(0.1, &FloatTy::F16), 0.0999755859375);
+        assert_eq!(round_float(2049.0, &FloatTy::F16), 2048.0);
+        assert_eq!(round_float(2051.0, &FloatTy::F16), 2052.0);
+        assert_eq!(round_float(-2049.0, &FloatTy::F16), -2048.0);
+        assert_eq!(round_float(-2051.0, &FloatTy::F16), -2052.0);
+        assert_eq!(round_float(-65519.0, &FloatTy::F16), -65504.0);
+        assert_eq!(round_float(65520.0, &FloatTy::F16), f64::INFINITY);
+        // the smallest subnormal half-precision value is 2^-24
+        assert_eq!(round_float(2f64.powi(-24) * 1.4, &FloatTy::F16), 2f64.powi(-24));
+        assert_eq!(round_float(2f64.powi(-26), &FloatTy::F16), 0.0);
+        let sum = Value::arith(
+            ArithLogOp::Add,
+            Value::new_float(1.0),
+            Some(Value::new_float(1e-4)),
+            &Type::Float(FloatTy::F16),
+            OverflowMode::Wrapping,
+        );
+        assert_eq!(sum, Ok(Value::new_float(1.0)));
+    }
+
+    #[test]
+    fn timestamp_overflow() {
+        assert_eq!(Value::new_timestamp(Time::from_secs(1)), Ok(Timestamp(1_000_000_000)));
+        assert!(matches!(Value::new_timestamp(Time::from_secs(u64::MAX)), Err(RuntimeError::Overflow(_))));
+    }
 }
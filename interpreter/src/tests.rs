@@ -5,6 +5,14 @@ use std::io::Write;
 use tempfile::NamedTempFile;
 
 fn run(spec: &str, data: &str) -> Result<Arc<OutputHandler>, Box<dyn std::error::Error>> {
+    run_with_policy(spec, data, TriggerPolicy::default())
+}
+
+fn run_with_policy(
+    spec: &str,
+    data: &str,
+    trigger_policy: TriggerPolicy,
+) -> Result<Arc<OutputHandler>, Box<dyn std::error::Error>> {
     let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
     let mut file = NamedTempFile::new().expect("failed to create temporary file");
     write!(file, "{}", data).expect("writing tempfile failed");
@@ -17,6 +25,29 @@ fn run(spec: &str, data: &str) -> Result<Arc<OutputHandler>, Box<dyn std::error:
         ExecutionMode::Offline,
         TimeRepresentation::Hide,
     );
+    let cfg = EvalConfig { trigger_policy, ..cfg };
+    let config = Config { cfg, ir };
+    config.run()
+}
+
+fn run_with_runtime_errors(
+    spec: &str,
+    data: &str,
+    runtime_errors: RuntimeErrorPolicy,
+) -> Result<Arc<OutputHandler>, Box<dyn std::error::Error>> {
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let mut file = NamedTempFile::new().expect("failed to create temporary file");
+    write!(file, "{}", data).expect("writing tempfile failed");
+    let mut cfg = EvalConfig::new(
+        EventSourceConfig::CSV { src: CSVInputSource::file(file.path().to_str().unwrap().to_string(), None, None) },
+        Statistics::Debug,
+        Verbosity::Silent,
+        OutputChannel::StdErr,
+        EvaluatorChoice::ClosureBased,
+        ExecutionMode::Offline,
+        TimeRepresentation::Hide,
+    );
+    cfg.runtime_errors = runtime_errors;
     let config = Config { cfg, ir };
     config.run()
 }
@@ -101,6 +132,197 @@ trigger c > 2 "c is too large"
     assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
 }
 
+#[test]
+fn named_trigger_counts() {
+    let spec = r#"
+input a: Int64
+
+trigger too_high: critical := a > 5 "a is {a}"
+trigger too_low: info := a < 2
+trigger a = 3
+    "#;
+
+    let data = r#"a,time
+1,0.0
+6,0.1
+3,0.2
+7,0.3
+0,0.4
+1,0.5"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    let statistics = output_handler.statistics.as_ref().unwrap();
+    assert_eq!(statistics.get_num_trigger_by_name("too_high"), Some(2));
+    assert_eq!(statistics.get_num_trigger_by_name("too_low"), Some(3));
+    assert_eq!(statistics.get_num_trigger_by_name("trigger"), None);
+}
+
+#[test]
+fn assumption_violation_counts() {
+    let spec = r#"
+input alt: Float64 assume alt >= 0.0 && alt < 10000.0
+
+trigger alt > 5000.0 "too high"
+    "#;
+
+    let data = r#"alt,time
+10.0,0.0
+-3.5,0.5
+6000.0,1.0
+20000.0,1.5"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    let statistics = output_handler.statistics.as_ref().unwrap();
+    assert_eq!(statistics.get_num_violations(), 2);
+    assert_eq!(statistics.get_num_trigger(0), 1);
+}
+
+#[test]
+fn runtime_error_counts() {
+    let spec = r#"
+input a: Int64
+input b: Int64
+
+output q := a / b
+trigger q > 2 "q is {q}"
+    "#;
+
+    let data = r#"a,b,time
+6,3,0.0
+6,0,0.5
+9,3,1.0
+1,0,1.5"#;
+
+    let output_handler = run_with_runtime_errors(spec, data, RuntimeErrorPolicy::Continue)
+        .unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    let statistics = output_handler.statistics.as_ref().unwrap();
+    assert_eq!(statistics.get_num_runtime_errors(), 2);
+    assert_eq!(statistics.get_num_trigger(0), 1);
+}
+
+#[test]
+fn runtime_error_abort() {
+    let spec = r#"
+input a: Int64
+input b: Int64
+
+output q := a / b
+trigger q > 2 "q is {q}"
+    "#;
+
+    let data = r#"a,b,time
+6,3,0.0
+6,0,0.5
+9,3,1.0"#;
+
+    let err = run_with_runtime_errors(spec, data, RuntimeErrorPolicy::Abort).expect_err("evaluation should abort");
+    assert_eq!(err.to_string(), "runtime error in stream `q` at 0.500000000s: division by zero");
+}
+
+#[test]
+fn suppressed_trigger_counts() {
+    let spec = r#"
+input w_spd: Float64
+
+trigger w_spd > 14.0 "too windy" with rising
+trigger w_spd > 14.0 with limit: 1 per 1s
+    "#;
+
+    let data = r#"w_spd,time
+15.0,0.0
+16.0,0.5
+13.0,1.0
+17.0,1.5
+18.0,2.0"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    let statistics = output_handler.statistics.as_ref().unwrap();
+    assert_eq!(statistics.get_num_suppressed(0), 2);
+    assert_eq!(statistics.get_num_suppressed(1), 2);
+    assert_eq!(statistics.get_num_trigger(0), 2);
+    assert_eq!(statistics.get_num_trigger(1), 2);
+}
+
+#[test]
+fn fail_on_trigger() {
+    let spec = r#"
+input a: Int64
+
+trigger too_high := a > 5 "a is {a}"
+trigger too_low := a < 0
+    "#;
+
+    let data = r#"a,time
+1,0.0
+6,0.5
+7,1.0"#;
+
+    let fail_on = |fail_on| TriggerPolicy { fail_on, ..TriggerPolicy::default() };
+    assert!(run_with_policy(spec, data, fail_on(TriggerSelection::None)).is_ok());
+    assert!(run_with_policy(spec, data, fail_on(TriggerSelection::Named(vec!["too_low".into()]))).is_ok());
+    let err = run_with_policy(spec, data, fail_on(TriggerSelection::All)).err().expect("trigger was raised");
+    let failure = err.downcast_ref::<TriggerFailure>().expect("the error is a trigger failure");
+    assert_eq!(failure.name.as_deref(), Some("too_high"));
+    assert_eq!(failure.message, "a is 6");
+    assert_eq!(failure.to_string(), "trigger `too_high` was raised at 0.500000000s: a is 6");
+}
+
+#[test]
+fn trigger_action_names() {
+    let names = ["too_high", "too_low"];
+    let parse = |action: &str| Config::parse_trigger_action(action, &names);
+    assert_eq!(
+        parse("too_high=notify-send alarm"),
+        Ok((TriggerSelection::Named(vec!["too_high".into()]), "notify-send alarm".into()))
+    );
+    assert_eq!(parse("notify-send alarm"), Ok((TriggerSelection::All, "notify-send alarm".into())));
+    assert_eq!(
+        parse("curl 'localhost/alarm?level=high'"),
+        Ok((TriggerSelection::All, "curl 'localhost/alarm?level=high'".into()))
+    );
+    // environment assignments of the command are skipped when looking for the trigger name
+    assert_eq!(parse("FOO=1 notify-send alarm"), Ok((TriggerSelection::All, "FOO=1 notify-send alarm".into())));
+    assert_eq!(
+        parse("FOO=1 too_low=notify-send alarm"),
+        Ok((TriggerSelection::Named(vec!["too_low".into()]), "FOO=1 notify-send alarm".into()))
+    );
+    // a typo in the name must not run the whole action as a command for all triggers
+    assert_eq!(parse("to_high=notify-send"), Err("to_high".into()));
+    assert_eq!(parse("FOO=1 to_high=alarm"), Err("to_high".into()));
+}
+
+#[cfg(unix)]
+#[test]
+fn trigger_actions() {
+    let spec = r#"
+input a: Int64
+
+trigger too_high: critical := a > 5 "a is {a}"
+trigger a < 0
+    "#;
+
+    let data = r#"a,time
+1,0.0
+6,0.5
+-1,1.0
+7,1.5"#;
+
+    let log = NamedTempFile::new().expect("failed to create temporary file");
+    let command = format!(
+        "echo \"$RTLOLA_TRIGGER_INDEX $RTLOLA_TRIGGER_NAME $RTLOLA_TRIGGER_SEVERITY $RTLOLA_TRIGGER_MESSAGE\" >> {}",
+        log.path().display()
+    );
+    let policy = TriggerPolicy {
+        fail_on: TriggerSelection::None,
+        actions: vec![(TriggerSelection::Named(vec!["too_high".into()]), command)],
+    };
+    run_with_policy(spec, data, policy).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    let mut lines: Vec<String> =
+        std::fs::read_to_string(log.path()).expect("reading log failed").lines().map(String::from).collect();
+    lines.sort();
+    assert_eq!(lines, vec!["0 too_high critical a is 6", "0 too_high critical a is 7"]);
+}
+
 #[test]
 fn regex_simple() {
     let spec = r#"
@@ -328,6 +550,157 @@ trigger max == 2
     assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
 }
 
+#[test]
+fn unit_conversion() {
+    let spec = r#"
+input altitude: Float64<ft>
+input ground: Float64<m>
+input speed: Float64<kn>
+
+output above_ground: Float64<m> := altitude - ground
+output speed_ms: Float64<m/s> := speed
+
+trigger above_ground < 100.0
+trigger speed_ms > 50.0
+    "#;
+
+    let data = r#"altitude,ground,speed,time
+1000.0,200.0,90.0,0
+1000.0,250.0,100.0,1
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    // 1000ft are 304.8m, 90kn are 46.3m/s, and 100kn are 51.4m/s
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
+
+#[test]
+fn geofence() {
+    let spec = r#"
+import geo
+
+constant airfield: String := "49.20 7.10, 49.20 7.12, 49.22 7.12, 49.22 7.10"
+input lat: Float64
+input lon: Float64
+
+output dist := distance(lat, lon, lat.offset(by: -1).defaults(to: lat), lon.offset(by: -1).defaults(to: lon))
+output local := enu(lat, lon, 0.0, 49.20, 7.10, 0.0)
+
+trigger !within(lat, lon, polygon: airfield) "left the airfield"
+trigger dist > 1000.0 "implausible jump in position"
+trigger local.north > 2000.0 "far north of the origin"
+    "#;
+
+    let data = r#"lat,lon,time
+49.21,7.11,0
+49.211,7.111,1
+49.23,7.11,2
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(2), 1);
+}
+
+#[test]
+fn signal_operators() {
+    let spec = r#"
+input x: Float64
+
+output d := x.derivative()
+output e := x.ema(alpha: 0.5)
+output l := x.lowpass(cutoff: 1Hz)
+
+trigger d > 3.0 "x rises fast"
+trigger e > 1.0 "average above one"
+trigger l > 0.8 ∧ l < x "filter lags behind"
+    "#;
+
+    // the derivative is 0, 1, and 4; the average is 0, 0.5, and 1.75; the filter yields 0, 0.86, and 2.49
+    let data = r#"x,time
+0.0,0.0
+1.0,1.0
+3.0,1.5
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(2), 2);
+}
+
+#[test]
+fn signal_operators_with_activation() {
+    let spec = r#"
+input a: Float64
+input b: Float64
+
+output d @ a ∧ b := a.derivative()
+
+trigger d > 2.2 "a rises fast between evaluations"
+    "#;
+
+    // d is evaluated at 0 and 2, so the derivative is (5 - 0) / 2 and ignores the value of a at 1
+    let data = r#"a,b,time
+0.0,0.0,0.0
+1.0,#,1.0
+5.0,0.0,2.0
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+}
+
+#[test]
+fn ip_addresses() {
+    let spec = r#"
+constant lan: IPv4Net := 192.168.0.0/16
+input src: IPv4Addr
+input dst: IPv6Addr
+
+trigger src.in_subnet(lan) "source in the local network"
+trigger src.is_private() ∧ ¬src.in_subnet(10.0.0.0/8) "private source outside of 10.0.0.0/8"
+trigger dst.in_subnet(fe80::/10) ∨ dst.is_loopback() "link-local or loopback destination"
+trigger dst.to_string() == "2001:db8::1" "documentation destination"
+    "#;
+
+    let data = r#"src,dst,time
+192.168.1.5,fe80::1,0.0
+10.1.2.3,::1,0.1
+8.8.8.8,2001:db8:0::1,0.2
+172.16.0.1,2001:db8::2,0.3
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 2);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(2), 2);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(3), 1);
+}
+
+#[test]
+fn set_of_optional_values() {
+    let spec = r#"
+input x: UInt8
+
+output s: Set<UInt8?> := s.offset(by: -1).defaults(to: Set()).insert(x.offset(by: -1))
+
+trigger s.len() == 3 "seen three values"
+    "#;
+
+    // the first insertion adds an absent value, which is ordered before all present values
+    let data = r#"x,time
+1,0.0
+2,0.1
+1,0.2
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+}
+
 #[test]
 fn bytes_at() {
     let spec = r#"
@@ -350,6 +723,29 @@ trigger x == 50 // utf-8 character value of "2"
     assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
 }
 
+#[test]
+fn bytes_methods() {
+    let spec = r#"
+input payload: Bytes
+
+output request := payload.starts_with("GET ") || payload.starts_with("POST ")
+output version := payload.find("HTTP/").defaults(to: 0)
+
+trigger request && version > 10
+trigger payload.len() < 4
+    "#;
+
+    let data = r#"payload,time
+GET /index.html HTTP/1.1,0
+POST / HTTP/1.1,0.1
+GET,0.2
+"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
+
 #[test]
 fn rtlola_stream_but_eventbased() {
     let spec = r#"
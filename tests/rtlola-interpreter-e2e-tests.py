@@ -139,7 +139,7 @@ with open("e2e-results.xml", 'w') as results_file:
                         for line in lines:
                             if line == "":
                                 continue
-                            m = re.match(r'((?P<timeinfo>.*): )?Trigger: (?P<trig_msg>.*)', line)
+                            m = re.match(r'((?P<timeinfo>.*): )?Trigger( (?P<name>\S+))? \((?P<severity>\w+)\): (?P<trig_msg>.*)', line)
                             if m:
                                 timeinfo = m.group('timeinfo')
                                 trig_msg = m.group('trig_msg')